@@ -0,0 +1,6 @@
+#![no_main]
+
+use cex_exchanges_fuzz::fuzz_trading_pair;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (&str, char)| fuzz_trading_pair(input.0, input.1));
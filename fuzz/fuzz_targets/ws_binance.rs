@@ -0,0 +1,7 @@
+#![no_main]
+
+use cex_exchanges::binance::Binance;
+use cex_exchanges_fuzz::fuzz_ws_message;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| fuzz_ws_message::<Binance>(data));
@@ -0,0 +1,81 @@
+//! shared harnesses for the cargo-fuzz targets in `fuzz_targets/`, every
+//! target only checks that arbitrary input can never panic a stream
+//!
+//! run with `cargo +nightly fuzz run ws_binance`, the frames in
+//! `tests/fixtures/messages` make a good seed corpus
+
+use cex_exchanges::{
+    clients::ws::{CriticalWsMessage, WsError},
+    normalized::{
+        types::RawTradingPair,
+        ws::CombinedWsMessage
+    },
+    CexExchange, Exchange
+};
+
+mod pairs;
+pub use pairs::*;
+
+/// deserializes & normalizes a ws frame the same way the live stream does
+pub fn fuzz_ws_message<E: Exchange>(data: &[u8]) {
+    let Ok(frame) = std::str::from_utf8(data) else { return };
+
+    match serde_json::from_str::<E::WsMessage>(frame) {
+        Ok(mut msg) => {
+            msg.make_critical(frame.to_string());
+            let msg: CombinedWsMessage = msg.into();
+            let normalized = msg.clone().normalize();
+            let _ = msg == normalized;
+        }
+        Err(e) => {
+            let _ = WsError::from(e)
+                .normalized_with_exchange(E::EXCHANGE, Some(frame.to_string()))
+                .normalize();
+        }
+    }
+}
+
+/// parses a raw pair for every exchange & converts it back
+pub fn fuzz_trading_pair(pair: &str, delimiter: char) {
+    let mut raw_pairs = vec![RawTradingPair::new_no_delim(pair)];
+    if delimiter != '\0' {
+        raw_pairs.push(RawTradingPair::new_raw(pair, delimiter));
+    }
+
+    for raw in raw_pairs {
+        for exchange in CexExchange::vec_all() {
+            let normalized = raw.get_normalized_pair(exchange);
+            try_into_every_exchange(&normalized);
+            let _ = RawTradingPair::from(normalized).get_normalized_pair(exchange);
+        }
+    }
+
+    let value = serde_json::Value::String(pair.to_string());
+    #[cfg(feature = "us")]
+    {
+        use cex_exchanges::{coinbase::CoinbaseTradingPair, okex::OkexTradingPair};
+
+        if let Ok(p) = serde_json::from_value::<CoinbaseTradingPair>(value.clone()) {
+            try_into_every_exchange(&p.normalize());
+        }
+        if let Ok(p) = serde_json::from_value::<OkexTradingPair>(value.clone()) {
+            try_into_every_exchange(&p.normalize());
+        }
+        let _ = CoinbaseTradingPair::parse_for_bad_pair(pair);
+        let _ = OkexTradingPair::parse_for_bad_pair(pair);
+    }
+    #[cfg(feature = "non-us")]
+    {
+        use cex_exchanges::{binance::BinanceTradingPair, bybit::BybitTradingPair, kucoin::KucoinTradingPair};
+
+        if let Ok(p) = serde_json::from_value::<BinanceTradingPair>(value.clone()) {
+            try_into_every_exchange(&p.normalize());
+        }
+        if let Ok(p) = serde_json::from_value::<BybitTradingPair>(value.clone()) {
+            try_into_every_exchange(&p.normalize());
+        }
+        if let Ok(p) = serde_json::from_value::<KucoinTradingPair>(value) {
+            try_into_every_exchange(&p.normalize());
+        }
+    }
+}
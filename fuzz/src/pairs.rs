@@ -0,0 +1,23 @@
+//! shared w/ the property tests in `tests/pairs.rs`
+
+use cex_exchanges::normalized::types::{NormalizedTradingPair, RawTradingPair};
+
+/// denormalizes the pair for every exchange, only checking that it doesn't
+/// panic
+pub fn try_into_every_exchange(pair: &NormalizedTradingPair) {
+    #[cfg(feature = "us")]
+    {
+        let _ = cex_exchanges::coinbase::CoinbaseTradingPair::try_from(pair.clone());
+        let _ = cex_exchanges::okex::OkexTradingPair::try_from(pair.clone());
+    }
+    #[cfg(feature = "non-us")]
+    {
+        let _ = cex_exchanges::binance::BinanceTradingPair::try_from(pair.clone());
+        let _ = cex_exchanges::kucoin::KucoinTradingPair::try_from(pair.clone());
+        let _ = cex_exchanges::bybit::BybitTradingPair::try_from(pair.clone());
+    }
+
+    let _ = pair.extra_data();
+    let _ = pair.make_pair();
+    let _ = RawTradingPair::from(pair.clone());
+}
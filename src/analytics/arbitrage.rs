@@ -0,0 +1,285 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration
+};
+
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use super::{NbboAggregator, NbboSide};
+use crate::{
+    exchanges::normalized::{
+        types::{CanonicalPair, NormalizedQuote, NormalizedTradingPair, NumberExt},
+        ws::{CombinedWsMessage, NormalizedWsDataTypes}
+    },
+    CexExchange
+};
+
+/// buying a pair on one exchange & selling it on another, profitable after the
+/// taker fees of both
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArbitrageOpportunity {
+    pub pair:       CanonicalPair,
+    /// the ask that is bought
+    pub buy:        NbboSide,
+    /// the bid that is sold to
+    pub sell:       NbboSide,
+    /// the tradable amount, the smaller of the ask & bid amounts
+    pub amount:     f64,
+    /// fee adjusted bid - fee adjusted ask
+    pub spread:     f64,
+    /// the spread in bps of the fee adjusted ask
+    pub spread_bps: f64,
+    /// recieve time of the message that updated the opportunity
+    pub time:       DateTime<Utc>
+}
+
+impl ArbitrageOpportunity {
+    /// profit in the quote asset of trading the full amount
+    pub fn profit(&self) -> f64 {
+        self.spread * self.amount
+    }
+}
+
+/// tracks the quotes of a set of pairs across the exchanges & emits the best
+/// [ArbitrageOpportunity] of a pair when its spread is above the threshold
+///
+/// an opportunity is emitted when it opens or changes, not on every quote
+pub struct ArbitrageMonitor {
+    pairs:          HashSet<CanonicalPair>,
+    quotes:         NbboAggregator,
+    /// (exchange, pair) -> minimum order amount in the base asset
+    min_sizes:      HashMap<(CexExchange, CanonicalPair), f64>,
+    min_spread_bps: f64,
+    /// the last emitted opportunity of every pair that is still open
+    last:           HashMap<CanonicalPair, ArbitrageOpportunity>
+}
+
+impl ArbitrageMonitor {
+    /// monitors the pairs for any spread above 0 after fees, the exchanges'
+    /// pairs are mapped with [default_pair_mapper](super::default_pair_mapper)
+    pub fn new<I>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = CanonicalPair>
+    {
+        Self {
+            pairs:          pairs.into_iter().collect(),
+            quotes:         NbboAggregator::new(),
+            min_sizes:      HashMap::new(),
+            min_spread_bps: 0.0,
+            last:           HashMap::new()
+        }
+    }
+
+    /// taker fee of the exchange as a fraction, ex: 0.001 for 10bps
+    pub fn with_taker_fee(mut self, exchange: CexExchange, fee: f64) -> Self {
+        self.quotes = self.quotes.with_taker_fee(exchange, fee);
+        self
+    }
+
+    /// minimum order amount (in the base asset) of the pair on the exchange,
+    /// opportunities with a smaller tradable amount are skipped
+    pub fn with_min_size(mut self, exchange: CexExchange, pair: CanonicalPair, min_size: f64) -> Self {
+        self.min_sizes.insert((exchange, pair), min_size);
+        self
+    }
+
+    /// only emits opportunities with a fee adjusted spread above `min_spread_bps`
+    pub fn with_min_spread_bps(mut self, min_spread_bps: f64) -> Self {
+        self.min_spread_bps = min_spread_bps;
+        self
+    }
+
+    /// ignores quotes recieved more than `max_staleness` before the latest
+    /// message
+    pub fn with_max_staleness(mut self, max_staleness: Duration) -> Self {
+        self.quotes = self.quotes.with_max_staleness(max_staleness);
+        self
+    }
+
+    /// maps the exchanges' pairs to the monitored pairs, ex: with
+    /// [InstrumentRegistry::spot_pair](crate::normalized::registry::InstrumentRegistry::spot_pair)
+    pub fn with_pair_mapper<F>(mut self, pair_mapper: F) -> Self
+    where
+        F: Fn(&NormalizedTradingPair) -> Option<CanonicalPair> + Send + Sync + 'static
+    {
+        self.quotes = self.quotes.with_pair_mapper(pair_mapper);
+        self
+    }
+
+    /// updates the quotes of the message's exchange, returning the
+    /// opportunities that opened or changed
+    pub fn update(&mut self, msg: CombinedWsMessage) -> Vec<ArbitrageOpportunity> {
+        let received_at = msg.receive_time().map_or_else(Utc::now, |time| time.wall);
+        self.update_normalized(msg.normalize(), received_at)
+    }
+
+    /// [Self::update] with already normalized data, the staleness of the quotes
+    /// is measured from `received_at`
+    pub fn update_normalized(&mut self, data: NormalizedWsDataTypes, received_at: DateTime<Utc>) -> Vec<ArbitrageOpportunity> {
+        let mut pairs = self.quotes.apply(data, received_at);
+        pairs.retain(|pair| self.pairs.contains(pair));
+
+        pairs
+            .into_iter()
+            .filter_map(|pair| {
+                let Some(opportunity) = self.opportunity(&pair, received_at) else {
+                    self.last.remove(&pair);
+                    return None
+                };
+
+                if self.last.get(&pair).is_some_and(|last| {
+                    last.buy == opportunity.buy && last.sell == opportunity.sell && last.amount == opportunity.amount
+                }) {
+                    return None
+                }
+
+                self.last.insert(pair, opportunity.clone());
+                Some(opportunity)
+            })
+            .collect()
+    }
+
+    /// the most profitable opportunity of the pair above the threshold w/o the
+    /// quotes that are stale at `now`
+    pub fn opportunity(&self, pair: &CanonicalPair, now: DateTime<Utc>) -> Option<ArbitrageOpportunity> {
+        let quotes = self.quotes.venue_quotes(pair, now);
+
+        let mut best: Option<ArbitrageOpportunity> = None;
+        quotes.iter().for_each(|buy| {
+            quotes
+                .iter()
+                .filter(|sell| sell.exchange != buy.exchange)
+                .filter_map(|sell| self.evaluate(pair, buy, sell, now))
+                .for_each(|opportunity| {
+                    if best
+                        .as_ref()
+                        .is_none_or(|best| opportunity.profit() > best.profit())
+                    {
+                        best = Some(opportunity)
+                    }
+                })
+        });
+
+        best
+    }
+
+    /// consolidates the quotes of the stream into the opened or changed
+    /// opportunities
+    pub fn into_stream<S>(mut self, stream: S) -> impl Stream<Item = ArbitrageOpportunity>
+    where
+        S: Stream<Item = CombinedWsMessage>
+    {
+        stream.flat_map(move |msg| stream::iter(self.update(msg)))
+    }
+
+    fn evaluate(&self, pair: &CanonicalPair, buy: &NormalizedQuote, sell: &NormalizedQuote, now: DateTime<Utc>) -> Option<ArbitrageOpportunity> {
+        let (ask_price, ask_amount) = (buy.ask_price.to_f64(), buy.ask_amount.to_f64());
+        let (bid_price, bid_amount) = (sell.bid_price.to_f64(), sell.bid_amount.to_f64());
+        if ask_price <= 0.0 || ask_amount <= 0.0 || bid_price <= 0.0 || bid_amount <= 0.0 {
+            return None
+        }
+
+        let amount = ask_amount.min(bid_amount);
+        if amount < self.min_size(buy.exchange, pair) || amount < self.min_size(sell.exchange, pair) {
+            return None
+        }
+
+        let buy_price = ask_price * (1.0 + self.quotes.taker_fee(buy.exchange));
+        let sell_price = bid_price * (1.0 - self.quotes.taker_fee(sell.exchange));
+        let spread = sell_price - buy_price;
+        let spread_bps = spread / buy_price * 10_000.0;
+        if spread <= 0.0 || spread_bps <= self.min_spread_bps {
+            return None
+        }
+
+        Some(ArbitrageOpportunity {
+            pair: pair.clone(),
+            buy: NbboSide { exchange: buy.exchange, price: ask_price, adjusted_price: buy_price, amount: ask_amount },
+            sell: NbboSide { exchange: sell.exchange, price: bid_price, adjusted_price: sell_price, amount: bid_amount },
+            amount,
+            spread,
+            spread_bps,
+            time: now
+        })
+    }
+
+    fn min_size(&self, exchange: CexExchange, pair: &CanonicalPair) -> f64 {
+        self.min_sizes
+            .get(&(exchange, pair.clone()))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "us")]
+mod tests {
+    use super::*;
+    use crate::normalized::types::{num, TimeOrUpdateId};
+
+    fn quote(exchange: CexExchange, base: &str, bid: (f64, f64), ask: (f64, f64)) -> NormalizedWsDataTypes {
+        NormalizedWsDataTypes::Quote(NormalizedQuote {
+            exchange,
+            pair: NormalizedTradingPair::new_base_quote(exchange, base, "USD", Some('-'), None),
+            bid_price: num(bid.0),
+            bid_amount: num(bid.1),
+            ask_price: num(ask.0),
+            ask_amount: num(ask.1),
+            orderbook_ids_time: TimeOrUpdateId::new()
+        })
+    }
+
+    #[test]
+    fn test_arbitrage_opportunities() {
+        let btc_usd = CanonicalPair::new("BTC", "USD");
+        let mut monitor = ArbitrageMonitor::new([btc_usd.clone()])
+            .with_taker_fee(CexExchange::Coinbase, 0.001)
+            .with_taker_fee(CexExchange::Okex, 0.001)
+            .with_min_spread_bps(5.0);
+        let now = Utc::now();
+
+        assert!(monitor
+            .update_normalized(quote(CexExchange::Coinbase, "BTC", (100.0, 1.0), (100.1, 2.0)), now)
+            .is_empty());
+
+        // a 20bps raw spread isn't profitable after fees
+        assert!(monitor
+            .update_normalized(quote(CexExchange::Okex, "BTC", (100.3003, 0.5), (100.4, 1.0)), now)
+            .is_empty());
+
+        let opportunities = monitor.update_normalized(quote(CexExchange::Okex, "BTC", (100.5, 0.5), (100.6, 1.0)), now);
+        assert_eq!(opportunities.len(), 1);
+        let opportunity = &opportunities[0];
+        assert_eq!((opportunity.buy.exchange, opportunity.sell.exchange), (CexExchange::Coinbase, CexExchange::Okex));
+        assert_eq!(opportunity.amount, 0.5);
+        assert!((opportunity.spread - (100.5 * 0.999 - 100.1 * 1.001)).abs() < 1e-9);
+        assert!(opportunity.spread_bps > 5.0);
+
+        // unchanged opportunities & unmonitored pairs aren't emitted
+        assert!(monitor
+            .update_normalized(quote(CexExchange::Okex, "BTC", (100.5, 0.5), (100.7, 1.0)), now)
+            .is_empty());
+        assert!(monitor
+            .update_normalized(quote(CexExchange::Okex, "ETH", (10.0, 1.0), (10.1, 1.0)), now)
+            .is_empty());
+        assert!(monitor.opportunity(&btc_usd, now).is_some());
+    }
+
+    #[test]
+    fn test_arbitrage_min_size() {
+        let btc_usd = CanonicalPair::new("BTC", "USD");
+        let mut monitor = ArbitrageMonitor::new([btc_usd.clone()]).with_min_size(CexExchange::Okex, btc_usd.clone(), 1.0);
+        let now = Utc::now();
+
+        monitor.update_normalized(quote(CexExchange::Coinbase, "BTC", (100.0, 1.0), (100.1, 2.0)), now);
+        assert!(monitor
+            .update_normalized(quote(CexExchange::Okex, "BTC", (101.0, 0.5), (101.1, 1.0)), now)
+            .is_empty());
+
+        let opportunities = monitor.update_normalized(quote(CexExchange::Okex, "BTC", (101.0, 1.5), (101.1, 1.0)), now);
+        assert_eq!(opportunities[0].amount, 1.5);
+        assert!((opportunities[0].profit() - 0.9 * 1.5).abs() < 1e-9);
+    }
+}
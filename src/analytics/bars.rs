@@ -0,0 +1,237 @@
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::exchanges::normalized::{
+    types::{NormalizedTrade, NormalizedTradingPair, NumberExt},
+    ws::{CombinedWsMessage, NormalizedWsDataTypes}
+};
+
+/// when a [TradeBar] closes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BarKind {
+    /// fixed intervals aligned to the unix epoch, ex: every minute
+    Time(Duration),
+    /// every n trades
+    Tick(usize),
+    /// once the traded amount (in the base asset) reaches the threshold
+    Volume(f64),
+    /// once the traded notional (in the quote asset) reaches the threshold
+    Dollar(f64)
+}
+
+/// ohlcv of the trades of a pair on an exchange
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeBar {
+    pub pair:          NormalizedTradingPair,
+    /// start of the interval for time bars, else the time of the first trade
+    pub start:         DateTime<Utc>,
+    /// end of the interval for time bars, else the time of the last trade
+    pub end:           DateTime<Utc>,
+    pub open:          f64,
+    pub high:          f64,
+    pub low:           f64,
+    pub close:         f64,
+    /// traded amount in the base asset
+    pub volume:        f64,
+    /// traded notional in the quote asset
+    pub dollar_volume: f64,
+    pub trades:        usize
+}
+
+impl TradeBar {
+    fn new(trade: &NormalizedTrade, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            pair: trade.pair.clone(),
+            start,
+            end,
+            open: trade.price.to_f64(),
+            high: trade.price.to_f64(),
+            low: trade.price.to_f64(),
+            close: trade.price.to_f64(),
+            volume: 0.0,
+            dollar_volume: 0.0,
+            trades: 0
+        }
+    }
+
+    fn add(&mut self, trade: &NormalizedTrade) {
+        let (price, amount) = (trade.price.to_f64(), trade.amount.to_f64());
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.volume += amount;
+        self.dollar_volume += price * amount;
+        self.trades += 1;
+    }
+
+    pub fn vwap(&self) -> f64 {
+        if self.volume > 0.0 {
+            self.dollar_volume / self.volume
+        } else {
+            self.close
+        }
+    }
+}
+
+/// aggregates the trades of every pair into [TradeBar]s
+///
+/// bars are closed by the trades themselves (using the trades' time), so a
+/// time bar is only emitted once a trade of the next interval arrives &
+/// threshold bars aren't split, the trade that crosses the threshold closes the
+/// bar
+#[derive(Debug, Clone)]
+pub struct BarAggregator {
+    kind: BarKind,
+    /// the open bar of every pair
+    open: HashMap<NormalizedTradingPair, TradeBar>
+}
+
+impl BarAggregator {
+    pub fn new(kind: BarKind) -> Self {
+        Self { kind, open: HashMap::new() }
+    }
+
+    /// adds the trades of the message, returning the bars that closed
+    pub fn update(&mut self, msg: CombinedWsMessage) -> Vec<TradeBar> {
+        self.update_normalized(msg.normalize())
+    }
+
+    /// [Self::update] with already normalized data
+    pub fn update_normalized(&mut self, data: NormalizedWsDataTypes) -> Vec<TradeBar> {
+        match data {
+            NormalizedWsDataTypes::Trade(trade) => self.update_trade(&trade).into_iter().collect(),
+            NormalizedWsDataTypes::Trades(trades) => trades
+                .iter()
+                .filter_map(|trade| self.update_trade(trade))
+                .collect(),
+            _ => Vec::new()
+        }
+    }
+
+    /// adds the trade, returning the bar of its pair if it closed
+    pub fn update_trade(&mut self, trade: &NormalizedTrade) -> Option<TradeBar> {
+        match self.kind {
+            BarKind::Time(interval) => {
+                let interval = TimeDelta::from_std(interval)
+                    .unwrap_or(TimeDelta::MAX)
+                    .max(TimeDelta::milliseconds(1));
+                let start = align(trade.time, interval);
+
+                // a late trade of a closed interval is added to the open bar
+                let closed = match self.open.get(&trade.pair) {
+                    Some(bar) if start > bar.start => self.open.remove(&trade.pair),
+                    _ => None
+                };
+                self.open
+                    .entry(trade.pair.clone())
+                    .or_insert_with(|| TradeBar::new(trade, start, start + interval))
+                    .add(trade);
+
+                closed
+            }
+            kind => {
+                let bar = self
+                    .open
+                    .entry(trade.pair.clone())
+                    .or_insert_with(|| TradeBar::new(trade, trade.time, trade.time));
+                bar.add(trade);
+                bar.end = trade.time;
+
+                let is_closed = match kind {
+                    BarKind::Tick(ticks) => bar.trades >= ticks,
+                    BarKind::Volume(volume) => bar.volume >= volume,
+                    BarKind::Dollar(dollar_volume) => bar.dollar_volume >= dollar_volume,
+                    BarKind::Time(_) => unreachable!()
+                };
+                is_closed.then(|| self.open.remove(&trade.pair)).flatten()
+            }
+        }
+    }
+
+    /// closes & returns the open bars, ex: at the end of a stream
+    pub fn flush(&mut self) -> Vec<TradeBar> {
+        let mut bars = self.open.drain().map(|(_, bar)| bar).collect::<Vec<_>>();
+        bars.sort_by(|a, b| a.pair.cmp(&b.pair));
+
+        bars
+    }
+
+    /// aggregates the trades of the stream into the closed bars
+    pub fn into_stream<S>(mut self, stream: S) -> impl Stream<Item = TradeBar>
+    where
+        S: Stream<Item = CombinedWsMessage>
+    {
+        stream.flat_map(move |msg| stream::iter(self.update(msg)))
+    }
+}
+
+/// the start of the interval of the time
+fn align(time: DateTime<Utc>, interval: TimeDelta) -> DateTime<Utc> {
+    let interval_ms = interval.num_milliseconds();
+    let start_ms = time.timestamp_millis().div_euclid(interval_ms) * interval_ms;
+
+    DateTime::from_timestamp_millis(start_ms).unwrap_or(time)
+}
+
+#[cfg(test)]
+#[cfg(feature = "us")]
+mod tests {
+    use super::*;
+    use crate::{
+        normalized::types::{num, TradeSide},
+        CexExchange
+    };
+
+    fn trade(secs: i64, price: f64, amount: f64) -> NormalizedTrade {
+        NormalizedTrade {
+            exchange: CexExchange::Coinbase,
+            pair:     NormalizedTradingPair::new_base_quote(CexExchange::Coinbase, "BTC", "USD", Some('-'), None),
+            time:     DateTime::from_timestamp(1_699_999_980 + secs, 0).unwrap(),
+            side:     TradeSide::Buy,
+            raw_side: "buy".to_string(),
+            price:    num(price),
+            amount:   num(amount),
+            trade_id: None
+        }
+    }
+
+    #[test]
+    fn test_time_bars() {
+        let mut aggregator = BarAggregator::new(BarKind::Time(Duration::from_secs(60)));
+
+        let trades = vec![trade(0, 100.0, 1.0), trade(10, 102.0, 1.0), trade(30, 99.0, 2.0)];
+        assert!(aggregator
+            .update_normalized(NormalizedWsDataTypes::Trades(trades))
+            .is_empty());
+
+        let bar = aggregator.update_trade(&trade(70, 101.0, 1.0)).unwrap();
+        assert_eq!((bar.start.timestamp() % 60, bar.end - bar.start), (0, TimeDelta::seconds(60)));
+        assert_eq!((bar.open, bar.high, bar.low, bar.close), (100.0, 102.0, 99.0, 99.0));
+        assert_eq!((bar.volume, bar.trades), (4.0, 3));
+        assert_eq!(bar.vwap(), 400.0 / 4.0);
+
+        // a late trade is added to the open bar
+        assert_eq!(aggregator.update_trade(&trade(50, 98.0, 1.0)), None);
+        let open = aggregator.flush();
+        assert_eq!((open[0].low, open[0].close, open[0].trades), (98.0, 98.0, 2));
+        assert!(aggregator.flush().is_empty());
+    }
+
+    #[test]
+    fn test_threshold_bars() {
+        let trades = || vec![trade(0, 100.0, 1.0), trade(1, 100.0, 1.0), trade(2, 50.0, 2.0), trade(3, 100.0, 0.5)];
+
+        let tick_bars = BarAggregator::new(BarKind::Tick(2)).update_normalized(NormalizedWsDataTypes::Trades(trades()));
+        assert_eq!(tick_bars.iter().map(|bar| bar.trades).collect::<Vec<_>>(), vec![2, 2]);
+
+        let volume_bars = BarAggregator::new(BarKind::Volume(3.0)).update_normalized(NormalizedWsDataTypes::Trades(trades()));
+        assert_eq!(volume_bars.len(), 1);
+        assert_eq!((volume_bars[0].volume, volume_bars[0].end), (4.0, trade(2, 0.0, 0.0).time));
+
+        let dollar_bars = BarAggregator::new(BarKind::Dollar(150.0)).update_normalized(NormalizedWsDataTypes::Trades(trades()));
+        assert_eq!(dollar_bars.iter().map(|bar| bar.dollar_volume).collect::<Vec<_>>(), vec![200.0, 150.0]);
+    }
+}
@@ -0,0 +1,14 @@
+mod arbitrage;
+pub use arbitrage::*;
+
+mod bars;
+pub use bars::*;
+
+mod nbbo;
+pub use nbbo::*;
+
+mod rolling;
+pub use rolling::*;
+
+mod stream;
+pub use stream::*;
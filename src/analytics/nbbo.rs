@@ -0,0 +1,446 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    time::Duration
+};
+
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    exchanges::normalized::{
+        types::{CanonicalPair, NormalizedQuote, NormalizedTradingPair, NumberExt},
+        ws::{CombinedWsMessage, NormalizedWsDataTypes}
+    },
+    CexExchange
+};
+
+/// maps an exchange's pair to the pair its quotes are consolidated on, `None`
+/// skips the quote
+pub type PairMapper = Box<dyn Fn(&NormalizedTradingPair) -> Option<CanonicalPair> + Send + Sync>;
+
+/// quote assets of the concatenated pairs, ex: binance & bybit's 'BTCUSDT'.
+/// longer quotes come first so 'BTCFDUSD' isn't split into 'BTCFD' & 'USD'
+const QUOTE_ASSETS: [&str; 14] = ["FDUSD", "USDT", "USDC", "TUSD", "BUSD", "USDE", "EURI", "USD", "EUR", "TRY", "DAI", "BTC", "ETH", "BNB"];
+
+/// [NormalizedTradingPair::canonical_pair], splitting the concatenated pairs
+/// w/o a base & quote (binance & bybit) on a known quote asset
+///
+/// pairs w/ other quote assets are skipped, use a registry mapper for them
+/// (ex: [InstrumentRegistry::spot_pair](crate::normalized::registry::InstrumentRegistry::spot_pair))
+pub fn default_pair_mapper(pair: &NormalizedTradingPair) -> Option<CanonicalPair> {
+    if pair.base_quote().is_some() {
+        return pair.canonical_pair()
+    }
+
+    let symbol = pair.pair().as_ref()?;
+    if !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None
+    }
+
+    QUOTE_ASSETS.iter().find_map(|quote| {
+        symbol
+            .strip_suffix(quote)
+            .filter(|base| !base.is_empty())
+            .map(|base| CanonicalPair::new(base, quote))
+    })
+}
+
+/// the best bid & ask of a pair across the exchanges
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Nbbo {
+    pub pair: CanonicalPair,
+    /// highest fee adjusted bid
+    pub bid:  Option<NbboSide>,
+    /// lowest fee adjusted ask
+    pub ask:  Option<NbboSide>,
+    /// recieve time of the message that updated the nbbo
+    pub time: DateTime<Utc>
+}
+
+impl Nbbo {
+    /// fee adjusted ask - bid, negative if the nbbo is crossed
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.ask.as_ref()?.adjusted_price - self.bid.as_ref()?.adjusted_price)
+    }
+
+    /// the best bid is above the best ask after fees, buying on the ask's
+    /// exchange & selling on the bid's is profitable
+    pub fn is_crossed(&self) -> bool {
+        self.spread().is_some_and(|spread| spread < 0.0)
+    }
+
+    /// mid of the raw (not fee adjusted) prices
+    pub fn mid_price(&self) -> Option<f64> {
+        Some((self.ask.as_ref()?.price + self.bid.as_ref()?.price) / 2.0)
+    }
+}
+
+/// the exchange contributing one side of the [Nbbo]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NbboSide {
+    pub exchange:       CexExchange,
+    pub price:          f64,
+    /// price after the exchange's taker fee, lower for bids & higher for asks
+    pub adjusted_price: f64,
+    pub amount:         f64
+}
+
+#[derive(Debug, Clone)]
+struct VenueQuote {
+    quote:       NormalizedQuote,
+    received_at: DateTime<Utc>
+}
+
+/// keeps the latest quote of every exchange per pair & consolidates them into
+/// the [Nbbo]
+///
+/// stale quotes are only dropped when their pair is updated or queried
+pub struct NbboAggregator {
+    quotes:        HashMap<CanonicalPair, BTreeMap<CexExchange, VenueQuote>>,
+    /// the last emitted nbbo of every pair
+    last:          HashMap<CanonicalPair, Nbbo>,
+    taker_fees:    HashMap<CexExchange, f64>,
+    max_staleness: Option<chrono::Duration>,
+    l2_quotes:     bool,
+    pair_mapper:   PairMapper,
+    /// the pairs the mapper skipped, only warned about once
+    unmapped:      HashSet<NormalizedTradingPair>
+}
+
+impl Default for NbboAggregator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NbboAggregator {
+    /// consolidates the quotes on [default_pair_mapper], w/o fees or stale
+    /// quotes
+    pub fn new() -> Self {
+        Self {
+            quotes:        HashMap::new(),
+            last:          HashMap::new(),
+            taker_fees:    HashMap::new(),
+            max_staleness: None,
+            l2_quotes:     false,
+            pair_mapper:   Box::new(default_pair_mapper),
+            unmapped:      HashSet::new()
+        }
+    }
+
+    /// taker fee of the exchange as a fraction, ex: 0.001 for 10bps
+    pub fn with_taker_fee(mut self, exchange: CexExchange, fee: f64) -> Self {
+        self.taker_fees.insert(exchange, fee);
+        self
+    }
+
+    /// ignores quotes recieved more than `max_staleness` before the latest
+    /// message
+    pub fn with_max_staleness(mut self, max_staleness: Duration) -> Self {
+        self.max_staleness = Some(chrono::Duration::from_std(max_staleness).unwrap_or(chrono::Duration::MAX));
+        self
+    }
+
+    /// also uses the top of the l2 books as quotes, only correct for full book
+    /// snapshots (ex: binance's partial book depth) & not diffs
+    pub fn with_l2_quotes(mut self) -> Self {
+        self.l2_quotes = true;
+        self
+    }
+
+    /// maps the exchanges' pairs to the pairs they are consolidated on
+    pub fn with_pair_mapper<F>(mut self, pair_mapper: F) -> Self
+    where
+        F: Fn(&NormalizedTradingPair) -> Option<CanonicalPair> + Send + Sync + 'static
+    {
+        self.pair_mapper = Box::new(pair_mapper);
+        self
+    }
+
+    /// updates the quotes of the message's exchange, returning the nbbos that
+    /// changed
+    pub fn update(&mut self, msg: CombinedWsMessage) -> Vec<Nbbo> {
+        let received_at = msg.receive_time().map_or_else(Utc::now, |time| time.wall);
+        self.update_normalized(msg.normalize(), received_at)
+    }
+
+    /// [Self::update] with already normalized data, the staleness of the quotes
+    /// is measured from `received_at`
+    pub fn update_normalized(&mut self, data: NormalizedWsDataTypes, received_at: DateTime<Utc>) -> Vec<Nbbo> {
+        self.apply(data, received_at)
+            .into_iter()
+            .filter_map(|pair| {
+                let nbbo = self.nbbo(&pair, received_at)?;
+                if self
+                    .last
+                    .get(&pair)
+                    .is_some_and(|last| last.bid == nbbo.bid && last.ask == nbbo.ask)
+                {
+                    return None
+                }
+
+                self.last.insert(pair, nbbo.clone());
+                Some(nbbo)
+            })
+            .collect()
+    }
+
+    /// updates the quotes of the message's exchange, returning the pairs whose
+    /// quotes changed
+    pub(crate) fn apply(&mut self, data: NormalizedWsDataTypes, received_at: DateTime<Utc>) -> Vec<CanonicalPair> {
+        let mut pairs = Vec::new();
+        let mut push_pair = |pair: CanonicalPair| {
+            if !pairs.contains(&pair) {
+                pairs.push(pair)
+            }
+        };
+
+        match data {
+            NormalizedWsDataTypes::Quote(quote) => self.insert_quotes(vec![quote], received_at, push_pair),
+            NormalizedWsDataTypes::Quotes(quotes) => self.insert_quotes(quotes, received_at, push_pair),
+            NormalizedWsDataTypes::L2(l2) if self.l2_quotes => self.insert_quotes(l2.get_quote().into_iter().collect(), received_at, push_pair),
+            NormalizedWsDataTypes::Disconnect { exchange, .. } => self.quotes.iter_mut().for_each(|(pair, quotes)| {
+                if quotes.remove(&exchange).is_some() {
+                    push_pair(pair.clone())
+                }
+            }),
+            NormalizedWsDataTypes::RemovedPair { exchange, bad_pair, .. } => {
+                if let Some(pair) = (self.pair_mapper)(&bad_pair) {
+                    if let Some(quotes) = self.quotes.get_mut(&pair) {
+                        quotes.remove(&exchange);
+                        push_pair(pair)
+                    }
+                }
+            }
+            _ => ()
+        }
+
+        pairs
+    }
+
+    /// the nbbo of the pair w/o the quotes that are stale at `now`
+    pub fn nbbo(&self, pair: &CanonicalPair, now: DateTime<Utc>) -> Option<Nbbo> {
+        let quotes = self.quotes.get(pair)?;
+
+        let mut bid: Option<NbboSide> = None;
+        let mut ask: Option<NbboSide> = None;
+        quotes
+            .values()
+            .filter(|venue| self.is_fresh(venue, now))
+            .for_each(|venue| {
+                let exchange = venue.quote.exchange;
+                let fee = self.taker_fee(exchange);
+                let (bid_price, bid_amount) = (venue.quote.bid_price.to_f64(), venue.quote.bid_amount.to_f64());
+                let (ask_price, ask_amount) = (venue.quote.ask_price.to_f64(), venue.quote.ask_amount.to_f64());
+
+                if bid_price > 0.0 && bid_amount > 0.0 {
+                    let adjusted_price = bid_price * (1.0 - fee);
+                    if bid.as_ref().is_none_or(|best| adjusted_price > best.adjusted_price) {
+                        bid = Some(NbboSide { exchange, price: bid_price, adjusted_price, amount: bid_amount });
+                    }
+                }
+                if ask_price > 0.0 && ask_amount > 0.0 {
+                    let adjusted_price = ask_price * (1.0 + fee);
+                    if ask.as_ref().is_none_or(|best| adjusted_price < best.adjusted_price) {
+                        ask = Some(NbboSide { exchange, price: ask_price, adjusted_price, amount: ask_amount });
+                    }
+                }
+            });
+
+        Some(Nbbo { pair: pair.clone(), bid, ask, time: now })
+    }
+
+    /// the latest quote of every exchange for the pair w/o the quotes that are
+    /// stale at `now`
+    pub(crate) fn venue_quotes(&self, pair: &CanonicalPair, now: DateTime<Utc>) -> Vec<&NormalizedQuote> {
+        self.quotes
+            .get(pair)
+            .map(|quotes| {
+                quotes
+                    .values()
+                    .filter(|venue| self.is_fresh(venue, now))
+                    .map(|venue| &venue.quote)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn taker_fee(&self, exchange: CexExchange) -> f64 {
+        self.taker_fees
+            .get(&exchange)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn is_fresh(&self, venue: &VenueQuote, now: DateTime<Utc>) -> bool {
+        self.max_staleness
+            .is_none_or(|max_staleness| now - venue.received_at <= max_staleness)
+    }
+
+    /// consolidates the quotes of the stream into the changed nbbos
+    pub fn into_stream<S>(mut self, stream: S) -> impl Stream<Item = Nbbo>
+    where
+        S: Stream<Item = CombinedWsMessage>
+    {
+        stream.flat_map(move |msg| stream::iter(self.update(msg)))
+    }
+
+    fn insert_quotes<F>(&mut self, quotes: Vec<NormalizedQuote>, received_at: DateTime<Utc>, mut push_pair: F)
+    where
+        F: FnMut(CanonicalPair)
+    {
+        quotes.into_iter().for_each(|quote| {
+            let Some(pair) = (self.pair_mapper)(&quote.pair) else {
+                if self.unmapped.insert(quote.pair.clone()) {
+                    warn!(target: "cex-exchanges::nbbo", exchange=?quote.exchange, "skipping the quotes of {:?}", quote.pair.pair());
+                }
+                return
+            };
+
+            self.quotes
+                .entry(pair.clone())
+                .or_default()
+                .insert(quote.exchange, VenueQuote { quote, received_at });
+            push_pair(pair);
+        });
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "us")]
+mod tests {
+    use super::*;
+    use crate::normalized::types::{num, BidAsk, NormalizedL2, TimeOrUpdateId};
+
+    fn quote(exchange: CexExchange, bid: (f64, f64), ask: (f64, f64)) -> NormalizedWsDataTypes {
+        NormalizedWsDataTypes::Quote(NormalizedQuote {
+            exchange,
+            pair: NormalizedTradingPair::new_base_quote(exchange, "btc", "usd", Some('-'), None),
+            bid_price: num(bid.0),
+            bid_amount: num(bid.1),
+            ask_price: num(ask.0),
+            ask_amount: num(ask.1),
+            orderbook_ids_time: TimeOrUpdateId::new()
+        })
+    }
+
+    fn side(exchange: CexExchange, price: f64, adjusted_price: f64, amount: f64) -> Option<NbboSide> {
+        Some(NbboSide { exchange, price, adjusted_price, amount })
+    }
+
+    #[test]
+    fn test_nbbo_across_exchanges() {
+        let mut aggregator = NbboAggregator::new().with_taker_fee(CexExchange::Okex, 0.001);
+        let now = Utc::now();
+
+        let nbbos = aggregator.update_normalized(quote(CexExchange::Coinbase, (100.0, 1.0), (101.0, 2.0)), now);
+        assert_eq!(nbbos.len(), 1);
+        assert_eq!(nbbos[0].pair, CanonicalPair::new("BTC", "USD"));
+        assert_eq!(nbbos[0].spread(), Some(1.0));
+
+        // okex's better raw bid is worse after its fee, its ask is still better
+        let nbbos = aggregator.update_normalized(quote(CexExchange::Okex, (100.05, 1.0), (100.5, 3.0)), now);
+        assert_eq!(nbbos[0].bid, side(CexExchange::Coinbase, 100.0, 100.0, 1.0));
+        assert_eq!(nbbos[0].ask, side(CexExchange::Okex, 100.5, 100.5 * 1.001, 3.0));
+        assert!(!nbbos[0].is_crossed());
+
+        // an unchanged nbbo isn't emitted
+        assert!(aggregator
+            .update_normalized(quote(CexExchange::Coinbase, (100.0, 1.0), (102.0, 2.0)), now)
+            .is_empty());
+
+        // an empty side is ignored
+        let nbbos = aggregator.update_normalized(quote(CexExchange::Coinbase, (0.0, 0.0), (100.4, 2.0)), now);
+        assert_eq!(nbbos[0].bid, side(CexExchange::Okex, 100.05, 100.05 * 0.999, 1.0));
+        assert_eq!(nbbos[0].ask, side(CexExchange::Coinbase, 100.4, 100.4, 2.0));
+
+        let disconnect = NormalizedWsDataTypes::Disconnect {
+            exchange:    CexExchange::Okex,
+            kind:        crate::clients::ws::WsErrorKind::Transport,
+            message:     String::new(),
+            raw_message: String::new()
+        };
+        let nbbos = aggregator.update_normalized(disconnect, now);
+        assert_eq!(nbbos[0].bid, None);
+        assert_eq!(nbbos[0].ask, side(CexExchange::Coinbase, 100.4, 100.4, 2.0));
+    }
+
+    #[test]
+    fn test_nbbo_staleness() {
+        let mut aggregator = NbboAggregator::new().with_max_staleness(Duration::from_secs(5));
+        let now = Utc::now();
+
+        aggregator.update_normalized(quote(CexExchange::Coinbase, (100.0, 1.0), (100.5, 1.0)), now);
+        let nbbos = aggregator.update_normalized(quote(CexExchange::Okex, (99.0, 1.0), (101.0, 1.0)), now + chrono::Duration::seconds(10));
+        assert_eq!(nbbos[0].bid, side(CexExchange::Okex, 99.0, 99.0, 1.0));
+        assert_eq!(nbbos[0].ask, side(CexExchange::Okex, 101.0, 101.0, 1.0));
+
+        let pair = CanonicalPair::new("BTC", "USD");
+        assert_eq!(aggregator.nbbo(&pair, now).unwrap().spread(), Some(0.5));
+    }
+
+    #[test]
+    fn test_nbbo_l2_quotes() {
+        let l2 = NormalizedWsDataTypes::L2(NormalizedL2 {
+            exchange:           CexExchange::Coinbase,
+            pair:               NormalizedTradingPair::new_base_quote(CexExchange::Coinbase, "BTC", "USD", Some('-'), None),
+            bids:               vec![BidAsk::new(num(99.0), num(1.0)), BidAsk::new(num(100.0), num(2.0))],
+            asks:               vec![BidAsk::new(num(101.0), num(1.0)), BidAsk::new(num(100.5), num(0.0))],
+            orderbook_ids_time: TimeOrUpdateId::new()
+        });
+
+        assert!(NbboAggregator::new()
+            .update_normalized(l2.clone(), Utc::now())
+            .is_empty());
+
+        let nbbos = NbboAggregator::new()
+            .with_l2_quotes()
+            .update_normalized(l2, Utc::now());
+        assert_eq!(nbbos[0].bid, side(CexExchange::Coinbase, 100.0, 100.0, 2.0));
+        assert_eq!(nbbos[0].ask, side(CexExchange::Coinbase, 101.0, 101.0, 1.0));
+    }
+
+    #[test]
+    #[cfg(feature = "non-us")]
+    fn test_nbbo_concatenated_pairs() {
+        let concatenated_quote = |exchange: CexExchange, pair: &str, bid: f64, ask: f64| {
+            NormalizedWsDataTypes::Quote(NormalizedQuote {
+                exchange,
+                pair: NormalizedTradingPair::new_no_base_quote(exchange, pair),
+                bid_price: num(bid),
+                bid_amount: num(1.0),
+                ask_price: num(ask),
+                ask_amount: num(1.0),
+                orderbook_ids_time: TimeOrUpdateId::new()
+            })
+        };
+        let mut aggregator = NbboAggregator::new();
+        let now = Utc::now();
+
+        let coinbase = NormalizedWsDataTypes::Quote(NormalizedQuote {
+            exchange:           CexExchange::Coinbase,
+            pair:               NormalizedTradingPair::new_base_quote(CexExchange::Coinbase, "BTC", "USDT", Some('-'), None),
+            bid_price:          num(100.0),
+            bid_amount:         num(1.0),
+            ask_price:          num(101.0),
+            ask_amount:         num(1.0),
+            orderbook_ids_time: TimeOrUpdateId::new()
+        });
+        aggregator.update_normalized(coinbase, now);
+
+        let nbbos = aggregator.update_normalized(concatenated_quote(CexExchange::Binance, "BTCUSDT", 100.5, 100.8), now);
+        assert_eq!(nbbos[0].pair, CanonicalPair::new("BTC", "USDT"));
+        assert_eq!(nbbos[0].bid, side(CexExchange::Binance, 100.5, 100.5, 1.0));
+        assert_eq!(nbbos[0].ask, side(CexExchange::Binance, 100.8, 100.8, 1.0));
+
+        let nbbos = aggregator.update_normalized(concatenated_quote(CexExchange::Bybit, "BTCFDUSD", 100.0, 100.2), now);
+        assert_eq!(nbbos[0].pair, CanonicalPair::new("BTC", "FDUSD"));
+
+        // unknown quote assets need a registry mapper
+        assert!(aggregator
+            .update_normalized(concatenated_quote(CexExchange::Binance, "BTCXYZ", 100.0, 100.2), now)
+            .is_empty());
+    }
+}
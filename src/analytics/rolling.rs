@@ -0,0 +1,183 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::exchanges::normalized::{
+    types::{NormalizedTrade, NormalizedTradingPair, NumberExt},
+    ws::{CombinedWsMessage, NormalizedWsDataTypes}
+};
+
+/// the vwap & twap of a pair on an exchange over the window ending at `time`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RollingAverage {
+    pub pair:   NormalizedTradingPair,
+    /// time of the latest trade
+    pub time:   DateTime<Utc>,
+    /// volume weighted price of the trades in the window
+    pub vwap:   f64,
+    /// time weighted price, each trade's price is weighted by how long it was
+    /// the last price in the window
+    pub twap:   f64,
+    /// traded amount in the base asset of the trades in the window
+    pub volume: f64,
+    pub trades: usize
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WindowTrade {
+    time:   DateTime<Utc>,
+    price:  f64,
+    amount: f64
+}
+
+/// rolling vwap & twap of every pair over a time window
+///
+/// the window is measured with the trades' time & ends at the latest trade of
+/// the pair
+#[derive(Debug, Clone)]
+pub struct RollingAverager {
+    window: TimeDelta,
+    /// the trades of every pair in the window, with the last trade before the
+    /// window as the twap's starting price
+    trades: HashMap<NormalizedTradingPair, VecDeque<WindowTrade>>
+}
+
+impl RollingAverager {
+    pub fn new(window: Duration) -> Self {
+        Self { window: TimeDelta::from_std(window).unwrap_or(TimeDelta::MAX), trades: HashMap::new() }
+    }
+
+    /// adds the trades of the message, returning the averages of the updated
+    /// pairs
+    pub fn update(&mut self, msg: CombinedWsMessage) -> Vec<RollingAverage> {
+        self.update_normalized(msg.normalize())
+    }
+
+    /// [Self::update] with already normalized data
+    pub fn update_normalized(&mut self, data: NormalizedWsDataTypes) -> Vec<RollingAverage> {
+        let trades = match data {
+            NormalizedWsDataTypes::Trade(trade) => vec![trade],
+            NormalizedWsDataTypes::Trades(trades) => trades,
+            _ => return Vec::new()
+        };
+
+        let mut pairs = Vec::new();
+        trades.iter().for_each(|trade| {
+            self.insert(trade);
+            if !pairs.contains(&trade.pair) {
+                pairs.push(trade.pair.clone())
+            }
+        });
+
+        pairs
+            .iter()
+            .filter_map(|pair| self.average(pair))
+            .collect()
+    }
+
+    /// the averages of the pair over the window ending at its latest trade
+    pub fn average(&self, pair: &NormalizedTradingPair) -> Option<RollingAverage> {
+        let trades = self.trades.get(pair)?;
+        let end = trades.back()?.time;
+        let start = end - self.window;
+
+        let in_window = trades
+            .iter()
+            .filter(|trade| trade.time > start)
+            .collect::<Vec<_>>();
+        let volume = in_window.iter().map(|trade| trade.amount).sum::<f64>();
+        let vwap = if volume > 0.0 {
+            in_window
+                .iter()
+                .map(|trade| trade.price * trade.amount)
+                .sum::<f64>()
+                / volume
+        } else {
+            trades.back()?.price
+        };
+
+        let (mut weighted, mut total) = (0.0, 0.0);
+        trades.iter().enumerate().for_each(|(idx, trade)| {
+            let seg_end = trades.get(idx + 1).map_or(end, |next| next.time);
+            let seg = (seg_end - trade.time.max(start)).num_microseconds().unwrap_or(i64::MAX) as f64;
+            if seg > 0.0 {
+                weighted += trade.price * seg;
+                total += seg;
+            }
+        });
+        let twap = if total > 0.0 { weighted / total } else { trades.back()?.price };
+
+        Some(RollingAverage { pair: pair.clone(), time: end, vwap, twap, volume, trades: in_window.len() })
+    }
+
+    /// aggregates the trades of the stream into the updated averages
+    pub fn into_stream<S>(mut self, stream: S) -> impl Stream<Item = RollingAverage>
+    where
+        S: Stream<Item = CombinedWsMessage>
+    {
+        stream.flat_map(move |msg| stream::iter(self.update(msg)))
+    }
+
+    fn insert(&mut self, trade: &NormalizedTrade) {
+        let trades = self.trades.entry(trade.pair.clone()).or_default();
+
+        // out of order trades are treated as trades at the latest time
+        let time = trades
+            .back()
+            .map_or(trade.time, |last| last.time.max(trade.time));
+        trades.push_back(WindowTrade { time, price: trade.price.to_f64(), amount: trade.amount.to_f64() });
+
+        let start = time - self.window;
+        while trades.get(1).is_some_and(|next| next.time <= start) {
+            trades.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "us")]
+mod tests {
+    use super::*;
+    use crate::{
+        normalized::types::{num, TradeSide},
+        CexExchange
+    };
+
+    fn trade(secs: i64, price: f64, amount: f64) -> NormalizedTrade {
+        NormalizedTrade {
+            exchange: CexExchange::Coinbase,
+            pair:     NormalizedTradingPair::new_base_quote(CexExchange::Coinbase, "BTC", "USD", Some('-'), None),
+            time:     DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+            side:     TradeSide::Buy,
+            raw_side: "buy".to_string(),
+            price:    num(price),
+            amount:   num(amount),
+            trade_id: None
+        }
+    }
+
+    #[test]
+    fn test_rolling_averages() {
+        let mut averager = RollingAverager::new(Duration::from_secs(10));
+
+        let averages = averager.update_normalized(NormalizedWsDataTypes::Trades(vec![trade(0, 100.0, 1.0), trade(4, 110.0, 3.0)]));
+        assert_eq!(averages.len(), 1);
+        assert_eq!(averages[0].vwap, (100.0 + 330.0) / 4.0);
+        assert_eq!(averages[0].twap, 100.0);
+
+        // the trade at 0s is out of the window, its price is the twap's price until 4s
+        let averages = averager.update_normalized(NormalizedWsDataTypes::Trade(trade(12, 90.0, 1.0)));
+        assert_eq!((averages[0].trades, averages[0].volume), (2, 4.0));
+        assert_eq!(averages[0].vwap, (330.0 + 90.0) / 4.0);
+        assert_eq!(averages[0].twap, (100.0 * 2.0 + 110.0 * 8.0) / 10.0);
+
+        assert!(averager
+            .update_normalized(NormalizedWsDataTypes::Other { exchange: CexExchange::Coinbase, kind: String::new(), value: String::new() })
+            .is_empty());
+    }
+}
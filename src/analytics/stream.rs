@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use futures::{stream, Stream, StreamExt};
+
+use super::{BarAggregator, BarKind, RollingAverage, RollingAverager, TradeBar};
+use crate::exchanges::normalized::ws::NormalizedWsDataTypes;
+
+/// trade aggregations over a stream of normalized messages, ex:
+/// `stream.map(|msg| msg.normalize()).trade_bars(BarKind::Tick(100))`
+pub trait NormalizedWsStreamExt: Stream<Item = NormalizedWsDataTypes> + Sized {
+    /// the closed [TradeBar]s of every pair
+    fn trade_bars(self, kind: BarKind) -> impl Stream<Item = TradeBar> {
+        let mut aggregator = BarAggregator::new(kind);
+        self.flat_map(move |data| stream::iter(aggregator.update_normalized(data)))
+    }
+
+    /// the [RollingAverage] of every pair on each of its trades
+    fn rolling_averages(self, window: Duration) -> impl Stream<Item = RollingAverage> {
+        let mut averager = RollingAverager::new(window);
+        self.flat_map(move |data| stream::iter(averager.update_normalized(data)))
+    }
+}
+
+impl<S> NormalizedWsStreamExt for S where S: Stream<Item = NormalizedWsDataTypes> + Sized {}
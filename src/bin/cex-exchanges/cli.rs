@@ -0,0 +1,115 @@
+use std::future::Future;
+
+use cex_exchanges::{clients::rest_api::RestApiError, CexExchange};
+use clap::{Args, Parser, Subcommand};
+use futures::future::join_all;
+use tracing::{warn, Level};
+
+use crate::{currencies::CurrenciesArgs, instruments::InstrumentsArgs, output::OutputFormat, record::RecordArgs, stream::StreamArgs};
+
+#[derive(Debug, Parser)]
+#[command(name = "cex-exchanges", about = "query the normalized rest apis & live streams of centralized exchanges")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command:   Commands,
+    /// level of the logs written to stderr, overridden by `RUST_LOG`
+    #[arg(long, global = true, default_value_t = Level::WARN)]
+    pub log_level: Level
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    /// lists the trading instruments of the exchanges
+    Instruments(InstrumentsArgs),
+    /// lists the currencies of the exchanges
+    Currencies(CurrenciesArgs),
+    /// prints the normalized trades/quotes/l2 of the exchanges as json lines
+    Stream(StreamArgs),
+    /// writes the raw ws frames of the exchanges to disk
+    Record(RecordArgs)
+}
+
+/// the exchanges to query & how to print the results
+#[derive(Debug, Args)]
+pub struct ExchangeArgs {
+    /// exchanges to query (comma separated or repeated), defaults to all
+    #[arg(short, long = "exchange", value_enum, value_delimiter = ',')]
+    pub exchanges: Vec<CexExchange>,
+    /// output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format:    OutputFormat
+}
+
+impl ExchangeArgs {
+    pub fn exchanges(&self) -> Vec<CexExchange> {
+        selected_exchanges(&self.exchanges)
+    }
+}
+
+/// the deduplicated exchanges, or all of them if none were selected
+pub fn selected_exchanges(exchanges: &[CexExchange]) -> Vec<CexExchange> {
+    if exchanges.is_empty() {
+        CexExchange::vec_all()
+    } else {
+        let mut exchanges = exchanges.to_vec();
+        exchanges.sort();
+        exchanges.dedup();
+        exchanges
+    }
+}
+
+/// queries every exchange concurrently, the exchanges that fail are logged &
+/// skipped unless all of them fail
+pub async fn query_exchanges<T, F, Fut>(exchanges: Vec<CexExchange>, query: F) -> eyre::Result<Vec<T>>
+where
+    F: Fn(CexExchange) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, RestApiError>>
+{
+    let results = join_all(exchanges.into_iter().map(|exchange| {
+        let fut = query(exchange);
+        async move { (exchange, fut.await) }
+    }))
+    .await;
+
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    for (exchange, result) in results {
+        match result {
+            Ok(vals) => values.extend(vals),
+            Err(e) => {
+                warn!(target: "cex-exchanges::cli", "failed to query {exchange}: {e}");
+                errors.push(format!("{exchange}: {e}"));
+            }
+        }
+    }
+
+    if values.is_empty() && !errors.is_empty() {
+        return Err(eyre::eyre!("every exchange failed - {}", errors.join(", ")))
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use cex_exchanges::normalized::ws::NormalizedWsChannelKinds;
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn test_cli_args() {
+        Cli::command().debug_assert();
+
+        let args = ["cex-exchanges", "stream", "-c", "trades,quotes", "-p", "BTC-USDT", "-p", "eth/usdc", "--threads", "2"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        let Commands::Stream(args) = cli.command else { panic!("expected the stream subcommand") };
+        assert_eq!(args.feed_args.channels, vec![NormalizedWsChannelKinds::Trades, NormalizedWsChannelKinds::Quotes]);
+        assert_eq!(args.feed_args.pairs, vec!["BTC-USDT".to_string(), "eth/usdc".to_string()]);
+        assert_eq!(args.feed_args.threads, Some(2));
+
+        assert!(Cli::try_parse_from(["cex-exchanges", "stream", "-c", "trades"]).is_err());
+        assert!(Cli::try_parse_from(["cex-exchanges", "record", "-c", "l2", "-p", "BTC-USDT", "--all-active", "-d", "out"]).is_err());
+        assert!(Cli::try_parse_from(["cex-exchanges", "record", "-c", "l2", "--all-active", "-d", "out", "--no-rotation", "--zstd", "3"]).is_ok());
+    }
+}
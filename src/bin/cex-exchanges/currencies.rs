@@ -0,0 +1,70 @@
+use cex_exchanges::{
+    normalized::types::{Blockchain, CurrencyFilter, NormalizedCurrency},
+    traits::{EmptyFilter, ExchangeFilter}
+};
+use clap::Args;
+
+use crate::{
+    cli::{query_exchanges, ExchangeArgs},
+    output::{write_output, TableRows}
+};
+
+#[derive(Debug, Args)]
+pub struct CurrenciesArgs {
+    #[command(flatten)]
+    pub exchange_args: ExchangeArgs,
+    /// only the currency with this symbol
+    #[arg(long)]
+    pub symbol:        Option<String>,
+    /// only currencies on these blockchains (comma separated or repeated),
+    /// their other blockchains are left out
+    #[arg(short, long = "blockchain", value_enum, value_delimiter = ',')]
+    pub blockchains:   Vec<Blockchain>
+}
+
+impl CurrenciesArgs {
+    pub async fn execute(self) -> eyre::Result<()> {
+        let mut currencies = query_exchanges(self.exchange_args.exchanges(), |exchange| exchange.get_all_currencies(None::<EmptyFilter>)).await?;
+
+        if let Some(symbol) = &self.symbol {
+            CurrencyFilter::symbol(symbol.to_uppercase()).filter_matches(&mut currencies);
+        }
+        if !self.blockchains.is_empty() {
+            self.blockchains.filter_matches(&mut currencies);
+            currencies
+                .iter_mut()
+                .for_each(|curr| curr.blockchains.retain(|b| self.blockchains.contains(&b.blockchain)));
+        }
+        currencies.sort_by(|a, b| a.exchange.cmp(&b.exchange).then_with(|| a.symbol.cmp(&b.symbol)));
+
+        write_output(std::io::stdout().lock(), self.exchange_args.format, &currencies)
+    }
+}
+
+/// one row per blockchain of the currency
+impl TableRows for NormalizedCurrency {
+    const HEADERS: &'static [&'static str] = &["exchange", "symbol", "name", "status", "blockchain", "address", "wrapped"];
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        let currency = [self.exchange.to_string(), self.symbol.clone(), self.name.clone(), self.status.clone()];
+        if self.blockchains.is_empty() {
+            return vec![currency.into_iter().chain(vec![String::new(); 3]).collect()]
+        }
+
+        self.blockchains
+            .iter()
+            .map(|chain| {
+                let wrapped = match (&chain.wrapped_currency, chain.is_wrapped) {
+                    (Some(wrapped), _) => wrapped.symbol.clone(),
+                    (None, is_wrapped) => is_wrapped.to_string()
+                };
+
+                currency
+                    .iter()
+                    .cloned()
+                    .chain([chain.blockchain.to_string(), chain.address.clone().unwrap_or_default(), wrapped])
+                    .collect()
+            })
+            .collect()
+    }
+}
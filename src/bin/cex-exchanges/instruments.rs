@@ -0,0 +1,91 @@
+use cex_exchanges::{
+    normalized::types::{InstrumentFilter, NormalizedInstrument},
+    traits::{EmptyFilter, ExchangeFilter}
+};
+use clap::Args;
+
+use crate::{
+    cli::{query_exchanges, ExchangeArgs},
+    output::{write_output, TableRows}
+};
+
+#[derive(Debug, Args)]
+pub struct InstrumentsArgs {
+    #[command(flatten)]
+    pub exchange_args: ExchangeArgs,
+    /// only the instrument with this normalized pair, ex: 'BTC-USDT' on okex
+    /// or 'BTCUSDT' on binance
+    #[arg(long)]
+    pub pair:          Option<String>,
+    /// only instruments with this symbol as either the base or quote asset
+    #[arg(long)]
+    pub symbol:        Option<String>,
+    /// only instruments with this base asset
+    #[arg(long)]
+    pub base:          Option<String>,
+    /// only instruments with this quote asset
+    #[arg(long)]
+    pub quote:         Option<String>,
+    /// only active instruments
+    #[arg(long)]
+    pub active:        bool
+}
+
+impl InstrumentsArgs {
+    /// an instrument has to match all of the filters
+    fn filters(&self) -> Vec<InstrumentFilter> {
+        let upper = |v: &Option<String>| v.as_ref().map(|v| v.to_uppercase());
+
+        let mut filters = Vec::new();
+        if let Some(pair) = upper(&self.pair) {
+            filters.push(InstrumentFilter::pair(pair));
+        }
+        if let Some(symbol) = upper(&self.symbol) {
+            filters.push(InstrumentFilter::base_or_quote(symbol));
+        }
+        match (upper(&self.base), upper(&self.quote)) {
+            (Some(base), Some(quote)) => filters.push(InstrumentFilter::base_and_quote(base, quote)),
+            (Some(base), None) => filters.push(InstrumentFilter::base_only(base)),
+            (None, Some(quote)) => filters.push(InstrumentFilter::quote_only(quote)),
+            (None, None) => ()
+        }
+        if self.active {
+            filters.push(InstrumentFilter::Active);
+        }
+
+        filters
+    }
+
+    pub async fn execute(self) -> eyre::Result<()> {
+        let mut instruments = query_exchanges(self.exchange_args.exchanges(), |exchange| exchange.get_all_instruments(None::<EmptyFilter>)).await?;
+
+        self.filters()
+            .iter()
+            .for_each(|filter| filter.filter_matches(&mut instruments));
+        instruments.sort_by(|a, b| {
+            a.exchange
+                .cmp(&b.exchange)
+                .then_with(|| a.trading_pair.cmp(&b.trading_pair))
+        });
+
+        write_output(std::io::stdout().lock(), self.exchange_args.format, &instruments)
+    }
+}
+
+impl TableRows for NormalizedInstrument {
+    const HEADERS: &'static [&'static str] = &["exchange", "pair", "type", "base", "quote", "active", "expiry"];
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.exchange.to_string(),
+            self.trading_pair.make_pair(),
+            self.trading_type.to_string(),
+            self.base_asset_symbol.clone(),
+            self.quote_asset_symbol.clone(),
+            self.active.to_string(),
+            self.futures_expiry
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+        ]]
+    }
+}
@@ -0,0 +1,34 @@
+//! command line interface over the normalized rest apis & live streams of
+//! every exchange
+
+mod cli;
+mod currencies;
+mod instruments;
+mod output;
+mod record;
+mod stream;
+
+use clap::Parser;
+use cli::{Cli, Commands};
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let cli = Cli::parse();
+
+    // logs go to stderr so they never end up in the json/csv output
+    let filter = EnvFilter::builder()
+        .with_default_directive(format!("cex-exchanges={}", cli.log_level).parse()?)
+        .from_env_lossy();
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(filter)
+        .init();
+
+    match cli.command {
+        Commands::Instruments(args) => args.execute().await,
+        Commands::Currencies(args) => args.execute().await,
+        Commands::Stream(args) => args.execute().await,
+        Commands::Record(args) => args.execute().await
+    }
+}
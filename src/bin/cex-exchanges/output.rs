@@ -0,0 +1,123 @@
+use std::io::Write;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// aligned columns
+    #[default]
+    Table,
+    /// the full normalized values
+    Json,
+    /// the same columns as the table
+    Csv
+}
+
+/// a value printed as one or more rows of a table/csv
+pub trait TableRows: Serialize {
+    const HEADERS: &'static [&'static str];
+
+    fn rows(&self) -> Vec<Vec<String>>;
+}
+
+pub fn write_output<T: TableRows, W: Write>(mut writer: W, format: OutputFormat, values: &[T]) -> eyre::Result<()> {
+    match format {
+        OutputFormat::Table => write_table(&mut writer, T::HEADERS, values.iter().flat_map(T::rows).collect())?,
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, values)?;
+            writeln!(writer)?;
+        }
+        OutputFormat::Csv => {
+            let mut csv_writer = csv::Writer::from_writer(&mut writer);
+            csv_writer.write_record(T::HEADERS)?;
+            for row in values.iter().flat_map(T::rows) {
+                csv_writer.write_record(row)?;
+            }
+            csv_writer.flush()?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_table<W: Write>(writer: &mut W, headers: &[&str], rows: Vec<Vec<String>>) -> std::io::Result<()> {
+    let mut widths = headers.iter().map(|h| h.chars().count()).collect::<Vec<_>>();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let write_line = |writer: &mut W, cells: Vec<String>| {
+        let line = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(writer, "{}", line.trim_end())
+    };
+
+    write_line(writer, headers.iter().map(ToString::to_string).collect())?;
+    write_line(writer, widths.iter().map(|w| "-".repeat(*w)).collect())?;
+    for row in rows {
+        write_line(writer, row)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct TestRow {
+        symbol: String,
+        chains: Vec<String>
+    }
+
+    impl TableRows for TestRow {
+        const HEADERS: &'static [&'static str] = &["symbol", "chain"];
+
+        fn rows(&self) -> Vec<Vec<String>> {
+            self.chains
+                .iter()
+                .map(|chain| vec![self.symbol.clone(), chain.clone()])
+                .collect()
+        }
+    }
+
+    fn test_values() -> Vec<TestRow> {
+        vec![
+            TestRow { symbol: "USDC".to_string(), chains: vec!["Ethereum".to_string(), "Solana".to_string()] },
+            TestRow { symbol: "WETH".to_string(), chains: vec!["Arbitrum, One".to_string()] },
+        ]
+    }
+
+    fn render(format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        write_output(&mut out, format, &test_values()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_table() {
+        let expected = "symbol  chain\n------  -------------\nUSDC    Ethereum\nUSDC    Solana\nWETH    Arbitrum, One\n";
+        assert_eq!(render(OutputFormat::Table), expected);
+    }
+
+    #[test]
+    fn test_csv() {
+        let expected = "symbol,chain\nUSDC,Ethereum\nUSDC,Solana\nWETH,\"Arbitrum, One\"\n";
+        assert_eq!(render(OutputFormat::Csv), expected);
+    }
+
+    #[test]
+    fn test_json() {
+        let calculated: serde_json::Value = serde_json::from_str(&render(OutputFormat::Json)).unwrap();
+        assert_eq!(calculated, serde_json::to_value(test_values()).unwrap());
+    }
+}
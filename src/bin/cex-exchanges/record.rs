@@ -0,0 +1,56 @@
+use std::{path::PathBuf, time::Duration};
+
+use cex_exchanges::clients::ws::{WsRecorderConfig, WsStreamConfig};
+use clap::Args;
+use tracing::warn;
+
+use crate::stream::FeedArgs;
+
+#[derive(Debug, Args)]
+pub struct RecordArgs {
+    #[command(flatten)]
+    pub feed_args:      FeedArgs,
+    /// root directory of the recordings, the frames are written to
+    /// `<dir>/<exchange>/`
+    #[arg(short, long)]
+    pub dir:            PathBuf,
+    /// rotates the files after this many bytes
+    #[arg(long, default_value_t = 256 * 1024 * 1024)]
+    pub max_file_bytes: u64,
+    /// rotates the files after this many seconds
+    #[arg(long, default_value_t = 60 * 60)]
+    pub max_file_secs:  u64,
+    /// never rotates the files
+    #[arg(long, conflicts_with_all = ["max_file_bytes", "max_file_secs"])]
+    pub no_rotation:    bool,
+    /// compresses the files with this zstd level
+    #[arg(long)]
+    pub zstd:           Option<i32>
+}
+
+impl RecordArgs {
+    /// writes the raw frames to disk, only logging the stream errors
+    pub async fn execute(self) -> eyre::Result<()> {
+        let mut recorder = if self.no_rotation {
+            WsRecorderConfig::new(&self.dir).without_rotation()
+        } else {
+            WsRecorderConfig::new(&self.dir)
+                .with_max_file_bytes(self.max_file_bytes)
+                .with_max_file_age(Duration::from_secs(self.max_file_secs))
+        };
+        if let Some(level) = self.zstd {
+            recorder = recorder.with_zstd(level);
+        }
+
+        let config = WsStreamConfig::new(self.feed_args.max_retries).with_recorder(recorder);
+
+        self.feed_args
+            .run(config, |msg| {
+                if msg.is_err() {
+                    warn!(target: "cex-exchanges::cli", "{:?}", msg.normalize());
+                }
+                Ok(())
+            })
+            .await
+    }
+}
@@ -0,0 +1,185 @@
+use std::{collections::HashMap, io::Write, pin::pin};
+
+use cex_exchanges::{
+    clients::ws::WsStreamConfig,
+    normalized::{
+        types::{InstrumentFilter, NormalizedTradingType, RawTradingPair},
+        ws::{CombinedWsMessage, NormalizedExchangeBuilder, NormalizedWsChannelKinds, NormalizedWsChannels}
+    },
+    CexExchange
+};
+use clap::Args;
+use futures::{Stream, StreamExt};
+use tracing::{info, warn};
+
+use crate::cli::{query_exchanges, selected_exchanges};
+
+/// the feeds to connect to, shared by `stream` & `record`
+#[derive(Debug, Args)]
+pub struct FeedArgs {
+    /// exchanges to connect to (comma separated or repeated), defaults to all
+    /// that support the channels
+    #[arg(short, long = "exchange", value_enum, value_delimiter = ',')]
+    pub exchanges:              Vec<CexExchange>,
+    /// channels to subscribe to (comma separated or repeated)
+    #[arg(short, long = "channel", value_enum, value_delimiter = ',', required = true)]
+    pub channels:               Vec<NormalizedWsChannelKinds>,
+    /// pairs to subscribe to (comma separated or repeated), ex: 'BTC-USDT' or
+    /// 'eth/usdc'
+    #[arg(short, long = "pair", value_delimiter = ',', required_unless_present = "all_active", conflicts_with = "all_active")]
+    pub pairs:                  Vec<String>,
+    /// subscribes to every active spot instrument of the exchanges
+    #[arg(long)]
+    pub all_active:             bool,
+    /// spreads the connections over this many threads, by default they all run
+    /// on the current thread
+    #[arg(long)]
+    pub threads:                Option<usize>,
+    /// max channels per ws connection, defaults to each exchange's limit
+    #[arg(long)]
+    pub connections_per_stream: Option<usize>,
+    /// max retries of each connection before it is closed, by default it never
+    /// gives up
+    #[arg(long)]
+    pub max_retries:            Option<u64>,
+    /// exits after this many messages, by default it runs until ctrl-c
+    #[arg(long)]
+    pub limit:                  Option<usize>
+}
+
+impl FeedArgs {
+    /// connects to the feeds and calls `on_message` with every message until
+    /// ctrl-c, the `limit` or all the connections are closed
+    pub async fn run<F>(&self, config: WsStreamConfig, on_message: F) -> eyre::Result<()>
+    where
+        F: FnMut(CombinedWsMessage) -> eyre::Result<()>
+    {
+        let builder = self.builder().await?;
+
+        if let Some(threads) = self.threads {
+            let (stream, handle) = builder
+                .build_all_multithreaded(threads, config, self.connections_per_stream)?
+                .ok_or(eyre::eyre!("no streams to connect to"))?;
+            let res = self.drain(stream, on_message).await;
+
+            handle.shutdown();
+            tokio::task::spawn_blocking(move || handle.join()).await??;
+            res
+        } else {
+            let stream = builder
+                .build_all_multistream(config, self.connections_per_stream)?
+                .ok_or(eyre::eyre!("no streams to connect to"))?;
+            self.drain(stream, on_message).await
+        }
+    }
+
+    async fn builder(&self) -> eyre::Result<NormalizedExchangeBuilder> {
+        let channels = self.supported_channels()?;
+        let exchanges = selected_exchanges(&self.exchanges)
+            .into_iter()
+            .filter(|exchange| channels.contains_key(exchange))
+            .collect::<Vec<_>>();
+
+        let pairs = if self.all_active {
+            let mut instruments = query_exchanges(exchanges, |exchange| exchange.get_all_instruments(Some(InstrumentFilter::Active))).await?;
+            instruments.retain(|instrument| instrument.trading_type == NormalizedTradingType::Spot);
+
+            let mut pairs = HashMap::<_, Vec<_>>::new();
+            instruments.into_iter().for_each(|instrument| {
+                pairs
+                    .entry(instrument.exchange)
+                    .or_default()
+                    .push(RawTradingPair::from(instrument.trading_pair))
+            });
+            pairs
+        } else {
+            let pairs = self.pairs.iter().map(|p| RawTradingPair::parse(p)).collect::<Vec<_>>();
+            exchanges
+                .into_iter()
+                .map(|exchange| (exchange, pairs.clone()))
+                .collect()
+        };
+
+        let mut builder = NormalizedExchangeBuilder::new();
+        pairs.into_iter().for_each(|(exchange, pairs)| {
+            info!(target: "cex-exchanges::cli", "subscribing to {} {exchange} pairs", pairs.len());
+            builder.add_pairs_all_channels(exchange, &channels[&exchange], &pairs)
+        });
+
+        Ok(builder)
+    }
+
+    /// the channels of every exchange that it supports, erroring if an
+    /// explicitly selected exchange doesn't support a channel
+    fn supported_channels(&self) -> eyre::Result<HashMap<CexExchange, Vec<NormalizedWsChannelKinds>>> {
+        let mut supported = HashMap::new();
+        for exchange in selected_exchanges(&self.exchanges) {
+            let (channels, unsupported): (Vec<_>, Vec<_>) = self
+                .channels
+                .iter()
+                .copied()
+                .partition(|channel| exchange.supports_ws_channel(&NormalizedWsChannels::new_default(*channel)));
+
+            if let Some(channel) = unsupported.first() {
+                if !self.exchanges.is_empty() {
+                    eyre::bail!("{exchange} doesn't support the {channel:?} channel");
+                }
+                warn!(target: "cex-exchanges::cli", "skipping the unsupported {exchange} channels {unsupported:?}");
+            }
+            if !channels.is_empty() {
+                supported.insert(exchange, channels);
+            }
+        }
+
+        if supported.is_empty() {
+            eyre::bail!("no exchange supports the channels {:?}", self.channels);
+        }
+
+        Ok(supported)
+    }
+
+    async fn drain<S, F>(&self, mut stream: S, mut on_message: F) -> eyre::Result<()>
+    where
+        S: Stream<Item = CombinedWsMessage> + Unpin,
+        F: FnMut(CombinedWsMessage) -> eyre::Result<()>
+    {
+        let mut ctrl_c = pin!(tokio::signal::ctrl_c());
+        let mut count = 0;
+
+        while self.limit.is_none_or(|limit| count < limit) {
+            tokio::select! {
+                _ = &mut ctrl_c => break,
+                msg = stream.next() => match msg {
+                    Some(msg) => on_message(msg)?,
+                    None => break
+                }
+            }
+            count += 1;
+        }
+
+        info!(target: "cex-exchanges::cli", "stopped after {count} messages");
+        Ok(())
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct StreamArgs {
+    #[command(flatten)]
+    pub feed_args: FeedArgs
+}
+
+impl StreamArgs {
+    /// prints every normalized message as a json line
+    pub async fn execute(self) -> eyre::Result<()> {
+        let config = WsStreamConfig::new(self.feed_args.max_retries);
+
+        let mut stdout = std::io::stdout().lock();
+        self.feed_args
+            .run(config, |msg| {
+                serde_json::to_writer(&mut stdout, &msg.normalize())?;
+                writeln!(stdout)?;
+                Ok(())
+            })
+            .await
+    }
+}
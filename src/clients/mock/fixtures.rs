@@ -0,0 +1,110 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf}
+};
+
+use crate::CexExchange;
+
+/// canned rest responses & ws frames served by a
+/// [MockExchangeServer](super::MockExchangeServer)
+#[derive(Debug, Clone, Default)]
+pub struct MockFixtures {
+    /// response bodies keyed by `/<exchange><path>[?<query>]`
+    rest: HashMap<String, String>,
+    /// frames sent (in order) once a ws connection has subscribed
+    ws:   HashMap<CexExchange, Vec<String>>
+}
+
+impl MockFixtures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// loads the fixtures of every exchange from `dir`:
+    /// - `<dir>/<exchange>/ws.jsonl` - one ws frame per line
+    /// - `<dir>/<exchange>/rest/<path>.json` - the response to `GET
+    ///   /<exchange>/<path>`, a `@` in the file name is the start of the query
+    ///   (ex: `rest/api/v5/public/instruments@instType=SPOT.json`)
+    pub fn from_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut this = Self::new();
+
+        for exchange in CexExchange::vec_all() {
+            let exchange_dir = dir.as_ref().join(exchange.to_string());
+
+            let ws_path = exchange_dir.join("ws.jsonl");
+            if ws_path.exists() {
+                let frames = fs::read_to_string(ws_path)?
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(ToString::to_string)
+                    .collect();
+                this = this.with_ws_frames(exchange, frames);
+            }
+
+            let rest_dir = exchange_dir.join("rest");
+            for path in json_files(&rest_dir)? {
+                let relative = path
+                    .strip_prefix(&rest_dir)
+                    .unwrap_or(&path)
+                    .with_extension("")
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join("/")
+                    .replacen('@', "?", 1);
+
+                this = this.with_rest(&format!("/{exchange}/{relative}"), fs::read_to_string(&path)?);
+            }
+        }
+
+        Ok(this)
+    }
+
+    /// serves `body` for `GET <path>`, where `path` includes the exchange
+    /// prefix (ex: `/coinbase/products`)
+    ///
+    /// a path without a query matches requests with any query
+    pub fn with_rest(mut self, path: &str, body: impl Into<String>) -> Self {
+        self.rest.insert(path.to_string(), body.into());
+        self
+    }
+
+    pub fn with_ws_frames(mut self, exchange: CexExchange, frames: Vec<String>) -> Self {
+        self.ws.insert(exchange, frames);
+        self
+    }
+
+    pub(crate) fn rest_response(&self, path_and_query: &str) -> Option<&String> {
+        self.rest.get(path_and_query).or_else(|| {
+            path_and_query
+                .split_once('?')
+                .and_then(|(path, _)| self.rest.get(path))
+        })
+    }
+
+    pub(crate) fn ws_frames(&self, exchange: CexExchange) -> &[String] {
+        self.ws.get(&exchange).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+fn json_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !dir.exists() {
+        return Ok(files)
+    }
+
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "json") {
+                files.push(path);
+            }
+        }
+    }
+
+    Ok(files)
+}
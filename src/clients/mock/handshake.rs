@@ -0,0 +1,113 @@
+use serde_json::{json, Value};
+
+use crate::CexExchange;
+
+/// token handed out by the mocked kucoin bullet endpoint, ws connections
+/// without it are rejected
+pub(crate) const MOCK_KUCOIN_TOKEN: &str = "mock-token";
+
+const MOCK_CONN_ID: &str = "mock";
+
+/// the mocked exchange's response to a single client text frame
+#[derive(Debug, Default)]
+pub(crate) struct MockReply {
+    pub(crate) messages:     Vec<String>,
+    /// set if the frame was a subscribe request
+    pub(crate) subscription: Option<Value>
+}
+
+/// sent by the exchange as soon as the connection is opened
+pub(crate) fn welcome(exchange: CexExchange) -> Option<String> {
+    match exchange {
+        #[cfg(feature = "non-us")]
+        CexExchange::Kucoin => Some(json!({ "id": MOCK_CONN_ID, "type": "welcome" }).to_string()),
+        _ => None
+    }
+}
+
+/// acks subscribe requests & answers app level pings the way the exchange
+/// does, anything else is ignored
+pub(crate) fn reply(exchange: CexExchange, text: &str) -> MockReply {
+    let Ok(request) = serde_json::from_str::<Value>(text) else {
+        // okex's app level ping is the plain text 'ping'
+        return match text {
+            "ping" => MockReply { messages: vec!["pong".to_string()], subscription: None },
+            _ => MockReply::default()
+        }
+    };
+
+    let field = |name: &str| request.get(name).and_then(Value::as_str).unwrap_or_default();
+
+    match exchange {
+        #[cfg(feature = "us")]
+        CexExchange::Coinbase if field("type") == "subscribe" => MockReply {
+            messages:     vec![json!({ "type": "subscriptions", "channels": request["channels"] }).to_string()],
+            subscription: Some(request)
+        },
+        #[cfg(feature = "us")]
+        CexExchange::Okex if field("op") == "subscribe" => MockReply {
+            messages:     request["args"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|arg| json!({ "event": "subscribe", "arg": arg, "connId": MOCK_CONN_ID }).to_string())
+                .collect(),
+            subscription: Some(request)
+        },
+        #[cfg(feature = "non-us")]
+        CexExchange::Binance if field("method") == "SUBSCRIBE" => {
+            MockReply { messages: vec![json!({ "result": null, "id": request["id"] }).to_string()], subscription: Some(request) }
+        }
+        #[cfg(feature = "non-us")]
+        CexExchange::Bybit if field("op") == "subscribe" => MockReply {
+            messages:     vec![json!({ "success": true, "ret_msg": "", "conn_id": MOCK_CONN_ID, "req_id": "", "op": "subscribe" }).to_string()],
+            subscription: Some(request)
+        },
+        #[cfg(feature = "non-us")]
+        CexExchange::Bybit if field("op") == "ping" => MockReply {
+            messages:     vec![json!({ "success": true, "ret_msg": "pong", "conn_id": MOCK_CONN_ID, "op": "ping" }).to_string()],
+            subscription: None
+        },
+        #[cfg(feature = "non-us")]
+        CexExchange::Kucoin if field("type") == "subscribe" => {
+            // kucoin only acks when asked to & always sends the id back as a string
+            let ack = request["response"]
+                .as_bool()
+                .unwrap_or_default()
+                .then(|| json!({ "id": id_string(&request["id"]), "type": "ack" }).to_string());
+
+            MockReply { messages: ack.into_iter().collect(), subscription: Some(request) }
+        }
+        #[cfg(feature = "non-us")]
+        CexExchange::Kucoin if field("type") == "ping" => {
+            MockReply { messages: vec![json!({ "id": id_string(&request["id"]), "type": "pong" }).to_string()], subscription: None }
+        }
+        _ => MockReply::default()
+    }
+}
+
+/// response of the kucoin bullet endpoint, pointing at the mocked ws endpoint
+pub(crate) fn kucoin_bullet_response(ws_endpoint: &str) -> String {
+    json!({
+        "code": "200000",
+        "data": {
+            "token": MOCK_KUCOIN_TOKEN,
+            "instanceServers": [{
+                "endpoint": ws_endpoint,
+                "encrypt": false,
+                "protocol": "websocket",
+                "pingInterval": 18000,
+                "pingTimeout": 10000
+            }]
+        }
+    })
+    .to_string()
+}
+
+#[cfg(feature = "non-us")]
+fn id_string(id: &Value) -> String {
+    match id {
+        Value::String(id) => id.clone(),
+        id => id.to_string()
+    }
+}
@@ -0,0 +1,7 @@
+mod fixtures;
+pub use fixtures::*;
+
+mod handshake;
+
+mod server;
+pub use server::*;
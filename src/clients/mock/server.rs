@@ -0,0 +1,396 @@
+use std::{
+    io,
+    net::SocketAddr,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, RwLock
+    },
+    time::Duration
+};
+
+use futures::{SinkExt, StreamExt};
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::watch,
+    task::JoinHandle
+};
+use tokio_tungstenite::tungstenite::{
+    protocol::{frame::coding::CloseCode, CloseFrame},
+    Message
+};
+use tracing::{debug, warn};
+
+use super::{handshake, MockFixtures};
+use crate::{CexExchange, ExchangeEnvironment};
+
+/// the exchanges require a pong within one interval of every ping
+const PING_INTERVAL: Duration = Duration::from_secs(5);
+
+const MAX_REQUEST_HEAD_BYTES: usize = 16 * 1024;
+
+/// an in-process exchange server for testing without network access
+///
+/// every exchange is served under `/<exchange>` of the same port, so pointing
+/// an exchange at [MockExchangeServer::environment] (see
+/// [Exchange::with_environment](crate::Exchange::with_environment) &
+/// [ExchangeEnvironment::Custom]) redirects all of it's rest & ws requests
+/// here
+///
+/// - rest: `GET` requests are answered from the [MockFixtures] & kucoin's
+///   bullet endpoint hands out a token for the mocked ws endpoint
+/// - ws: subscribe requests are acked like the exchange does, after which the
+///   fixture frames are sent once. the server pings every connection and
+///   closes the ones that don't pong
+pub struct MockExchangeServer {
+    addr:        SocketAddr,
+    state:       Arc<MockState>,
+    shutdown_tx: watch::Sender<bool>,
+    handle:      JoinHandle<()>
+}
+
+#[derive(Debug)]
+struct MockState {
+    fixtures:      RwLock<MockFixtures>,
+    subscriptions: Mutex<Vec<(CexExchange, Value)>>,
+    connections:   AtomicUsize,
+    pongs:         AtomicUsize,
+    closes:        AtomicUsize
+}
+
+impl MockExchangeServer {
+    /// binds to a random local port and starts serving the fixtures
+    pub async fn start(fixtures: MockFixtures) -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let state = Arc::new(MockState {
+            fixtures: RwLock::new(fixtures),
+            subscriptions: Mutex::new(Vec::new()),
+            connections: AtomicUsize::new(0),
+            pongs: AtomicUsize::new(0),
+            closes: AtomicUsize::new(0)
+        });
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let handle = tokio::spawn(accept_loop(listener, state.clone(), shutdown_rx));
+
+        debug!(target: "cex-exchanges::mock", "started mock exchange server on {addr}");
+
+        Ok(Self { addr, state, shutdown_tx, handle })
+    }
+
+    /// every exchange is served under `<base_url>/<exchange>`
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// the environment to pass to
+    /// [Exchange::with_environment](crate::Exchange::with_environment)
+    pub fn environment(&self) -> ExchangeEnvironment {
+        ExchangeEnvironment::Custom(self.base_url())
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// serves `body` for `GET <path>` from now on, ex: to list or delist
+    /// instruments between two polls (see [MockFixtures::with_rest])
+    pub fn set_rest(&self, path: &str, body: impl Into<String>) {
+        let mut fixtures = self.state.fixtures.write().unwrap();
+        *fixtures = std::mem::take(&mut *fixtures).with_rest(path, body);
+    }
+
+    /// the subscribe requests recieved for the exchange, for binance's single
+    /// stream connections this is the name of the stream
+    pub fn subscriptions(&self, exchange: CexExchange) -> Vec<Value> {
+        self.state
+            .subscriptions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(exch, _)| *exch == exchange)
+            .map(|(_, sub)| sub.clone())
+            .collect()
+    }
+
+    /// number of ws connections opened since the server was started
+    pub fn ws_connections(&self) -> usize {
+        self.state.connections.load(Ordering::SeqCst)
+    }
+
+    /// number of pongs recieved over all ws connections
+    pub fn pongs_received(&self) -> usize {
+        self.state.pongs.load(Ordering::SeqCst)
+    }
+
+    /// number of `Close` frames recieved over all ws connections
+    pub fn closes_received(&self) -> usize {
+        self.state.closes.load(Ordering::SeqCst)
+    }
+
+    /// closes every open ws connection & stops accepting new ones
+    pub fn shutdown(&self) {
+        let _ = self.shutdown_tx.send(true);
+        self.handle.abort();
+    }
+}
+
+impl Drop for MockExchangeServer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+async fn accept_loop(listener: TcpListener, state: Arc<MockState>, shutdown_rx: watch::Receiver<bool>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                warn!(target: "cex-exchanges::mock", "error accepting connection: {:?}", e);
+                continue;
+            }
+        };
+
+        let state = state.clone();
+        let shutdown_rx = shutdown_rx.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, state, shutdown_rx).await {
+                debug!(target: "cex-exchanges::mock", "connection ended with error: {:?}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(stream: TcpStream, state: Arc<MockState>, shutdown_rx: watch::Receiver<bool>) -> eyre::Result<()> {
+    let (head, head_len) = peek_request_head(&stream).await?;
+    let request = RequestHead::parse(&head).ok_or_else(|| eyre::eyre!("invalid request head: {head}"))?;
+
+    if request.is_websocket_upgrade() {
+        handle_ws(stream, request, state, shutdown_rx).await
+    } else {
+        handle_http(stream, head_len, request, state).await
+    }
+}
+
+async fn handle_http(mut stream: TcpStream, head_len: usize, request: RequestHead, state: Arc<MockState>) -> eyre::Result<()> {
+    let mut consumed = vec![0; head_len + request.content_length()];
+    stream.read_exact(&mut consumed).await?;
+
+    let (status, body) = match request.method.as_str() {
+        #[cfg(feature = "non-us")]
+        "POST" if request.path() == "/kucoin/api/v1/bullet-public" => {
+            let ws_endpoint = format!("ws://{}/kucoin/ws", stream.local_addr()?);
+            ("200 OK", handshake::kucoin_bullet_response(&ws_endpoint))
+        }
+        "GET" => match state.fixtures.read().unwrap().rest_response(&request.target) {
+            Some(body) => ("200 OK", body.clone()),
+            None => ("404 Not Found", format!(r#"{{"msg":"no mock fixture for {}"}}"#, request.target))
+        },
+        _ => ("405 Method Not Allowed", String::new())
+    };
+
+    debug!(target: "cex-exchanges::mock", "{} {} -> {status}", request.method, request.target);
+    write_http_response(&mut stream, status, &body).await
+}
+
+async fn handle_ws(mut stream: TcpStream, request: RequestHead, state: Arc<MockState>, mut shutdown_rx: watch::Receiver<bool>) -> eyre::Result<()> {
+    let path = request.path().to_string();
+    let Some(exchange) = path
+        .split('/')
+        .nth(1)
+        .and_then(|exch| CexExchange::from_str(exch).ok())
+    else {
+        return write_http_response(&mut stream, "404 Not Found", "").await
+    };
+
+    #[cfg(feature = "non-us")]
+    if exchange == CexExchange::Kucoin && request.query_param("token") != Some(handshake::MOCK_KUCOIN_TOKEN) {
+        return write_http_response(&mut stream, "401 Unauthorized", r#"{"code":"401","msg":"invalid token"}"#).await
+    }
+
+    let mut ws = tokio_tungstenite::accept_async(stream).await?;
+    state.connections.fetch_add(1, Ordering::SeqCst);
+    debug!(target: "cex-exchanges::mock", ?exchange, "opened ws connection on {path}");
+
+    if let Some(welcome) = handshake::welcome(exchange) {
+        ws.send(Message::Text(welcome)).await?;
+    }
+
+    let fixture_frames = || {
+        state
+            .fixtures
+            .read()
+            .unwrap()
+            .ws_frames(exchange)
+            .iter()
+            .cloned()
+            .map(Message::Text)
+            .collect::<Vec<_>>()
+    };
+    let mut sent_fixtures = false;
+
+    // binance's single stream connections are subscribed by the url
+    #[cfg(feature = "non-us")]
+    if let Some(stream_name) = path.strip_prefix("/binance/ws/") {
+        state
+            .subscriptions
+            .lock()
+            .unwrap()
+            .push((exchange, Value::String(stream_name.to_string())));
+        for frame in fixture_frames() {
+            ws.send(frame).await?;
+        }
+        sent_fixtures = true;
+    }
+
+    let mut ping_interval = tokio::time::interval(PING_INTERVAL);
+    let mut awaiting_pong = false;
+
+    loop {
+        tokio::select! {
+            _ = shutdown_rx.changed() => {
+                let _ = ws.close(None).await;
+                break
+            }
+            _ = ping_interval.tick() => {
+                if awaiting_pong {
+                    warn!(target: "cex-exchanges::mock", ?exchange, "no pong recieved, closing the connection");
+                    let _ = ws.close(Some(CloseFrame { code: CloseCode::Policy, reason: "pong timeout".into() })).await;
+                    break
+                }
+
+                ws.send(Message::Ping(b"mock".to_vec())).await?;
+                awaiting_pong = true;
+            }
+            msg = ws.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    let reply = handshake::reply(exchange, &text);
+                    for msg in reply.messages {
+                        ws.send(Message::Text(msg)).await?;
+                    }
+
+                    if let Some(subscription) = reply.subscription {
+                        state.subscriptions.lock().unwrap().push((exchange, subscription));
+                        if !sent_fixtures {
+                            for frame in fixture_frames() {
+                                ws.send(frame).await?;
+                            }
+                            sent_fixtures = true;
+                        }
+                    }
+                }
+                Some(Ok(Message::Pong(_))) => {
+                    state.pongs.fetch_add(1, Ordering::SeqCst);
+                    awaiting_pong = false;
+                }
+                Some(Ok(Message::Close(_))) => {
+                    state.closes.fetch_add(1, Ordering::SeqCst);
+                    break
+                }
+                None => break,
+                Some(Ok(_)) => (),
+                Some(Err(e)) => return Err(e.into())
+            }
+        }
+    }
+
+    debug!(target: "cex-exchanges::mock", ?exchange, "closed ws connection on {path}");
+
+    Ok(())
+}
+
+/// peeks (without consuming) the request line & headers, so websocket
+/// upgrades can be handed to the handshake as is
+async fn peek_request_head(stream: &TcpStream) -> io::Result<(String, usize)> {
+    let mut buf = vec![0; MAX_REQUEST_HEAD_BYTES];
+    loop {
+        let read = stream.peek(&mut buf).await?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into())
+        }
+
+        if let Some(end) = buf[..read].windows(4).position(|w| w == b"\r\n\r\n") {
+            return Ok((String::from_utf8_lossy(&buf[..end]).into_owned(), end + 4))
+        }
+
+        if read == buf.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "request head too large"))
+        }
+
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+}
+
+async fn write_http_response(stream: &mut TcpStream, status: &str, body: &str) -> eyre::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+#[derive(Debug)]
+struct RequestHead {
+    method:  String,
+    /// path & query
+    target:  String,
+    /// lowercase names
+    headers: Vec<(String, String)>
+}
+
+impl RequestHead {
+    fn parse(head: &str) -> Option<Self> {
+        let mut lines = head.lines();
+        let mut request_line = lines.next()?.split_whitespace();
+        let method = request_line.next()?.to_string();
+        let target = request_line.next()?.to_string();
+
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+            .collect();
+
+        Some(Self { method, target, headers })
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn path(&self) -> &str {
+        self.target
+            .split_once('?')
+            .map_or(self.target.as_str(), |(path, _)| path)
+    }
+
+    #[cfg(feature = "non-us")]
+    fn query_param(&self, name: &str) -> Option<&str> {
+        self.target
+            .split_once('?')?
+            .1
+            .split('&')
+            .filter_map(|param| param.split_once('='))
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v)
+    }
+
+    fn content_length(&self) -> usize {
+        self.header("content-length")
+            .and_then(|len| len.parse().ok())
+            .unwrap_or_default()
+    }
+
+    fn is_websocket_upgrade(&self) -> bool {
+        self.header("upgrade")
+            .is_some_and(|upgrade| upgrade.eq_ignore_ascii_case("websocket"))
+    }
+}
@@ -1,2 +1,4 @@
+#[cfg(feature = "mock")]
+pub mod mock;
 pub mod rest_api;
 pub mod ws;
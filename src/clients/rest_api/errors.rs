@@ -1,11 +1,15 @@
 use thiserror::Error;
 
+use crate::UnsupportedEnvironment;
+
 #[derive(Debug, Error)]
 pub enum RestApiError {
     #[error("failed to deserialize the message: {0}")]
     DeserializingError(#[from] serde_json::Error),
     #[error("error sending request: {0}")]
-    ReqwestError(#[from] reqwest::Error)
+    ReqwestError(#[from] reqwest::Error),
+    #[error(transparent)]
+    UnsupportedEnvironment(#[from] UnsupportedEnvironment)
 }
 
 impl RestApiError {
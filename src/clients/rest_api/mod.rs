@@ -1,27 +1,43 @@
 mod errors;
 pub use errors::*;
 
-use crate::{exchanges::normalized::rest_api::CombinedRestApiResponse, normalized::rest_api::NormalizedRestApiRequest, Exchange};
+use crate::{exchanges::normalized::rest_api::CombinedRestApiResponse, normalized::rest_api::NormalizedRestApiRequest, Exchange, ExchangeEnvironment};
 
 #[derive(Debug, Default)]
 pub struct ExchangeApi {
-    web_client: reqwest::Client
+    web_client:  reqwest::Client,
+    environment: ExchangeEnvironment
 }
 
 impl ExchangeApi {
     pub fn new() -> Self {
-        Self { web_client: reqwest::Client::new() }
+        Self { web_client: reqwest::Client::new(), environment: ExchangeEnvironment::Production }
+    }
+
+    /// sends every request to the exchange's `environment`, see
+    /// [Exchange::with_environment]
+    pub fn with_environment(mut self, environment: ExchangeEnvironment) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    pub fn environment(&self) -> &ExchangeEnvironment {
+        &self.environment
     }
 
     pub async fn all_currencies<E: Exchange>(&self) -> Result<CombinedRestApiResponse, RestApiError> {
-        Ok(E::rest_api_call(&E::default(), &self.web_client, NormalizedRestApiRequest::AllCurrencies)
+        Ok(E::rest_api_call(&self.exchange(), &self.web_client, NormalizedRestApiRequest::AllCurrencies)
             .await?
             .into())
     }
 
     pub async fn all_instruments<E: Exchange>(&self) -> Result<CombinedRestApiResponse, RestApiError> {
-        Ok(E::rest_api_call(&E::default(), &self.web_client, NormalizedRestApiRequest::AllInstruments)
+        Ok(E::rest_api_call(&self.exchange(), &self.web_client, NormalizedRestApiRequest::AllInstruments)
             .await?
             .into())
     }
+
+    fn exchange<E: Exchange>(&self) -> E {
+        E::default().with_environment(self.environment.clone())
+    }
 }
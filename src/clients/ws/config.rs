@@ -1,15 +1,34 @@
-#[derive(Debug, Default, Copy, Clone)]
+use serde::{Deserialize, Serialize};
+
+use super::WsRecorderConfig;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct WsStreamConfig {
-    pub max_retries: Option<u64>
+    pub max_retries: Option<u64>,
+    /// registers the stream's counters in [WsMetrics::global](super::WsMetrics::global)
+    pub metrics:     bool,
+    /// records every raw text frame before it's deserialized
+    pub recorder:    Option<WsRecorderConfig>
 }
 
 impl WsStreamConfig {
     pub fn new(max_retries: Option<u64>) -> Self {
-        Self { max_retries }
+        Self { max_retries, metrics: false, recorder: None }
+    }
+
+    pub fn with_metrics(mut self) -> Self {
+        self.metrics = true;
+        self
     }
 
     pub fn with_max_retries(mut self, max_retries: u64) -> Self {
         self.max_retries = Some(max_retries);
         self
     }
+
+    pub fn with_recorder(mut self, recorder: WsRecorderConfig) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
 }
@@ -0,0 +1,84 @@
+use std::io::{self, Read};
+
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+
+/// compression used by an exchange for it's binary ws frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PayloadCompression {
+    /// plain utf-8 bytes
+    None,
+    Gzip,
+    /// zlib wrapped deflate
+    Zlib,
+    /// raw deflate without a header (ex: okex)
+    Deflate,
+    /// detects gzip & zlib from the header, otherwise tries utf-8 and then
+    /// raw deflate
+    Auto,
+}
+
+impl PayloadCompression {
+    /// decodes a binary ws frame into the text passed to the json deserializer
+    pub fn decode(self, payload: &[u8]) -> io::Result<String> {
+        match self {
+            PayloadCompression::None => String::from_utf8(payload.to_vec()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            PayloadCompression::Gzip => Self::inflate(GzDecoder::new(payload)),
+            PayloadCompression::Zlib => Self::inflate(ZlibDecoder::new(payload)),
+            PayloadCompression::Deflate => Self::inflate(DeflateDecoder::new(payload)),
+            PayloadCompression::Auto => Self::detect(payload).decode(payload),
+        }
+    }
+
+    fn detect(payload: &[u8]) -> Self {
+        match payload {
+            [0x1f, 0x8b, ..] => PayloadCompression::Gzip,
+            [0x78, 0x01 | 0x5e | 0x9c | 0xda, ..] => PayloadCompression::Zlib,
+            _ if std::str::from_utf8(payload).is_ok() => PayloadCompression::None,
+            _ => PayloadCompression::Deflate,
+        }
+    }
+
+    fn inflate<R: Read>(mut decoder: R) -> io::Result<String> {
+        let mut out = String::new();
+        decoder.read_to_string(&mut out)?;
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{
+        write::{DeflateEncoder, GzEncoder, ZlibEncoder},
+        Compression,
+    };
+
+    use super::*;
+
+    const MSG: &str = r#"{"arg":{"channel":"trades","instId":"BTC-USDT"},"data":[]}"#;
+
+    #[test]
+    fn test_decode_auto() {
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(MSG.as_bytes()).unwrap();
+        assert_eq!(PayloadCompression::Auto.decode(&gz.finish().unwrap()).unwrap(), MSG);
+
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(MSG.as_bytes()).unwrap();
+        assert_eq!(PayloadCompression::Auto.decode(&zlib.finish().unwrap()).unwrap(), MSG);
+
+        let mut deflate = DeflateEncoder::new(Vec::new(), Compression::default());
+        deflate.write_all(MSG.as_bytes()).unwrap();
+        assert_eq!(PayloadCompression::Auto.decode(&deflate.finish().unwrap()).unwrap(), MSG);
+
+        assert_eq!(PayloadCompression::Auto.decode(MSG.as_bytes()).unwrap(), MSG);
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(PayloadCompression::Gzip.decode(MSG.as_bytes()).is_err());
+        assert!(PayloadCompression::None.decode(&[0xff, 0xfe]).is_err());
+    }
+}
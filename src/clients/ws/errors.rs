@@ -1,6 +1,10 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio_tungstenite::tungstenite;
 
-use crate::{exchanges::normalized::ws::CombinedWsMessage, CexExchange};
+use crate::{exchanges::normalized::ws::CombinedWsMessage, CexExchange, UnsupportedEnvironment};
 
 #[derive(Debug, Error)]
 pub enum WsError {
@@ -10,19 +14,41 @@ pub enum WsError {
     WebInitializationError(String),
     #[error("failed to deserialize the message: {0}")]
     DeserializingError(#[from] serde_json::Error),
+    #[error("failed to decode the binary message: {0}")]
+    DecodingError(#[from] std::io::Error),
     #[error("recieved an error from the ws: {0}")]
     StreamRxError(tokio_tungstenite::tungstenite::Error),
     #[error("error sending value to the ws: {0}")]
     StreamTxError(tokio_tungstenite::tungstenite::Error),
     #[error("stream was terminated")]
-    StreamTerminated
+    StreamTerminated,
+    #[error(transparent)]
+    UnsupportedEnvironment(#[from] UnsupportedEnvironment)
 }
 
 impl WsError {
+    pub fn kind(&self) -> WsErrorKind {
+        match self {
+            WsError::ConnectionError(e) | WsError::StreamRxError(e) | WsError::StreamTxError(e) => WsErrorKind::from_tungstenite(e),
+            WsError::WebInitializationError(_) | WsError::StreamTerminated => WsErrorKind::Transport,
+            WsError::DeserializingError(_) | WsError::DecodingError(_) => WsErrorKind::Decode,
+            WsError::UnsupportedEnvironment(_) => WsErrorKind::Config
+        }
+    }
+
     pub fn normalized_with_exchange(self, exchange: CexExchange, raw_message: Option<String>) -> CombinedWsMessage {
-        let bad_pair = CexExchange::bad_pair(exchange, self.to_string());
+        let raw_message = raw_message.unwrap_or_default();
+        let mut kind = self.kind();
 
-        CombinedWsMessage::Disconnect { exchange, message: self.to_string(), raw_message: raw_message.unwrap_or_default(), bad_pair }
+        // only messages sent by the exchange can name a rejected pair
+        let bad_pair = if kind == WsErrorKind::Decode { CexExchange::bad_pair(exchange, raw_message.clone()) } else { None };
+        if bad_pair.is_some() {
+            kind = WsErrorKind::InvalidSymbol;
+        } else if kind == WsErrorKind::Decode {
+            kind = WsErrorKind::from_exchange_message(exchange, &raw_message).unwrap_or(kind);
+        }
+
+        CombinedWsMessage::Disconnect { exchange, kind, message: self.to_string(), raw_message, bad_pair }
     }
 }
 
@@ -31,3 +57,189 @@ impl WsError {
 //         Self::ConnectionError(value.to_string())
 //     }
 // }
+
+/// classification of ws errors for retry policies and alerting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WsErrorKind {
+    /// connection failures, io/tls errors and closed or timed out streams
+    Transport,
+    /// invalid ws frames or handshakes
+    Protocol,
+    /// rejected credentials or permissions
+    Auth,
+    /// includes http 403, which binance's waf sends when it throttles
+    RateLimited,
+    /// the exchange rejected a trading pair in the subscription
+    InvalidSymbol,
+    ExchangeMaintenance,
+    /// the message could not be decoded or deserialized
+    Decode,
+    /// the exchange can't connect with the way it was configured (ex: an
+    /// unsupported [ExchangeEnvironment](crate::ExchangeEnvironment))
+    Config
+}
+
+impl WsErrorKind {
+    /// false if reconnecting with the same subscription can't fix the error
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, WsErrorKind::Auth | WsErrorKind::Config)
+    }
+
+    fn from_tungstenite(err: &tungstenite::Error) -> Self {
+        match err {
+            tungstenite::Error::Http(response) => match response.status().as_u16() {
+                401 => WsErrorKind::Auth,
+                403 | 418 | 429 => WsErrorKind::RateLimited,
+                503 => WsErrorKind::ExchangeMaintenance,
+                _ => WsErrorKind::Transport
+            },
+            tungstenite::Error::Utf8 => WsErrorKind::Decode,
+            tungstenite::Error::Protocol(_)
+            | tungstenite::Error::Capacity(_)
+            | tungstenite::Error::AttackAttempt
+            | tungstenite::Error::Url(_)
+            | tungstenite::Error::HttpFormat(_)
+            | tungstenite::Error::WriteBufferFull(_) => WsErrorKind::Protocol,
+            _ => WsErrorKind::Transport
+        }
+    }
+
+    /// classifies an error message sent by the exchange, `None` if the message
+    /// doesn't match a known kind
+    ///
+    /// only the exchange's error code can classify a message as
+    /// [WsErrorKind::Auth], the text is matched for the retryable kinds
+    pub fn from_exchange_message(exchange: CexExchange, msg: &str) -> Option<Self> {
+        if let Some(kind) = error_code(msg).and_then(|code| Self::from_exchange_code(exchange, code)) {
+            return Some(kind)
+        }
+
+        let msg = msg.to_lowercase();
+        let contains_any = |patterns: &[&str]| patterns.iter().any(|p| msg.contains(p));
+
+        if contains_any(&["too many", "rate limit", "too frequent", "exceeded"]) {
+            Some(WsErrorKind::RateLimited)
+        } else if contains_any(&["maintenance", "unavailable", "upgrading"]) {
+            Some(WsErrorKind::ExchangeMaintenance)
+        } else if contains_any(&["invalid symbol", "doesn't exist", "does not exist", "delisted", "unknown product"]) {
+            Some(WsErrorKind::InvalidSymbol)
+        } else {
+            None
+        }
+    }
+
+    /// classifies the documented ws error codes of the exchange
+    fn from_exchange_code(exchange: CexExchange, code: i64) -> Option<Self> {
+        let kind = match exchange {
+            #[cfg(feature = "us")]
+            CexExchange::Okex => match code {
+                60004 | 60005 | 60006 | 60007 | 60009 | 60011 | 60024 | 60032 => WsErrorKind::Auth,
+                60014 | 50011 => WsErrorKind::RateLimited,
+                60018 => WsErrorKind::InvalidSymbol,
+                50001 | 64008 => WsErrorKind::ExchangeMaintenance,
+                _ => return None
+            },
+            #[cfg(feature = "non-us")]
+            CexExchange::Binance => match code {
+                -2015 | -2014 | -1002 => WsErrorKind::Auth,
+                -1003 => WsErrorKind::RateLimited,
+                -1121 => WsErrorKind::InvalidSymbol,
+                _ => return None
+            },
+            #[cfg(feature = "non-us")]
+            CexExchange::Kucoin => match code {
+                401 => WsErrorKind::Auth,
+                403 | 429 | 509 => WsErrorKind::RateLimited,
+                404 => WsErrorKind::InvalidSymbol,
+                _ => return None
+            },
+            _ => return None
+        };
+
+        Some(kind)
+    }
+}
+
+/// the `code` (or `error.code`) field of a json error message, as a number or
+/// a string
+fn error_code(msg: &str) -> Option<i64> {
+    let value = serde_json::from_str::<serde_json::Value>(&msg[msg.find('{')?..]).ok()?;
+    let code = value
+        .get("code")
+        .or_else(|| value.get("error")?.get("code"))?;
+
+    code.as_i64()
+        .or_else(|| code.as_str()?.parse().ok())
+}
+
+impl Display for WsErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WsErrorKind::Transport => write!(f, "transport"),
+            WsErrorKind::Protocol => write!(f, "protocol"),
+            WsErrorKind::Auth => write!(f, "auth"),
+            WsErrorKind::RateLimited => write!(f, "rate-limited"),
+            WsErrorKind::InvalidSymbol => write!(f, "invalid-symbol"),
+            WsErrorKind::ExchangeMaintenance => write!(f, "exchange-maintenance"),
+            WsErrorKind::Decode => write!(f, "decode"),
+            WsErrorKind::Config => write!(f, "config")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_kind() {
+        assert_eq!(WsError::StreamTerminated.kind(), WsErrorKind::Transport);
+        assert_eq!(WsError::StreamRxError(tungstenite::Error::ConnectionClosed).kind(), WsErrorKind::Transport);
+        assert_eq!(WsError::StreamRxError(tungstenite::Error::AttackAttempt).kind(), WsErrorKind::Protocol);
+
+        let http_kind = |status: u16| {
+            let response = tungstenite::http::Response::builder()
+                .status(status)
+                .body(None)
+                .unwrap();
+            WsError::ConnectionError(tungstenite::Error::Http(response)).kind()
+        };
+        assert_eq!(http_kind(429), WsErrorKind::RateLimited);
+        // a throttle of binance's waf, not a rejected key
+        assert_eq!(http_kind(403), WsErrorKind::RateLimited);
+        assert!(http_kind(451).is_retryable());
+        assert_eq!(http_kind(401), WsErrorKind::Auth);
+
+        let des_err = serde_json::from_str::<u64>("{}").unwrap_err();
+        assert_eq!(WsError::DeserializingError(des_err).kind(), WsErrorKind::Decode);
+    }
+
+    #[test]
+    #[cfg(feature = "us")]
+    fn test_from_exchange_message() {
+        let exchange = CexExchange::Okex;
+        assert_eq!(
+            WsErrorKind::from_exchange_message(exchange, "Wrong URL or channel:tickers,instId:NMR-USDT doesn't exist."),
+            Some(WsErrorKind::InvalidSymbol)
+        );
+        assert_eq!(WsErrorKind::from_exchange_message(exchange, "Too Many Requests"), Some(WsErrorKind::RateLimited));
+        assert_eq!(WsErrorKind::from_exchange_message(exchange, "System maintenance"), Some(WsErrorKind::ExchangeMaintenance));
+        assert_eq!(WsErrorKind::from_exchange_message(exchange, "{\"type\":\"subscriptions\"}"), None);
+
+        // free text never closes the stream
+        assert_eq!(WsErrorKind::from_exchange_message(exchange, "Please login or check the api key permission"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "us")]
+    fn test_from_exchange_code() {
+        let login = r#"{"event":"error","msg":"Login failed.","code":"60009","connId":"a4d3ae55"}"#;
+        assert_eq!(WsErrorKind::from_exchange_message(CexExchange::Okex, login), Some(WsErrorKind::Auth));
+        assert_eq!(WsErrorKind::from_exchange_message(CexExchange::Coinbase, login), None);
+
+        let upgrade = r#"some prefix - {"event":"notice","msg":"The connection will soon be closed","code":"64008"}"#;
+        assert_eq!(WsErrorKind::from_exchange_message(CexExchange::Okex, upgrade), Some(WsErrorKind::ExchangeMaintenance));
+        assert!(WsErrorKind::from_exchange_message(CexExchange::Okex, login)
+            .is_some_and(|kind| !kind.is_retryable()));
+    }
+}
@@ -0,0 +1,103 @@
+use std::thread::JoinHandle;
+
+use tokio::sync::watch;
+use tracing::{error, info};
+
+/// handle to the threads spawned by
+/// [MultiWsStreamBuilder::spawn_multithreaded](super::MultiWsStreamBuilder::spawn_multithreaded)
+/// and
+/// [NormalizedExchangeBuilder::build_all_multithreaded](crate::normalized::ws::NormalizedExchangeBuilder::build_all_multithreaded)
+///
+/// dropping the handle does NOT stop the threads, call
+/// [WsThreadHandle::shutdown] to close the streams
+#[derive(Debug)]
+pub struct WsThreadHandle {
+    shutdown_tx: watch::Sender<bool>,
+    threads:     Vec<JoinHandle<eyre::Result<()>>>,
+}
+
+impl WsThreadHandle {
+    pub(crate) fn new() -> Self {
+        let (shutdown_tx, _) = watch::channel(false);
+        Self { shutdown_tx, threads: Vec::new() }
+    }
+
+    pub(crate) fn shutdown_rx(&self) -> watch::Receiver<bool> {
+        self.shutdown_tx.subscribe()
+    }
+
+    pub(crate) fn push_thread(&mut self, thread: JoinHandle<eyre::Result<()>>) {
+        self.threads.push(thread);
+    }
+
+    /// number of spawned threads
+    pub fn thread_count(&self) -> usize {
+        self.threads.len()
+    }
+
+    /// true if every spawned thread has exited
+    pub fn is_finished(&self) -> bool {
+        self.threads.iter().all(|t| t.is_finished())
+    }
+
+    /// signals every thread to send a `Close` frame on its connections and
+    /// exit, does not wait for the threads to finish
+    pub fn shutdown(&self) {
+        info!(target: "cex-exchanges::live-stream", "shutting down {} stream threads", self.threads.len());
+        self.shutdown_tx.send_replace(true);
+    }
+
+    /// blocks until every thread has exited, returning the first error
+    /// encountered (all errors are logged)
+    pub fn join(self) -> eyre::Result<()> {
+        let mut res = Ok(());
+
+        self.threads.into_iter().for_each(|thread| {
+            let thread_res = thread
+                .join()
+                .unwrap_or_else(|_| Err(eyre::eyre!("stream thread panicked")));
+
+            if let Err(e) = thread_res {
+                error!(target: "cex-exchanges::live-stream", "stream thread exited with an error: {:?}", e);
+                if res.is_ok() {
+                    res = Err(e);
+                }
+            }
+        });
+
+        res
+    }
+
+    /// signals the threads to shutdown and waits for them to exit without
+    /// blocking the current runtime
+    pub async fn shutdown_and_join(self) -> eyre::Result<()> {
+        self.shutdown();
+        tokio::task::spawn_blocking(move || self.join()).await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_surfaces_errors() {
+        let mut handle = WsThreadHandle::new();
+
+        let mut shutdown_rx = handle.shutdown_rx();
+        handle.push_thread(std::thread::spawn(move || {
+            while !*shutdown_rx.borrow_and_update() {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            Ok(())
+        }));
+        handle.push_thread(std::thread::spawn(|| Err(eyre::eyre!("bad thread"))));
+
+        assert_eq!(handle.thread_count(), 2);
+
+        handle.shutdown();
+        let res = handle.join();
+
+        assert_eq!(res.unwrap_err().to_string(), "bad thread");
+    }
+}
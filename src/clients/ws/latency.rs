@@ -0,0 +1,162 @@
+use std::{collections::HashMap, time::Instant};
+
+use chrono::{DateTime, Utc};
+
+use crate::{exchanges::normalized::ws::CombinedWsMessage, CexExchange};
+
+/// upper bounds (in ms) of the [LatencyHistogram] buckets, the last bucket is
+/// unbounded
+pub const LATENCY_BUCKETS_MS: [i64; 14] = [1, 2, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000, 30000];
+
+/// local time a message was recieved from the ws, stamped before the message
+/// is deserialized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ReceiveTime {
+    /// monotonic time, use for intervals between messages
+    pub monotonic: Instant,
+    /// wall-clock time, use to compare against exchange timestamps
+    pub wall:      DateTime<Utc>,
+}
+
+impl ReceiveTime {
+    pub fn now() -> Self {
+        Self { monotonic: Instant::now(), wall: Utc::now() }
+    }
+
+    /// milliseconds between the exchange timestamp and the time the message
+    /// was recieved
+    ///
+    /// a negative value means the exchange's clock is ahead of the local clock
+    pub fn latency_ms(&self, exchange_time: DateTime<Utc>) -> i64 {
+        (self.wall - exchange_time).num_milliseconds()
+    }
+}
+
+impl Default for ReceiveTime {
+    fn default() -> Self {
+        Self::now()
+    }
+}
+
+/// histogram of the feed delay (recieve time - exchange time) in ms
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// counts for each bound in [LATENCY_BUCKETS_MS] + the unbounded bucket
+    pub buckets:  [u64; LATENCY_BUCKETS_MS.len() + 1],
+    /// samples where the exchange time was ahead of the recieve time (clock
+    /// skew)
+    pub negative: u64,
+    pub count:    u64,
+    pub sum_ms:   i64,
+    pub min_ms:   Option<i64>,
+    pub max_ms:   Option<i64>,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self { buckets: [0; LATENCY_BUCKETS_MS.len() + 1], negative: 0, count: 0, sum_ms: 0, min_ms: None, max_ms: None }
+    }
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency_ms: i64) {
+        self.count += 1;
+        self.sum_ms += latency_ms;
+        self.min_ms = Some(self.min_ms.map_or(latency_ms, |m| m.min(latency_ms)));
+        self.max_ms = Some(self.max_ms.map_or(latency_ms, |m| m.max(latency_ms)));
+
+        if latency_ms < 0 {
+            self.negative += 1;
+        }
+
+        let idx = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| latency_ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.buckets[idx] += 1;
+    }
+
+    pub fn mean_ms(&self) -> Option<f64> {
+        (self.count != 0).then(|| self.sum_ms as f64 / self.count as f64)
+    }
+
+    /// upper bound of the bucket containing the quantile `q` (0.0 - 1.0),
+    /// `None` if there are no samples or the quantile is in the unbounded
+    /// bucket
+    pub fn quantile_ms(&self, q: f64) -> Option<i64> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let target = (q.clamp(0.0, 1.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (idx, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return LATENCY_BUCKETS_MS.get(idx).copied();
+            }
+        }
+
+        None
+    }
+}
+
+/// per-exchange latency histograms
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeLatencies {
+    histograms: HashMap<CexExchange, LatencyHistogram>,
+}
+
+impl ExchangeLatencies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, exchange: CexExchange, receive_time: &ReceiveTime, exchange_time: DateTime<Utc>) {
+        self.histograms
+            .entry(exchange)
+            .or_default()
+            .record(receive_time.latency_ms(exchange_time));
+    }
+
+    /// records the latency of a message, ignoring messages without a recieve
+    /// or exchange timestamp
+    pub fn record_message(&mut self, msg: &CombinedWsMessage) {
+        if let Some(receive_time) = msg.receive_time() {
+            if let Some(exchange_time) = msg.exchange_time() {
+                self.record(msg.exchange(), &receive_time, exchange_time);
+            }
+        }
+    }
+
+    pub fn get(&self, exchange: CexExchange) -> Option<&LatencyHistogram> {
+        self.histograms.get(&exchange)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&CexExchange, &LatencyHistogram)> {
+        self.histograms.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_record() {
+        let mut hist = LatencyHistogram::default();
+        [-3, 0, 4, 40, 40, 60000].into_iter().for_each(|l| hist.record(l));
+
+        assert_eq!(hist.count, 6);
+        assert_eq!(hist.negative, 1);
+        assert_eq!(hist.min_ms, Some(-3));
+        assert_eq!(hist.max_ms, Some(60000));
+        assert_eq!(hist.buckets[0], 2);
+        assert_eq!(hist.buckets[2], 1);
+        assert_eq!(hist.buckets[5], 2);
+        assert_eq!(hist.buckets[LATENCY_BUCKETS_MS.len()], 1);
+        assert_eq!(hist.quantile_ms(0.5), Some(5));
+        assert_eq!(hist.quantile_ms(0.8), Some(50));
+        assert_eq!(hist.quantile_ms(1.0), None);
+    }
+}
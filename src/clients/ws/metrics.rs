@@ -0,0 +1,392 @@
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    task::JoinHandle,
+};
+use tracing::{debug, error, info};
+
+use crate::CexExchange;
+
+static GLOBAL_METRICS: OnceLock<WsMetrics> = OnceLock::new();
+
+/// window of the `messages_per_sec` rate
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// (name, type, help, value) of a prometheus metric
+type PrometheusMetric = (&'static str, &'static str, &'static str, fn(&ConnectionMetricsSnapshot) -> Option<f64>);
+
+/// registry of the metrics of every [WsStream](super::WsStream) built with
+/// [WsStreamConfig::with_metrics](super::WsStreamConfig::with_metrics)
+#[derive(Debug, Default)]
+pub struct WsMetrics {
+    next_connection_id: AtomicU64,
+    connections:        Mutex<HashMap<u64, Arc<ConnectionMetrics>>>,
+}
+
+impl WsMetrics {
+    /// the registry shared by all streams
+    pub fn global() -> &'static Self {
+        GLOBAL_METRICS.get_or_init(Self::default)
+    }
+
+    /// allocates a new process-wide connection id
+    pub(crate) fn next_connection_id(&self) -> u64 {
+        self.next_connection_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    pub(crate) fn register(&self, exchange: CexExchange) -> Arc<ConnectionMetrics> {
+        let connection_id = self.next_connection_id();
+        let metrics = Arc::new(ConnectionMetrics::new(exchange, connection_id));
+        self.connections
+            .lock()
+            .unwrap()
+            .insert(connection_id, metrics.clone());
+
+        metrics
+    }
+
+    pub(crate) fn unregister(&self, connection_id: u64) {
+        self.connections.lock().unwrap().remove(&connection_id);
+    }
+
+    /// takes a snapshot of all live connections
+    ///
+    /// `messages_per_sec` is the rate over the last complete window of
+    /// [RATE_WINDOW], so snapshots don't reset each other's rate
+    pub fn snapshot(&self) -> WsMetricsSnapshot {
+        let mut connections = self
+            .connections
+            .lock()
+            .unwrap()
+            .values()
+            .map(|c| c.snapshot())
+            .collect::<Vec<_>>();
+        connections.sort_by_key(|c| (c.exchange, c.connection_id));
+
+        WsMetricsSnapshot { taken_at: Utc::now(), connections }
+    }
+
+    /// serves the snapshot in the prometheus text format on `GET /metrics`
+    pub async fn serve_prometheus(&'static self, addr: SocketAddr) -> std::io::Result<JoinHandle<()>> {
+        let listener = TcpListener::bind(addr).await?;
+        info!(target: "cex-exchanges::metrics", "serving prometheus metrics on {}", listener.local_addr()?);
+
+        Ok(tokio::spawn(async move {
+            loop {
+                let (mut socket, peer) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        error!(target: "cex-exchanges::metrics", "error accepting connection: {:?}", e);
+                        continue;
+                    }
+                };
+
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    let n = socket.read(&mut buf).await.unwrap_or_default();
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    debug!(target: "cex-exchanges::metrics", "request from {peer}: {:?}", request.lines().next());
+
+                    let response = if request.starts_with("GET /metrics ") {
+                        let body = self.snapshot().to_prometheus();
+                        format!("HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len())
+                    } else {
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+                    };
+
+                    if let Err(e) = socket.write_all(response.as_bytes()).await {
+                        error!(target: "cex-exchanges::metrics", "error writing response to {peer}: {:?}", e);
+                    }
+                });
+            }
+        }))
+    }
+}
+
+/// counters for a single ws connection
+#[derive(Debug)]
+pub struct ConnectionMetrics {
+    exchange:                 CexExchange,
+    connection_id:            u64,
+    messages:                 AtomicU64,
+    bytes:                    AtomicU64,
+    reconnects:               AtomicU64,
+    retry_count:              AtomicU64,
+    removed_bad_pairs:        AtomicU64,
+    deserialization_failures: AtomicU64,
+    last_message:             Mutex<Option<Instant>>,
+    rate:                     Mutex<RateSampler>,
+}
+
+impl ConnectionMetrics {
+    fn new(exchange: CexExchange, connection_id: u64) -> Self {
+        Self {
+            exchange,
+            connection_id,
+            messages: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+            reconnects: AtomicU64::new(0),
+            retry_count: AtomicU64::new(0),
+            removed_bad_pairs: AtomicU64::new(0),
+            deserialization_failures: AtomicU64::new(0),
+            last_message: Mutex::new(None),
+            rate: Mutex::new(RateSampler::new(Instant::now())),
+        }
+    }
+
+    pub fn connection_id(&self) -> u64 {
+        self.connection_id
+    }
+
+    pub(crate) fn record_message(&self, bytes: usize) {
+        let now = Instant::now();
+        let messages = self.messages.fetch_add(1, Ordering::Relaxed) + 1;
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        *self.last_message.lock().unwrap() = Some(now);
+        self.rate.lock().unwrap().record(messages, now);
+    }
+
+    pub(crate) fn record_reconnect(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_retry_count(&self, retry_count: u64) {
+        self.retry_count.store(retry_count, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_removed_bad_pair(&self) {
+        self.removed_bad_pairs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_deserialization_failure(&self) {
+        self.deserialization_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> ConnectionMetricsSnapshot {
+        let now = Instant::now();
+        let messages = self.messages.load(Ordering::Relaxed);
+        let messages_per_sec = self.rate.lock().unwrap().messages_per_sec(messages, now);
+
+        ConnectionMetricsSnapshot {
+            exchange: self.exchange,
+            connection_id: self.connection_id,
+            messages,
+            messages_per_sec,
+            bytes: self.bytes.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            retry_count: self.retry_count.load(Ordering::Relaxed),
+            removed_bad_pairs: self.removed_bad_pairs.load(Ordering::Relaxed),
+            deserialization_failures: self.deserialization_failures.load(Ordering::Relaxed),
+            last_message_age: self.last_message.lock().unwrap().map(|t| now.duration_since(t)),
+        }
+    }
+}
+
+/// messages/sec over windows of [RATE_WINDOW] that are rolled by the recieved
+/// messages, so reading the rate doesn't change it
+#[derive(Debug, Clone, Copy)]
+struct RateSampler {
+    window_start:    Instant,
+    /// messages recieved before the window
+    window_messages: u64,
+    /// rate of the last complete window
+    rate:            Option<f64>,
+}
+
+impl RateSampler {
+    fn new(now: Instant) -> Self {
+        Self { window_start: now, window_messages: 0, rate: None }
+    }
+
+    fn record(&mut self, messages: u64, now: Instant) {
+        let elapsed = now.duration_since(self.window_start);
+        if elapsed >= RATE_WINDOW {
+            self.rate = Some(messages.saturating_sub(self.window_messages) as f64 / elapsed.as_secs_f64());
+            self.window_start = now;
+            self.window_messages = messages;
+        }
+    }
+
+    /// the rate of the last complete window, or of the current window if it
+    /// is the first one or has been open for longer than a window (ex: the
+    /// connection stopped recieving messages)
+    fn messages_per_sec(&self, messages: u64, now: Instant) -> f64 {
+        let elapsed = now.duration_since(self.window_start);
+        match self.rate {
+            Some(rate) if elapsed < RATE_WINDOW => rate,
+            _ if elapsed.is_zero() => 0.0,
+            _ => messages.saturating_sub(self.window_messages) as f64 / elapsed.as_secs_f64(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ConnectionMetricsSnapshot {
+    pub exchange:                 CexExchange,
+    pub connection_id:            u64,
+    pub messages:                 u64,
+    pub messages_per_sec:         f64,
+    pub bytes:                    u64,
+    pub reconnects:               u64,
+    pub retry_count:              u64,
+    pub removed_bad_pairs:        u64,
+    pub deserialization_failures: u64,
+    /// `None` if no messages have been recieved
+    pub last_message_age:         Option<Duration>,
+}
+
+/// [ConnectionMetricsSnapshot]s summed over all connections of an exchange
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ExchangeMetricsSnapshot {
+    pub connections:              usize,
+    pub messages:                 u64,
+    pub messages_per_sec:         f64,
+    pub bytes:                    u64,
+    pub reconnects:               u64,
+    pub retry_count:              u64,
+    pub removed_bad_pairs:        u64,
+    pub deserialization_failures: u64,
+    /// age of the most recent message on any connection
+    pub last_message_age:         Option<Duration>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct WsMetricsSnapshot {
+    pub taken_at:    DateTime<Utc>,
+    pub connections: Vec<ConnectionMetricsSnapshot>,
+}
+
+impl WsMetricsSnapshot {
+    pub fn by_exchange(&self) -> HashMap<CexExchange, ExchangeMetricsSnapshot> {
+        let mut exchanges: HashMap<CexExchange, ExchangeMetricsSnapshot> = HashMap::new();
+
+        self.connections.iter().for_each(|conn| {
+            let exch = exchanges.entry(conn.exchange).or_default();
+            exch.connections += 1;
+            exch.messages += conn.messages;
+            exch.messages_per_sec += conn.messages_per_sec;
+            exch.bytes += conn.bytes;
+            exch.reconnects += conn.reconnects;
+            exch.retry_count += conn.retry_count;
+            exch.removed_bad_pairs += conn.removed_bad_pairs;
+            exch.deserialization_failures += conn.deserialization_failures;
+            exch.last_message_age = match (exch.last_message_age, conn.last_message_age) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        });
+
+        exchanges
+    }
+
+    /// formats the snapshot in the prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        let metrics: [PrometheusMetric; 8] = [
+            ("cex_ws_messages_total", "counter", "messages recieved", |c| Some(c.messages as f64)),
+            ("cex_ws_messages_per_second", "gauge", "messages per second over the last 10 seconds", |c| Some(c.messages_per_sec)),
+            ("cex_ws_bytes_total", "counter", "bytes recieved", |c| Some(c.bytes as f64)),
+            ("cex_ws_reconnects_total", "counter", "reconnects", |c| Some(c.reconnects as f64)),
+            ("cex_ws_retry_count", "gauge", "current retry count", |c| Some(c.retry_count as f64)),
+            ("cex_ws_removed_bad_pairs_total", "counter", "bad pairs removed from the subscription", |c| Some(c.removed_bad_pairs as f64)),
+            ("cex_ws_deserialization_failures_total", "counter", "messages that failed to deserialize", |c| Some(c.deserialization_failures as f64)),
+            ("cex_ws_last_message_age_seconds", "gauge", "seconds since the last message", |c| c.last_message_age.map(|a| a.as_secs_f64())),
+        ];
+
+        metrics.into_iter().for_each(|(name, kind, help, value)| {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+            self.connections.iter().for_each(|conn| {
+                if let Some(v) = value(conn) {
+                    let _ = writeln!(out, "{name}{{exchange=\"{}\",connection=\"{}\"}} {v}", conn.exchange, conn.connection_id);
+                }
+            });
+        });
+
+        let _ = writeln!(out, "# HELP cex_ws_connections live connections");
+        let _ = writeln!(out, "# TYPE cex_ws_connections gauge");
+        let mut by_exchange = self.by_exchange().into_iter().collect::<Vec<_>>();
+        by_exchange.sort_by_key(|(exch, _)| *exch);
+        by_exchange.into_iter().for_each(|(exch, snapshot)| {
+            let _ = writeln!(out, "cex_ws_connections{{exchange=\"{exch}\"}} {}", snapshot.connections);
+        });
+
+        out
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "non-us")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let registry = WsMetrics::default();
+        let exchange = CexExchange::Binance;
+
+        let conn0 = registry.register(exchange);
+        let conn1 = registry.register(exchange);
+        conn0.record_message(100);
+        conn0.record_message(50);
+        conn0.record_reconnect();
+        conn1.set_retry_count(2);
+        conn1.record_deserialization_failure();
+        conn1.record_removed_bad_pair();
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.connections.len(), 2);
+        assert_eq!(snapshot.connections[0].messages, 2);
+        assert_eq!(snapshot.connections[0].bytes, 150);
+        assert!(snapshot.connections[0].last_message_age.is_some());
+        assert!(snapshot.connections[1].last_message_age.is_none());
+
+        let by_exchange = snapshot.by_exchange();
+        let exch = by_exchange.get(&exchange).unwrap();
+        assert_eq!(exch.connections, 2);
+        assert_eq!(exch.reconnects, 1);
+        assert_eq!(exch.retry_count, 2);
+        assert_eq!(exch.removed_bad_pairs, 1);
+        assert_eq!(exch.deserialization_failures, 1);
+
+        let prometheus = snapshot.to_prometheus();
+        assert!(prometheus.contains("cex_ws_bytes_total{exchange=\"binance\",connection=\"0\"} 150"));
+        assert!(prometheus.contains("cex_ws_connections{exchange=\"binance\"} 2"));
+
+        // snapshots don't reset each other's rate
+        assert!(registry.snapshot().connections[0].messages_per_sec > 0.0);
+
+        registry.unregister(conn0.connection_id());
+        assert_eq!(registry.snapshot().connections.len(), 1);
+    }
+
+    #[test]
+    fn test_rate_sampler() {
+        let start = Instant::now();
+        let mut sampler = RateSampler::new(start);
+
+        sampler.record(50, start + Duration::from_secs(5));
+        assert_eq!(sampler.messages_per_sec(50, start + Duration::from_secs(5)), 10.0);
+
+        sampler.record(100, start + RATE_WINDOW);
+        assert_eq!(sampler.messages_per_sec(120, start + Duration::from_secs(12)), 10.0);
+        assert_eq!(sampler.messages_per_sec(120, start + Duration::from_secs(12)), 10.0);
+
+        // a window w/o messages
+        assert_eq!(sampler.messages_per_sec(100, start + Duration::from_secs(30)), 0.0);
+    }
+}
@@ -11,3 +11,21 @@ pub use critical::*;
 
 mod config;
 pub use config::*;
+
+mod handle;
+pub use handle::*;
+
+mod decoder;
+pub use decoder::*;
+
+mod latency;
+pub use latency::*;
+
+mod metrics;
+pub use metrics::*;
+
+mod recorder;
+pub use recorder::*;
+
+mod replay;
+pub use replay::*;
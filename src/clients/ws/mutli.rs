@@ -2,39 +2,56 @@ use std::{
     fmt::Debug,
     pin::Pin,
     task::{Context, Poll},
+    thread::JoinHandle,
 };
 
-use futures::{Stream, StreamExt};
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use futures::{stream::SelectAll, Stream, StreamExt};
+use tokio::sync::{
+    mpsc::{UnboundedReceiver, UnboundedSender},
+    watch,
+};
+use tracing::{debug, error};
 
-use super::{errors::WsError, WsStream, WsStreamConfig};
-use crate::{exchanges::normalized::ws::CombinedWsMessage, Exchange};
+use super::{errors::WsError, single::BoxedWsStream, WsStream, WsStreamConfig, WsThreadHandle};
+use crate::{exchanges::normalized::ws::CombinedWsMessage, Exchange, ExchangeEnvironment};
 
 pub struct MultiWsStream {
-    combined_streams: Pin<Box<dyn Stream<Item = CombinedWsMessage> + Send>>,
+    combined_streams: SelectAll<BoxedWsStream>,
     stream_count: usize,
 }
 
 impl MultiWsStream {
-    pub fn combine_other(self, other: Self) -> Self {
-        let combined_streams = Box::pin(futures::stream::select_all(vec![self.combined_streams, other.combined_streams]));
+    pub fn combine_other(mut self, other: Self) -> Self {
+        self.combined_streams.extend(other.combined_streams);
 
-        Self { combined_streams, stream_count: self.stream_count + other.stream_count }
+        Self { combined_streams: self.combined_streams, stream_count: self.stream_count + other.stream_count }
     }
 
     pub fn stream_count(&self) -> usize {
         self.stream_count
     }
 
-    pub(crate) fn spawn_on_new_thread(self, tx: UnboundedSender<CombinedWsMessage>) {
+    /// sends a `Close` frame on every inner stream, returning the first error
+    pub async fn close(&mut self) -> Result<(), WsError> {
+        futures::future::join_all(self.combined_streams.iter_mut().map(|s| s.close_stream()))
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        debug!(target: "cex-exchanges::live-stream", "closed {} streams", self.stream_count);
+
+        Ok(())
+    }
+
+    pub(crate) fn spawn_on_new_thread(self, tx: UnboundedSender<CombinedWsMessage>, shutdown_rx: watch::Receiver<bool>) -> JoinHandle<eyre::Result<()>> {
         std::thread::spawn(move || {
             let thread_rt = tokio::runtime::Builder::new_current_thread()
                 .enable_all()
                 .build()?;
 
-            thread_rt.block_on(self.run_with_sender(tx))?;
+            thread_rt.block_on(self.run_with_sender_until_shutdown(tx, shutdown_rx))?;
             Ok::<(), eyre::Report>(())
-        });
+        })
     }
 
     pub async fn run_with_sender(mut self, tx: UnboundedSender<CombinedWsMessage>) -> eyre::Result<()> {
@@ -45,9 +62,44 @@ impl MultiWsStream {
         Ok(())
     }
 
-    pub(crate) fn build_from_raw(raw_streams: Vec<Pin<Box<dyn Stream<Item = CombinedWsMessage> + Send>>>) -> Self {
+    /// runs the stream until it ends or `true` is sent on `shutdown_rx`, then
+    /// closes every inner stream
+    pub async fn run_with_sender_until_shutdown(
+        mut self,
+        tx: UnboundedSender<CombinedWsMessage>,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) -> eyre::Result<()> {
+        let res = loop {
+            tokio::select! {
+                Ok(_) = shutdown_rx.changed() => {
+                    if *shutdown_rx.borrow_and_update() {
+                        debug!(target: "cex-exchanges::live-stream", "recieved shutdown signal");
+                        break Ok(());
+                    }
+                }
+                val = self.next() => match val {
+                    Some(v) => {
+                        if let Err(e) = tx.send(v) {
+                            break Err(e.into());
+                        }
+                    }
+                    None => break Ok(()),
+                }
+            }
+        };
+
+        if let Err(e) = self.close().await {
+            error!(target: "cex-exchanges::live-stream", "error closing streams: {:?}", e);
+            res?;
+            return Err(e.into());
+        }
+
+        res
+    }
+
+    pub(crate) fn build_from_raw(raw_streams: Vec<BoxedWsStream>) -> Self {
         let stream_count = raw_streams.len();
-        let combined_streams = Box::pin(futures::stream::select_all(raw_streams));
+        let combined_streams = futures::stream::select_all(raw_streams);
         Self { stream_count, combined_streams }
     }
 }
@@ -85,12 +137,27 @@ where
         Self { exchanges }
     }
 
+    /// connects every stream to `environment`, see
+    /// [Exchange::with_environment]
+    pub fn with_environment(self, environment: ExchangeEnvironment) -> Self {
+        Self {
+            exchanges: self
+                .exchanges
+                .into_iter()
+                .map(|exch| exch.with_environment(environment.clone()))
+                .collect(),
+        }
+    }
+
     pub async fn build_multistream(self, config: WsStreamConfig) -> Result<MultiWsStream, WsError> {
         let ws_streams = futures::stream::iter(self.exchanges)
-            .map(|exch| async move {
-                let mut stream = WsStream::new(exch, config);
-                stream.connect().await?;
-                Ok::<_, WsError>(stream)
+            .map(|exch| {
+                let config = config.clone();
+                async move {
+                    let mut stream = WsStream::new(exch, config);
+                    stream.connect().await?;
+                    Ok::<_, WsError>(stream)
+                }
             })
             .buffer_unordered(10)
             .collect::<Vec<_>>()
@@ -99,7 +166,7 @@ where
             .collect::<Result<Vec<_>, _>>()?;
 
         let stream_count = ws_streams.len();
-        let combined_streams = Box::pin(futures::stream::select_all(ws_streams));
+        let combined_streams = futures::stream::select_all(ws_streams.into_iter().map(|s| Box::new(s) as BoxedWsStream));
 
         Ok(MultiWsStream { combined_streams, stream_count })
     }
@@ -108,43 +175,38 @@ where
         let ws_streams = self
             .exchanges
             .into_iter()
-            .map(|exch| WsStream::new(exch, config))
+            .map(|exch| WsStream::new(exch, config.clone()))
             .collect::<Vec<_>>();
 
         let stream_count = ws_streams.len();
-        let combined_streams = Box::pin(futures::stream::select_all(ws_streams));
+        let combined_streams = futures::stream::select_all(ws_streams.into_iter().map(|s| Box::new(s) as BoxedWsStream));
 
         MultiWsStream { combined_streams, stream_count }
     }
 
-    pub(crate) fn build_multistream_unconnected_raw(self, config: WsStreamConfig) -> Vec<Pin<Box<dyn Stream<Item = CombinedWsMessage> + Send>>> {
+    pub(crate) fn build_multistream_unconnected_raw(self, config: WsStreamConfig) -> Vec<BoxedWsStream> {
         self.exchanges
             .into_iter()
-            .map(|exch| Box::pin(WsStream::new(exch, config)) as Pin<Box<dyn Stream<Item = CombinedWsMessage> + Send>>)
+            .map(|exch| Box::new(WsStream::new(exch, config.clone())) as BoxedWsStream)
             .collect::<Vec<_>>()
     }
 
-    pub fn spawn_multithreaded(self, num_threads: usize, config: WsStreamConfig) -> UnboundedReceiver<CombinedWsMessage> {
+    /// spawns the streams over `num_threads` threads, returning the receiver
+    /// of all messages and a handle to shutdown/join the threads
+    pub fn spawn_multithreaded(self, num_threads: usize, config: WsStreamConfig) -> (UnboundedReceiver<CombinedWsMessage>, WsThreadHandle) {
         let chunk_size = if self.exchanges.len() < num_threads + 1 { 1 } else { self.exchanges.len() / num_threads + 1 };
         let exchange_chunks = self.exchanges.chunks(chunk_size);
 
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut handle = WsThreadHandle::new();
 
         exchange_chunks.into_iter().for_each(|exchanges| {
-            let exchanges = exchanges.to_vec();
-            let tx = tx.clone();
-            std::thread::spawn(move || {
-                let thread_rt = tokio::runtime::Builder::new_current_thread()
-                    .enable_all()
-                    .build()?;
-                let this_new = Self { exchanges };
-                let ms = this_new.build_multistream_unconnected(config);
-
-                thread_rt.block_on(ms.run_with_sender(tx))?;
-                Ok::<(), eyre::Report>(())
-            });
+            let this_new = Self { exchanges: exchanges.to_vec() };
+            let ms = this_new.build_multistream_unconnected(config.clone());
+
+            handle.push_thread(ms.spawn_on_new_thread(tx.clone(), handle.shutdown_rx()));
         });
 
-        rx
+        (rx, handle)
     }
 }
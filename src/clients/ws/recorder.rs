@@ -0,0 +1,328 @@
+use std::{
+    fs::{self, File},
+    future::Future,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant}
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DurationSeconds};
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    oneshot
+};
+use tracing::{debug, warn};
+
+use crate::CexExchange;
+
+/// most frames written per trip to the blocking pool
+const MAX_BATCH_FRAMES: usize = 1024;
+
+/// where and how the raw ws frames are recorded
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WsRecorderConfig {
+    /// root directory, files are written to `<dir>/<exchange>/`
+    pub dir:            PathBuf,
+    /// rotates the file once this many (uncompressed) bytes have been written
+    #[serde(default = "WsRecorderConfig::default_max_file_bytes")]
+    pub max_file_bytes: Option<u64>,
+    /// rotates the file once it has been open this long
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    #[serde(rename = "max_file_age_secs", default = "WsRecorderConfig::default_max_file_age")]
+    pub max_file_age:   Option<Duration>,
+    /// zstd compression level, `None` writes plain jsonl
+    #[serde(default)]
+    pub zstd_level:     Option<i32>
+}
+
+impl WsRecorderConfig {
+    /// defaults to rotating every 256mb or hour, uncompressed
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir:            dir.into(),
+            max_file_bytes: Self::default_max_file_bytes(),
+            max_file_age:   Self::default_max_file_age(),
+            zstd_level:     None
+        }
+    }
+
+    fn default_max_file_bytes() -> Option<u64> {
+        Some(256 * 1024 * 1024)
+    }
+
+    fn default_max_file_age() -> Option<Duration> {
+        Some(Duration::from_secs(60 * 60))
+    }
+
+    pub fn with_max_file_bytes(mut self, max_file_bytes: u64) -> Self {
+        self.max_file_bytes = Some(max_file_bytes);
+        self
+    }
+
+    pub fn with_max_file_age(mut self, max_file_age: Duration) -> Self {
+        self.max_file_age = Some(max_file_age);
+        self
+    }
+
+    /// never rotates the file
+    pub fn without_rotation(mut self) -> Self {
+        self.max_file_bytes = None;
+        self.max_file_age = None;
+        self
+    }
+
+    pub fn with_zstd(mut self, level: i32) -> Self {
+        self.zstd_level = Some(level);
+        self
+    }
+}
+
+/// a single raw text frame as recieved from the exchange, written as one json
+/// line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub exchange:      CexExchange,
+    pub connection_id: u64,
+    pub received_at:   DateTime<Utc>,
+    pub frame:         String
+}
+
+/// tees the raw frames of a single ws connection to append-only files
+///
+/// the files are written by a background task on the blocking pool, so
+/// recording never blocks the stream's poll
+pub struct WsRecorder {
+    exchange:      CexExchange,
+    connection_id: u64,
+    /// moved to the writer task w/ the first frame, so the recorder can be
+    /// created outside of a runtime
+    writer:        Option<RecordingWriter>,
+    tx:            Option<UnboundedSender<WriterCmd>>
+}
+
+enum WriterCmd {
+    Frame(RecordedFrame),
+    Finish(oneshot::Sender<io::Result<()>>)
+}
+
+impl WsRecorder {
+    pub fn new(config: WsRecorderConfig, exchange: CexExchange, connection_id: u64) -> Self {
+        Self { exchange, connection_id, writer: Some(RecordingWriter { config, exchange, connection_id, file: None }), tx: None }
+    }
+
+    pub fn connection_id(&self) -> u64 {
+        self.connection_id
+    }
+
+    /// queues the frame for the writer, only fails if the writer stopped
+    pub fn record(&mut self, received_at: DateTime<Utc>, frame: &str) -> io::Result<()> {
+        let frame = RecordedFrame { exchange: self.exchange, connection_id: self.connection_id, received_at, frame: frame.to_string() };
+        self.sender()
+            .send(WriterCmd::Frame(frame))
+            .map_err(|_| writer_stopped())
+    }
+
+    /// writes the queued frames & closes the file (finishing the zstd frame)
+    pub fn finish(self) -> impl Future<Output = io::Result<()>> + Send + 'static {
+        let tx = self.tx;
+        async move {
+            // nothing was recorded
+            let Some(tx) = tx else { return Ok(()) };
+
+            let (finished_tx, finished_rx) = oneshot::channel();
+            tx.send(WriterCmd::Finish(finished_tx))
+                .map_err(|_| writer_stopped())?;
+            finished_rx.await.unwrap_or_else(|_| Err(writer_stopped()))
+        }
+    }
+
+    fn sender(&mut self) -> &UnboundedSender<WriterCmd> {
+        let writer = &mut self.writer;
+        self.tx.get_or_insert_with(|| {
+            let (tx, rx) = unbounded_channel();
+            tokio::spawn(write_frames(writer.take().expect("the writer is only spawned once"), rx));
+            tx
+        })
+    }
+}
+
+fn writer_stopped() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "the recorder's writer stopped")
+}
+
+/// writes the queued frames in batches on the blocking pool
+async fn write_frames(mut writer: RecordingWriter, mut rx: UnboundedReceiver<WriterCmd>) {
+    let mut cmds = Vec::new();
+    while rx.recv_many(&mut cmds, MAX_BATCH_FRAMES).await > 0 {
+        let batch = std::mem::take(&mut cmds);
+        match tokio::task::spawn_blocking(move || {
+            let finished = writer.write_batch(batch);
+            (writer, finished)
+        })
+        .await
+        {
+            Ok((_, true)) => return,
+            Ok((w, false)) => writer = w,
+            Err(e) => {
+                warn!(target: "cex-exchanges::recorder", "error joining the recorder's writer: {:?}", e);
+                return
+            }
+        }
+    }
+
+    // the stream was dropped w/o finishing the recording
+    let _ = tokio::task::spawn_blocking(move || writer.finish()).await;
+}
+
+/// the files of a recording, only written to on the blocking pool
+struct RecordingWriter {
+    config:        WsRecorderConfig,
+    exchange:      CexExchange,
+    connection_id: u64,
+    file:          Option<RecordingFile>
+}
+
+struct RecordingFile {
+    writer:    Box<dyn Write + Send>,
+    opened_at: Instant,
+    bytes:     u64
+}
+
+impl RecordingWriter {
+    /// returns true once the recording is finished
+    fn write_batch(&mut self, cmds: Vec<WriterCmd>) -> bool {
+        for cmd in cmds {
+            match cmd {
+                WriterCmd::Frame(frame) => {
+                    if let Err(e) = self.write(&frame) {
+                        let (exchange, connection_id) = (self.exchange, self.connection_id);
+                        warn!(target: "cex-exchanges::recorder", exchange=?exchange, connection_id, "error recording frame: {:?}", e);
+                    }
+                }
+                WriterCmd::Finish(finished_tx) => {
+                    let _ = finished_tx.send(self.finish());
+                    return true
+                }
+            }
+        }
+
+        false
+    }
+
+    fn write(&mut self, frame: &RecordedFrame) -> io::Result<()> {
+        let mut line = serde_json::to_vec(frame)?;
+        line.push(b'\n');
+
+        if self.should_rotate() {
+            self.rotate(frame.received_at)?;
+        }
+
+        let file = self.file.as_mut().expect("file was opened in rotate");
+        file.writer.write_all(&line)?;
+        file.bytes += line.len() as u64;
+
+        Ok(())
+    }
+
+    /// flushes & drops the file, which finishes the zstd frame
+    fn finish(&mut self) -> io::Result<()> {
+        match self.file.take() {
+            Some(mut file) => file.writer.flush(),
+            None => Ok(())
+        }
+    }
+
+    fn should_rotate(&self) -> bool {
+        let Some(file) = self.file.as_ref() else { return true };
+
+        self.config
+            .max_file_bytes
+            .map(|max| file.bytes >= max)
+            .unwrap_or_default()
+            || self
+                .config
+                .max_file_age
+                .map(|max| file.opened_at.elapsed() >= max)
+                .unwrap_or_default()
+    }
+
+    /// closes the current file (finishing the zstd frame) and opens a new one
+    fn rotate(&mut self, now: DateTime<Utc>) -> io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.writer.flush()?;
+        }
+
+        let dir = self.config.dir.join(self.exchange.to_string());
+        fs::create_dir_all(&dir)?;
+
+        let path = self.file_path(&dir, now);
+        debug!(target: "cex-exchanges::recorder", exchange=?self.exchange, connection_id=self.connection_id, "recording to {}", path.display());
+
+        let file = BufWriter::new(File::options().create(true).append(true).open(path)?);
+        let writer: Box<dyn Write + Send> = match self.config.zstd_level {
+            Some(level) => Box::new(zstd::Encoder::new(file, level)?.auto_finish()),
+            None => Box::new(file)
+        };
+
+        self.file = Some(RecordingFile { writer, opened_at: Instant::now(), bytes: 0 });
+        Ok(())
+    }
+
+    fn file_path(&self, dir: &Path, now: DateTime<Utc>) -> PathBuf {
+        let ext = if self.config.zstd_level.is_some() { "jsonl.zst" } else { "jsonl" };
+        dir.join(format!("{}-{}-{}.{ext}", self.exchange, self.connection_id, now.format("%Y%m%dT%H%M%S%.6fZ")))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "non-us")]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_recorder_rotation() {
+        let dir = std::env::temp_dir().join(format!("cex-exchanges-recorder-{}", std::process::id()));
+        let config = WsRecorderConfig::new(&dir)
+            .without_rotation()
+            .with_max_file_bytes(1)
+            .with_zstd(3);
+        let exchange = CexExchange::Binance;
+
+        let mut recorder = WsRecorder::new(config, exchange, 7);
+        recorder.record(Utc::now(), r#"{"a":1}"#).unwrap();
+        recorder.record(Utc::now(), r#"{"b":2}"#).unwrap();
+        recorder.finish().await.unwrap();
+
+        let mut files = fs::read_dir(dir.join(exchange.to_string()))
+            .unwrap()
+            .map(|f| f.unwrap().path())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files.len(), 2);
+
+        let frames = files
+            .iter()
+            .map(|path| {
+                let mut out = String::new();
+                zstd::Decoder::new(File::open(path).unwrap())
+                    .unwrap()
+                    .read_to_string(&mut out)
+                    .unwrap();
+                serde_json::from_str::<RecordedFrame>(out.trim_end()).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(frames[0].frame, r#"{"a":1}"#);
+        assert_eq!(frames[1].frame, r#"{"b":2}"#);
+        assert!(frames
+            .iter()
+            .all(|f| f.connection_id == 7 && f.exchange == exchange));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
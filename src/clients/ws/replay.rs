@@ -0,0 +1,254 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufRead, BufReader},
+    iter::Peekable,
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant
+};
+
+use chrono::{DateTime, Utc};
+use futures::{FutureExt, Stream};
+use tokio::time::Sleep;
+use tracing::warn;
+
+use super::{RecordedFrame, ReceiveTime, WsError, WsStream};
+#[cfg(feature = "non-us")]
+use crate::{binance::Binance, bybit::Bybit, kucoin::Kucoin};
+#[cfg(feature = "us")]
+use crate::{coinbase::Coinbase, okex::Okex};
+use crate::{exchanges::normalized::ws::CombinedWsMessage, CexExchange, Exchange};
+
+/// how fast a [ReplayStream] yields the recorded frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplayMode {
+    /// yields every frame as soon as it's polled
+    #[default]
+    AsFastAsPossible,
+    /// waits between frames for the same interval as when they were recorded
+    OriginalTiming
+}
+
+type ReplaySource = Peekable<Box<dyn Iterator<Item = RecordedFrame> + Send>>;
+
+/// replays frames written by a [WsRecorder](super::WsRecorder), yielding the
+/// same [CombinedWsMessage]s as the live [WsStream]
+///
+/// frames from different connections are merged by their recieve time
+pub struct ReplayStream {
+    sources: Vec<ReplaySource>,
+    mode:    ReplayMode,
+    /// local time & recorded time of the first frame
+    started: Option<(Instant, DateTime<Utc>)>,
+    pending: Option<RecordedFrame>,
+    sleep:   Option<Pin<Box<Sleep>>>
+}
+
+impl ReplayStream {
+    pub fn from_frames(frames: Vec<RecordedFrame>, mode: ReplayMode) -> Self {
+        let source: Box<dyn Iterator<Item = RecordedFrame> + Send> = Box::new(frames.into_iter());
+        Self::new(vec![source.peekable()], mode)
+    }
+
+    /// replays the given files, files of the same connection are read in
+    /// order of their rotation
+    pub fn from_files(paths: Vec<PathBuf>, mode: ReplayMode) -> io::Result<Self> {
+        let mut connections: BTreeMap<String, Vec<(String, PathBuf)>> = BTreeMap::new();
+        for path in paths {
+            let file_name = path
+                .file_name()
+                .and_then(|f| f.to_str())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid recording file name: {}", path.display())))?;
+
+            // `<exchange>-<connection id>-<timestamp>.jsonl[.zst]`
+            let (connection, timestamp) = match file_name.splitn(3, '-').collect::<Vec<_>>().as_slice() {
+                [exchange, connection_id, timestamp] => (format!("{exchange}-{connection_id}"), timestamp.to_string()),
+                _ => (file_name.to_string(), String::new())
+            };
+
+            connections
+                .entry(connection)
+                .or_default()
+                .push((timestamp, path));
+        }
+
+        let sources = connections
+            .into_values()
+            .map(|mut files| {
+                files.sort();
+                let readers = files
+                    .into_iter()
+                    .map(|(_, path)| read_recording(path))
+                    .collect::<io::Result<Vec<_>>>()?;
+
+                let source: Box<dyn Iterator<Item = RecordedFrame> + Send> = Box::new(readers.into_iter().flatten());
+                Ok(source.peekable())
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(Self::new(sources, mode))
+    }
+
+    /// replays every recording file in `dir` (and it's subdirectories)
+    pub fn from_dir(dir: impl AsRef<Path>, mode: ReplayMode) -> io::Result<Self> {
+        let mut paths = Vec::new();
+        let mut dirs = vec![dir.as_ref().to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if is_recording_file(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+
+        Self::from_files(paths, mode)
+    }
+
+    fn new(sources: Vec<ReplaySource>, mode: ReplayMode) -> Self {
+        Self { sources, mode, started: None, pending: None, sleep: None }
+    }
+
+    /// takes the earliest frame across all sources
+    fn next_frame(&mut self) -> Option<RecordedFrame> {
+        let idx = self
+            .sources
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(idx, source)| source.peek().map(|f| (f.received_at, idx)))
+            .min()?
+            .1;
+
+        self.sources[idx].next()
+    }
+}
+
+impl Stream for ReplayStream {
+    type Item = CombinedWsMessage;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if this.pending.is_none() {
+            this.pending = this.next_frame();
+        }
+
+        let Some(received_at) = this.pending.as_ref().map(|f| f.received_at) else { return Poll::Ready(None) };
+
+        if this.mode == ReplayMode::OriginalTiming {
+            let (start, recorded_start) = *this.started.get_or_insert((Instant::now(), received_at));
+            let deadline = start + (received_at - recorded_start).to_std().unwrap_or_default();
+
+            if deadline > Instant::now() {
+                let sleep = this
+                    .sleep
+                    .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(deadline.into())));
+                if sleep.poll_unpin(cx).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+            this.sleep = None;
+        }
+
+        Poll::Ready(this.pending.take().map(deserialize_frame))
+    }
+}
+
+/// reads the frames of a single (plain or zstd compressed) recording file
+///
+/// corrupt lines are skipped, a truncated file (ex: the process was killed
+/// while recording) ends at the last complete frame
+pub fn read_recording(path: impl AsRef<Path>) -> io::Result<impl Iterator<Item = RecordedFrame> + Send> {
+    let path = path.as_ref().to_path_buf();
+    let file = File::open(&path)?;
+
+    let reader: Box<dyn BufRead + Send> = if path.extension().is_some_and(|ext| ext == "zst") {
+        Box::new(BufReader::new(zstd::Decoder::new(file)?))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+
+    Ok(reader
+        .lines()
+        .map_while(move |line| {
+            line.inspect_err(|e| warn!(target: "cex-exchanges::replay", "error reading {}: {:?}", path.display(), e))
+                .ok()
+        })
+        .filter_map(|line| {
+            serde_json::from_str::<RecordedFrame>(&line)
+                .inspect_err(|e| warn!(target: "cex-exchanges::replay", "skipping corrupt frame: {:?}", e))
+                .ok()
+        }))
+}
+
+fn is_recording_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|f| f.to_str())
+        .is_some_and(|f| f.ends_with(".jsonl") || f.ends_with(".jsonl.zst"))
+}
+
+fn deserialize_frame(frame: RecordedFrame) -> CombinedWsMessage {
+    match frame.exchange {
+        #[cfg(feature = "us")]
+        CexExchange::Coinbase => deserialize_exchange_frame::<Coinbase>(frame),
+        #[cfg(feature = "us")]
+        CexExchange::Okex => deserialize_exchange_frame::<Okex>(frame),
+        #[cfg(feature = "non-us")]
+        CexExchange::Binance => deserialize_exchange_frame::<Binance>(frame),
+        #[cfg(feature = "non-us")]
+        CexExchange::Kucoin => deserialize_exchange_frame::<Kucoin>(frame),
+        #[cfg(feature = "non-us")]
+        CexExchange::Bybit => deserialize_exchange_frame::<Bybit>(frame)
+    }
+}
+
+fn deserialize_exchange_frame<T: Exchange>(frame: RecordedFrame) -> CombinedWsMessage {
+    match WsStream::<T>::deserialize_frame(frame.frame) {
+        Ok(msg) => {
+            let msg: CombinedWsMessage = msg.into();
+            msg.with_receive_time(ReceiveTime { monotonic: Instant::now(), wall: frame.received_at })
+        }
+        Err((e, raw_msg)) => WsError::from(e).normalized_with_exchange(T::EXCHANGE, Some(raw_msg))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "non-us")]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::clients::ws::{WsRecorder, WsRecorderConfig};
+
+    const TRADE: &str = r#"{"stream":"btcusdt@trade","data":{"e":"trade","E":1700000000001,"s":"BTCUSDT","t":12345,"p":"37000.10","q":"0.5","T":1700000000000,"m":true,"M":true}}"#;
+
+    #[tokio::test]
+    async fn test_replay_recording() {
+        let dir = std::env::temp_dir().join(format!("cex-exchanges-replay-{}", std::process::id()));
+        let start = Utc::now();
+
+        let mut recorder = WsRecorder::new(WsRecorderConfig::new(&dir).without_rotation().with_zstd(3), CexExchange::Binance, 1);
+        recorder.record(start, TRADE).unwrap();
+        recorder
+            .record(start + chrono::Duration::milliseconds(50), "not json")
+            .unwrap();
+        recorder.finish().await.unwrap();
+
+        let stream = ReplayStream::from_dir(&dir, ReplayMode::OriginalTiming).unwrap();
+        let timer = Instant::now();
+        let msgs = stream.collect::<Vec<_>>().await;
+
+        assert!(timer.elapsed() >= std::time::Duration::from_millis(50));
+        assert_eq!(msgs.len(), 2);
+        assert!(matches!(msgs[0], CombinedWsMessage::Binance(..)));
+        assert_eq!(msgs[0].receive_time().unwrap().wall, start);
+        assert_eq!(msgs[0], WsStream::<Binance>::deserialize_frame(TRADE.to_string()).unwrap().normalize());
+        assert!(matches!(&msgs[1], CombinedWsMessage::Disconnect { raw_message, .. } if raw_message == "not json"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,15 +1,16 @@
 use std::{
     fmt::Debug,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 
-use futures::{Future, FutureExt, SinkExt, Stream, StreamExt};
+use futures::{future::OptionFuture, Future, FutureExt, SinkExt, Stream, StreamExt};
 use tokio::net::TcpStream;
 use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
 use tracing::{debug, error, info, trace, warn};
 
-use super::{WsError, WsStreamConfig};
+use super::{ConnectionMetrics, ReceiveTime, WsError, WsMetrics, WsRecorder, WsStreamConfig};
 use crate::{
     clients::ws::critical::CriticalWsMessage,
     exchanges::normalized::ws::{CombinedWsMessage, MessageOrPing},
@@ -18,12 +19,28 @@ use crate::{
 
 type StreamConn = Pin<Box<WebSocketStream<MaybeTlsStream<TcpStream>>>>;
 
+/// type-erased [WsStream] so streams of different exchanges can be combined
+/// and still be closed
+pub(crate) type BoxedWsStream = Box<dyn CloseableWsStream>;
+
+pub trait CloseableWsStream: Stream<Item = CombinedWsMessage> + Send + Unpin {
+    /// sends a `Close` frame on the underlying connection (if any) and stops
+    /// the stream from reconnecting
+    fn close_stream(&mut self) -> Pin<Box<dyn Future<Output = Result<(), WsError>> + Send + '_>>;
+}
+
 pub struct WsStream<T> {
     exchange: T,
     stream: Option<StreamConn>,
     stream_futs: WsStreamFutures<T>,
     config: WsStreamConfig,
     retry_count: u64,
+    is_closed: bool,
+    metrics: Option<Arc<ConnectionMetrics>>,
+    recorder: Option<WsRecorder>,
+    /// the close handshake after a non-retryable error, finished before the
+    /// stream ends
+    closing: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
 }
 
 impl<T> WsStream<T>
@@ -31,7 +48,26 @@ where
     T: Exchange,
 {
     pub fn new(exchange: T, config: WsStreamConfig) -> Self {
-        Self { stream_futs: WsStreamFutures::new(exchange.clone()), exchange, stream: None, config, retry_count: 0 }
+        let metrics = config.metrics.then(|| WsMetrics::global().register(T::EXCHANGE));
+        let recorder = config.recorder.clone().map(|recorder_config| {
+            let connection_id = metrics
+                .as_ref()
+                .map(|m| m.connection_id())
+                .unwrap_or_else(|| WsMetrics::global().next_connection_id());
+            WsRecorder::new(recorder_config, T::EXCHANGE, connection_id)
+        });
+
+        Self {
+            stream_futs: WsStreamFutures::new(exchange.clone()),
+            exchange,
+            stream: None,
+            config,
+            retry_count: 0,
+            is_closed: false,
+            metrics,
+            recorder,
+            closing: None,
+        }
     }
 
     pub fn exchange(&self) -> T {
@@ -47,26 +83,89 @@ where
         Ok(())
     }
 
+    /// sends a `Close` frame to the exchange and drops the connection
+    pub async fn close(&mut self) -> Result<(), WsError> {
+        if let Some(closing) = self.closing.take() {
+            closing.await;
+        }
+
+        self.shutdown().await
+    }
+
+    /// stops the stream from reconnecting, returning the close handshake of
+    /// the connection & the end of the recording
+    fn shutdown(&mut self) -> impl Future<Output = Result<(), WsError>> + Send + 'static {
+        self.is_closed = true;
+        self.stream_futs.clear();
+
+        let stream = self.stream.take();
+        let recording = self.recorder.take().map(WsRecorder::finish);
+        async move {
+            if let Some(Err(e)) = OptionFuture::from(recording).await {
+                warn!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "error finishing the recording: {:?}", e);
+            }
+
+            match stream {
+                Some(stream) => Self::close_connection(stream).await,
+                None => Ok(()),
+            }
+        }
+    }
+
+    /// sends the `Close` frame & flushes the sink
+    async fn close_connection(mut stream: StreamConn) -> Result<(), WsError> {
+        match stream.close().await {
+            Ok(_) | Err(tokio_tungstenite::tungstenite::Error::ConnectionClosed) | Err(tokio_tungstenite::tungstenite::Error::AlreadyClosed) => {
+                debug!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "closed stream");
+                Ok(())
+            }
+            Err(e) => Err(WsError::StreamTxError(e)),
+        }
+    }
+
     fn reconnect(&mut self, cx: &mut Context<'_>) {
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.record_reconnect();
+        }
         self.stream = None;
         self.stream_futs.new_reconnect();
         cx.waker().wake_by_ref();
     }
 
-    fn handle_incoming(message: Message) -> Result<MessageOrPing<T>, (WsError, String)> {
-        match message {
-            Message::Text(msg) => {
-                trace!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "recieved new message for the stream: {}", msg);
-                let mut des_msg = serde_json::from_str::<T::WsMessage>(&msg).map_err(|e| (e.into(), msg.clone()))?;
-                des_msg.make_critical(msg);
-                Ok(MessageOrPing::new_message(des_msg))
+    fn handle_incoming(message: Message, recorder: Option<&mut WsRecorder>) -> Result<MessageOrPing<T>, (WsError, String)> {
+        let receive_time = ReceiveTime::now();
+        let msg = match message {
+            Message::Text(msg) => msg,
+            Message::Binary(payload) => {
+                T::decode_binary_message(&payload).map_err(|e| (e.into(), String::from_utf8_lossy(&payload).to_string()))?
             }
-            Message::Ping(ping) => Ok(MessageOrPing::new_ping(ping)),
-            Message::Binary(_) => panic!("Exchange: {} - Message::Binary", T::EXCHANGE),
-            Message::Pong(_) => panic!("Exchange: {} - Message::Pong", T::EXCHANGE),
-            Message::Close(_) => Ok(MessageOrPing::new_close()),
-            Message::Frame(_) => panic!("Exchange: {} - Message::Frame", T::EXCHANGE),
+            Message::Ping(ping) => return Ok(MessageOrPing::new_ping(ping)),
+            Message::Pong(_) => {
+                trace!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "recieved pong");
+                return Ok(MessageOrPing::new_skip());
+            }
+            Message::Close(_) => return Ok(MessageOrPing::new_close()),
+            Message::Frame(_) => {
+                warn!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "recieved unexpected raw frame");
+                return Ok(MessageOrPing::new_skip());
+            }
+        };
+
+        trace!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "recieved new message for the stream: {}", msg);
+        if let Some(Err(e)) = recorder.map(|r| r.record(receive_time.wall, &msg)) {
+            warn!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "error recording message: {:?}", e);
         }
+
+        let des_msg = Self::deserialize_frame(msg).map_err(|(e, msg)| (e.into(), msg))?;
+        Ok(MessageOrPing::new_message(des_msg, receive_time))
+    }
+
+    /// deserializes a text frame into the exchange's message, shared by the
+    /// live stream and [ReplayStream](super::ReplayStream)
+    pub(crate) fn deserialize_frame(msg: String) -> Result<T::WsMessage, (serde_json::Error, String)> {
+        let mut des_msg = serde_json::from_str::<T::WsMessage>(&msg).map_err(|e| (e, msg.clone()))?;
+        des_msg.make_critical(msg);
+        Ok(des_msg)
     }
 
     fn flush_sink_queue(stream: &mut StreamConn, cx: &mut Context<'_>) -> Result<(), WsError> {
@@ -88,6 +187,16 @@ where
     }
 
     fn handle_retry(&mut self, msg: CombinedWsMessage) -> Poll<Option<CombinedWsMessage>> {
+        if let Some(kind) = msg.error_kind().filter(|k| !k.is_retryable()) {
+            error!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "recieved non-retryable {kind} error -- EXITING");
+            self.closing = Some(Box::pin(self.shutdown().map(|res| {
+                if let Err(e) = res {
+                    warn!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "error closing the stream: {:?}", e);
+                }
+            })));
+            return Poll::Ready(Some(msg));
+        }
+
         let stay_same = match self.handle_bad_pair(&msg) {
             Some(true) => return Poll::Ready(None),
             Some(false) => false,
@@ -96,6 +205,9 @@ where
         if let Some(retries) = self.config.max_retries {
             if !stay_same {
                 self.retry_count += 1;
+                if let Some(metrics) = self.metrics.as_ref() {
+                    metrics.set_retry_count(self.retry_count);
+                }
             }
 
             if self.retry_count > retries {
@@ -111,7 +223,12 @@ where
     /// Some(false) => subscription is not empty
     /// None => no bad pair found
     fn handle_bad_pair(&mut self, msg: &CombinedWsMessage) -> Option<bool> {
-        msg.bad_pair().map(|p| self.exchange.remove_bad_pair(p))
+        msg.bad_pair().map(|p| {
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.record_removed_bad_pair();
+            }
+            self.exchange.remove_bad_pair(p)
+        })
     }
 
     #[allow(unused)]
@@ -137,35 +254,62 @@ where
         let this = self.get_mut();
         // this.sanity_check();
 
+        if this.is_closed {
+            if let Some(closing) = this.closing.as_mut() {
+                if closing.poll_unpin(cx).is_pending() {
+                    return Poll::Pending;
+                }
+                this.closing = None;
+            }
+            return Poll::Ready(None);
+        }
+
         if let Some(stream) = this.stream.as_mut() {
             if let Poll::Ready(val) = stream.poll_next_unpin(cx) {
                 match val {
-                    Some(Ok(msg)) => match Self::handle_incoming(msg) {
-                        Ok(MessageOrPing::Message(d)) => {
-                            this.stream_futs.new_timeout_rx();
-                            return this.handle_retry(d.into());
+                    Some(Ok(msg)) => {
+                        let bytes = msg.len();
+                        let incoming = Self::handle_incoming(msg, this.recorder.as_mut());
+
+                        if let Some(metrics) = this.metrics.as_ref() {
+                            match &incoming {
+                                Ok(MessageOrPing::Message(..)) => metrics.record_message(bytes),
+                                Err((WsError::DeserializingError(_) | WsError::DecodingError(_), _)) => metrics.record_deserialization_failure(),
+                                _ => (),
+                            }
                         }
-                        Ok(MessageOrPing::Ping(v)) => {
-                            debug!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "recieved ping");
-                            if let Err(e) = stream.start_send_unpin(Message::Pong(v)) {
-                                error!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "error sending pong");
+
+                        match incoming {
+                            Ok(MessageOrPing::Message(d, receive_time)) => {
+                                this.stream_futs.new_timeout_rx();
+                                let msg: CombinedWsMessage = d.into();
+                                return this.handle_retry(msg.with_receive_time(receive_time));
+                            }
+                            Ok(MessageOrPing::Ping(v)) => {
+                                debug!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "recieved ping");
+                                if let Err(e) = stream.start_send_unpin(Message::Pong(v)) {
+                                    error!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "error sending pong");
+                                    this.reconnect(cx);
+                                    return this.handle_retry(WsError::StreamTxError(e).normalized_with_exchange(T::EXCHANGE, None));
+                                } else if let Err(e) = Self::flush_sink_queue(stream, cx) {
+                                    warn!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "error flushing queue sink");
+                                    this.reconnect(cx);
+                                    return this.handle_retry(e.normalized_with_exchange(T::EXCHANGE, None));
+                                }
+                            }
+                            Ok(MessageOrPing::Skip) => {
+                                cx.waker().wake_by_ref();
+                            }
+                            Ok(MessageOrPing::Close) => {
                                 this.reconnect(cx);
-                                return this.handle_retry(WsError::StreamTxError(e).normalized_with_exchange(T::EXCHANGE, None));
-                            } else if let Err(e) = Self::flush_sink_queue(stream, cx) {
-                                warn!(target: "cex-exchanges::live-stream", exchange=?T::EXCHANGE, "error flushing queue sink");
+                                return Poll::Pending;
+                            }
+                            Err((e, raw_msg)) => {
                                 this.reconnect(cx);
-                                return this.handle_retry(e.normalized_with_exchange(T::EXCHANGE, None));
+                                return this.handle_retry(e.normalized_with_exchange(T::EXCHANGE, Some(raw_msg)));
                             }
                         }
-                        Ok(MessageOrPing::Close) => {
-                            this.reconnect(cx);
-                            return Poll::Pending;
-                        }
-                        Err((e, raw_msg)) => {
-                            this.reconnect(cx);
-                            return this.handle_retry(e.normalized_with_exchange(T::EXCHANGE, Some(raw_msg)));
-                        }
-                    },
+                    }
                     Some(Err(e)) => {
                         this.reconnect(cx);
                         return this.handle_retry(WsError::StreamRxError(e).normalized_with_exchange(T::EXCHANGE, None));
@@ -201,6 +345,24 @@ where
     }
 }
 
+impl<T> Drop for WsStream<T> {
+    fn drop(&mut self) {
+        if let Some(metrics) = self.metrics.take() {
+            WsMetrics::global().unregister(metrics.connection_id());
+        }
+    }
+}
+
+impl<T> CloseableWsStream for WsStream<T>
+where
+    T: Exchange + Debug,
+    Self: Send,
+{
+    fn close_stream(&mut self) -> Pin<Box<dyn Future<Output = Result<(), WsError>> + Send + '_>> {
+        Box::pin(self.close())
+    }
+}
+
 type ReconnectFuture = Option<Pin<Box<dyn Future<Output = Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WsError>> + Send + 'static>>>;
 type TimeoutRxFuture = Option<Pin<Box<dyn Future<Output = ()> + Send + 'static>>>;
 
@@ -220,11 +382,14 @@ impl<T: Exchange> WsStreamFutures<T> {
         self.timeout_rx = None;
     }
 
+    fn clear(&mut self) {
+        self.reconnect = None;
+        self.timeout_rx = None;
+    }
+
     fn new_timeout_rx(&mut self) {
         if let Some(timeout_sec) = T::STREAM_TIMEOUT_MS {
             self.timeout_rx = Some(Box::pin(tokio::time::sleep(std::time::Duration::from_millis(timeout_sec))));
-        } else {
-            panic!()
         }
     }
 
@@ -18,7 +18,7 @@ use self::{
     rest_api::{BinanceAllInstruments, BinanceAllSymbols, BinanceRestApiResponse, BinanceSymbol},
     ws::{BinanceSubscription, BinanceWsMessage},
 };
-use super::traits::SpecificWsSubscription;
+use super::{traits::SpecificWsSubscription, ExchangeEnvironment};
 use crate::{
     clients::{rest_api::RestApiError, ws::WsError},
     exchanges::Exchange,
@@ -29,26 +29,30 @@ use crate::{
 const WSS_URL: &str = "wss://stream.binance.com:443";
 const BASE_REST_API_URL: &str = "https://api.binance.com/api/v3";
 const ALL_SYMBOLS_URL: &str = "https://www.binance.com/bapi/composite/v1/public/promo/cmc/cryptocurrency/listings/latest";
+const TESTNET_WSS_URL: &str = "wss://stream.testnet.binance.vision:443";
+const TESTNET_BASE_REST_API_URL: &str = "https://testnet.binance.vision/api/v3";
 
 #[derive(Debug, Default, Clone)]
 pub struct Binance {
     subscription: BinanceSubscription,
+    environment:  ExchangeEnvironment,
 }
 
 impl Binance {
     pub fn new_ws_subscription(subscription: BinanceSubscription) -> Self {
-        Self { subscription }
+        Self { subscription, environment: ExchangeEnvironment::Production }
     }
 
-    pub async fn get_all_instruments(web_client: &reqwest::Client) -> Result<BinanceAllInstruments, RestApiError> {
-        let instruments: BinanceAllInstruments = Self::simple_rest_api_request(web_client, format!("{BASE_REST_API_URL}/exchangeInfo"), None).await?;
+    pub async fn get_all_instruments(&self, web_client: &reqwest::Client) -> Result<BinanceAllInstruments, RestApiError> {
+        let url = format!("{}/exchangeInfo", self.endpoint_url(BASE_REST_API_URL, Some(TESTNET_BASE_REST_API_URL))?);
+        let instruments: BinanceAllInstruments = Self::simple_rest_api_request(web_client, url, None).await?;
         info!(target: "cex-exchanges::binance", "found {} instruments", instruments.instruments.len());
 
         Ok(instruments)
     }
 
-    pub async fn get_all_symbols(web_client: &reqwest::Client) -> Result<BinanceAllSymbols, RestApiError> {
-        let instruments: BinanceAllInstruments = Self::get_all_instruments(web_client).await?;
+    pub async fn get_all_symbols(&self, web_client: &reqwest::Client) -> Result<BinanceAllSymbols, RestApiError> {
+        let instruments: BinanceAllInstruments = self.get_all_instruments(web_client).await?;
         debug!(target: "cex-exchanges::binance", "got {} instruments to filter symbols", instruments.instruments.len());
 
         let pos_symbols = instruments
@@ -65,7 +69,7 @@ impl Binance {
         let mut err_count = 5;
         loop {
             debug!(target: "cex-exchanges::binance", "starting symbols iteration {query_start}");
-            let symbols_iteration = match Self::symbols_iteration(web_client, query_start).await {
+            let symbols_iteration = match self.symbols_iteration(web_client, query_start).await {
                 Ok(vals) => {
                     if vals.is_empty() {
                         trace!(target: "cex-exchanges::binance", "no symbols found in valid call - breaking loop");
@@ -109,8 +113,9 @@ impl Binance {
         Ok(BinanceAllSymbols { symbols: symbols.values().cloned().collect::<Vec<_>>() })
     }
 
-    async fn symbols_iteration(web_client: &reqwest::Client, query_start: u64) -> Result<Vec<BinanceSymbol>, RestApiError> {
-        let url = format!("{ALL_SYMBOLS_URL}?limit=5000&start={query_start}");
+    async fn symbols_iteration(&self, web_client: &reqwest::Client, query_start: u64) -> Result<Vec<BinanceSymbol>, RestApiError> {
+        // the listings aren't specific to the exchange's deployment, so the testnet uses them as well
+        let url = format!("{}?limit=5000&start={query_start}", self.endpoint_url(ALL_SYMBOLS_URL, Some(ALL_SYMBOLS_URL))?);
         let iter_symbols: BinanceAllSymbols =
             Self::simple_rest_api_request(web_client, url, Some((header::CONTENT_ENCODING, "gzip, deflate, br".parse().unwrap()))).await?;
         Ok(iter_symbols.symbols)
@@ -146,17 +151,32 @@ impl Exchange for Binance {
     const STREAM_TIMEOUT_MS: Option<u64> = Some(2000);
 
     fn remove_bad_pair(&mut self, bad_pair: NormalizedTradingPair) -> bool {
-        let pair = bad_pair.try_into().unwrap();
-        self.subscription.remove_pair(&pair)
+        match BinanceTradingPair::try_from(bad_pair) {
+            Ok(pair) => self.subscription.remove_pair(&pair),
+            Err(e) => {
+                error!(target: "cex-exchanges::binance", "could not convert the bad pair, keeping the subscription: {:?}", e);
+                false
+            }
+        }
+    }
+
+    fn with_environment(mut self, environment: ExchangeEnvironment) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    fn environment(&self) -> &ExchangeEnvironment {
+        &self.environment
     }
 
     async fn make_ws_connection(&self) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WsError> {
+        let wss_url = self.endpoint_url(WSS_URL, Some(TESTNET_WSS_URL))?;
         let ws = if let Some(single_sub) = self.subscription.try_single_subscription() {
-            let ws_url = format!("{WSS_URL}/ws/{single_sub}");
+            let ws_url = format!("{wss_url}/ws/{single_sub}");
             let (ws, _) = tokio_tungstenite::connect_async(&ws_url).await?;
             ws
         } else {
-            let ws_url = format!("{WSS_URL}/stream");
+            let ws_url = format!("{wss_url}/stream");
             let (mut ws, _) = tokio_tungstenite::connect_async(&ws_url).await?;
             let sub_message = serde_json::to_string(&self.subscription)?;
             ws.send(Message::Text(sub_message)).await?;
@@ -172,10 +192,10 @@ impl Exchange for Binance {
         api_channel: NormalizedRestApiRequest,
     ) -> Result<BinanceRestApiResponse, RestApiError> {
         let api_response = match api_channel {
-            NormalizedRestApiRequest::AllCurrencies => Self::get_all_symbols(web_client)
+            NormalizedRestApiRequest::AllCurrencies => self.get_all_symbols(web_client)
                 .await
                 .map(|v| BinanceRestApiResponse::Symbols(v)),
-            NormalizedRestApiRequest::AllInstruments => Self::get_all_instruments(web_client)
+            NormalizedRestApiRequest::AllInstruments => self.get_all_instruments(web_client)
                 .await
                 .map(|v| BinanceRestApiResponse::Instruments(v)),
         };
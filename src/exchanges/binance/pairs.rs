@@ -66,7 +66,9 @@ impl TryFrom<NormalizedTradingPair> for BinanceTradingPair {
 
             if let Some(d) = delim {
                 let mut split = raw_pair.split(d);
-                return Ok(BinanceTradingPair(format!("{}{}", split.next().unwrap().to_uppercase(), split.next().unwrap().to_uppercase())));
+                if let (Some(base), Some(quote)) = (split.next(), split.next()) {
+                    return Ok(BinanceTradingPair(format!("{}{}", base.to_uppercase(), quote.to_uppercase())));
+                }
             }
 
             let new_str = raw_pair.replace(['_', '-', '/'], "");
@@ -5,7 +5,7 @@ use tracing::warn;
 
 use crate::{
     binance::BinanceTradingPair,
-    normalized::types::{NormalizedQuote, TimeOrUpdateId},
+    normalized::types::{NormalizedQuote, Number, TimeOrUpdateId},
     CexExchange
 };
 
@@ -16,16 +16,16 @@ pub struct BinanceBookTicker {
     pub pair:                BinanceTradingPair,
     #[serde(rename = "A")]
     #[serde_as(as = "DisplayFromStr")]
-    pub best_ask_amt:        f64,
+    pub best_ask_amt:        Number,
     #[serde(rename = "a")]
     #[serde_as(as = "DisplayFromStr")]
-    pub best_ask_price:      f64,
+    pub best_ask_price:      Number,
     #[serde(rename = "B")]
     #[serde_as(as = "DisplayFromStr")]
-    pub best_bid_amt:        f64,
+    pub best_bid_amt:        Number,
     #[serde(rename = "b")]
     #[serde_as(as = "DisplayFromStr")]
-    pub best_bid_price:      f64,
+    pub best_bid_price:      Number,
     #[serde(rename = "u")]
     pub orderbook_update_id: u64,
     #[serde(default = "Utc::now")]
@@ -5,7 +5,7 @@ use tracing::warn;
 
 use crate::{
     binance::BinanceTradingPair,
-    normalized::types::{BidAsk, NormalizedL2, TimeOrUpdateId},
+    normalized::types::{BidAsk, NormalizedL2, Number, TimeOrUpdateId},
     CexExchange
 };
 
@@ -23,11 +23,11 @@ pub struct BinanceDiffDepth {
     #[serde(rename = "u")]
     pub last_orderbook_update_id: u64,
     #[serde(rename = "b")]
-    #[serde_as(as = "Vec<Vec<DisplayFromStr>>")]
-    pub bids: Vec<Vec<f64>>,
+    #[serde_as(as = "Vec<(DisplayFromStr, DisplayFromStr)>")]
+    pub bids: Vec<(Number, Number)>,
     #[serde(rename = "a")]
-    #[serde_as(as = "Vec<Vec<DisplayFromStr>>")]
-    pub asks: Vec<Vec<f64>>
+    #[serde_as(as = "Vec<(DisplayFromStr, DisplayFromStr)>")]
+    pub asks: Vec<(Number, Number)>
 }
 
 impl BinanceDiffDepth {
@@ -38,15 +38,15 @@ impl BinanceDiffDepth {
             bids:               self
                 .bids
                 .into_iter()
-                .map(|bid| BidAsk::new(bid[0], bid[1]))
+                .map(|(price, amount)| BidAsk::new(price, amount))
                 .collect(),
             asks:               self
                 .asks
                 .into_iter()
-                .map(|ask| BidAsk::new(ask[0], ask[1]))
+                .map(|(price, amount)| BidAsk::new(price, amount))
                 .collect(),
             orderbook_ids_time: TimeOrUpdateId::new()
-                .with_time(DateTime::from_timestamp_millis(self.event_time as i64).unwrap())
+                .with_time(DateTime::from_timestamp_millis(self.event_time as i64).unwrap_or_default())
                 .with_first_update_id(self.first_orderbook_update_id)
                 .with_last_update_id(self.last_orderbook_update_id)
         }
@@ -58,13 +58,13 @@ impl PartialEq<NormalizedL2> for BinanceDiffDepth {
         let our_bids = self
             .bids
             .iter()
-            .map(|bid| BidAsk::new(bid[0], bid[1]))
+            .map(|&(price, amount)| BidAsk::new(price, amount))
             .collect::<Vec<_>>();
 
         let our_asks = self
             .asks
             .iter()
-            .map(|ask| BidAsk::new(ask[0], ask[1]))
+            .map(|&(price, amount)| BidAsk::new(price, amount))
             .collect::<Vec<_>>();
         let equals = other.exchange == CexExchange::Binance
             && other.pair == self.pair.normalize()
@@ -74,7 +74,7 @@ impl PartialEq<NormalizedL2> for BinanceDiffDepth {
             && other.asks.len() == our_asks.len()
             && other.orderbook_ids_time
                 == TimeOrUpdateId::new()
-                    .with_time(DateTime::from_timestamp_millis(self.event_time as i64).unwrap())
+                    .with_time(DateTime::from_timestamp_millis(self.event_time as i64).unwrap_or_default())
                     .with_first_update_id(self.first_orderbook_update_id)
                     .with_last_update_id(self.last_orderbook_update_id);
 
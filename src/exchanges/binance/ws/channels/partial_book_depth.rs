@@ -4,7 +4,7 @@ use tracing::warn;
 
 use crate::{
     binance::BinanceTradingPair,
-    normalized::types::{BidAsk, NormalizedL2, TimeOrUpdateId},
+    normalized::types::{BidAsk, NormalizedL2, Number, TimeOrUpdateId},
     CexExchange
 };
 
@@ -12,8 +12,8 @@ use crate::{
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, PartialOrd)]
 pub struct BinancePartialBookDepth {
     pub pair:                BinanceTradingPair,
-    pub bids:                Vec<Vec<f64>>,
-    pub asks:                Vec<Vec<f64>>,
+    pub bids:                Vec<(Number, Number)>,
+    pub asks:                Vec<(Number, Number)>,
     pub orderbook_update_id: u64
 }
 
@@ -25,12 +25,12 @@ impl BinancePartialBookDepth {
             bids:               self
                 .bids
                 .into_iter()
-                .map(|bid| BidAsk::new(bid[0], bid[1]))
+                .map(|(price, amount)| BidAsk::new(price, amount))
                 .collect(),
             asks:               self
                 .asks
                 .into_iter()
-                .map(|ask| BidAsk::new(ask[0], ask[1]))
+                .map(|(price, amount)| BidAsk::new(price, amount))
                 .collect(),
             orderbook_ids_time: TimeOrUpdateId::new().with_first_update_id(self.orderbook_update_id)
         }
@@ -42,13 +42,13 @@ impl PartialEq<NormalizedL2> for BinancePartialBookDepth {
         let our_bids = self
             .bids
             .iter()
-            .map(|bid| BidAsk::new(bid[0], bid[1]))
+            .map(|&(price, amount)| BidAsk::new(price, amount))
             .collect::<Vec<_>>();
 
         let our_asks = self
             .asks
             .iter()
-            .map(|ask| BidAsk::new(ask[0], ask[1]))
+            .map(|&(price, amount)| BidAsk::new(price, amount))
             .collect::<Vec<_>>();
         let equals = other.exchange == CexExchange::Binance
             && other.pair == self.pair.normalize()
@@ -4,7 +4,7 @@ use serde_with::{serde_as, DisplayFromStr};
 use tracing::warn;
 
 use crate::{
-    exchanges::{binance::pairs::BinanceTradingPair, normalized::types::NormalizedTrade},
+    exchanges::{binance::pairs::BinanceTradingPair, normalized::types::{NormalizedTrade, Number, TradeSide}},
     CexExchange
 };
 
@@ -15,10 +15,10 @@ pub struct BinanceTrade {
     pub pair:                  BinanceTradingPair,
     #[serde(rename = "p")]
     #[serde_as(as = "DisplayFromStr")]
-    pub price:                 f64,
+    pub price:                 Number,
     #[serde(rename = "q")]
     #[serde_as(as = "DisplayFromStr")]
-    pub quantity:              f64,
+    pub quantity:              Number,
     #[serde(rename = "t")]
     pub trade_id:              u64,
     #[serde(rename = "m")]
@@ -32,8 +32,9 @@ impl BinanceTrade {
         NormalizedTrade {
             exchange: CexExchange::Binance,
             pair:     self.pair.normalize(),
-            time:     DateTime::from_timestamp_millis(self.trade_time as i64).unwrap(),
-            side:     if self.is_buyer_market_maker { "buy".to_string() } else { "sell".to_string() },
+            time:     DateTime::from_timestamp_millis(self.trade_time as i64).unwrap_or_default(),
+            side:     if self.is_buyer_market_maker { TradeSide::Sell } else { TradeSide::Buy },
+            raw_side: self.is_buyer_market_maker.to_string(),
             price:    self.price,
             amount:   self.quantity,
             trade_id: Some(self.trade_id.to_string())
@@ -45,11 +46,12 @@ impl PartialEq<NormalizedTrade> for BinanceTrade {
     fn eq(&self, other: &NormalizedTrade) -> bool {
         let equals = other.exchange == CexExchange::Binance
             && other.pair == self.pair.normalize()
-            && other.time == DateTime::from_timestamp_millis(self.trade_time as i64).unwrap()
-            && other.side == if self.is_buyer_market_maker { "buy".to_string() } else { "sell".to_string() }
+            && other.time == DateTime::from_timestamp_millis(self.trade_time as i64).unwrap_or_default()
+            && other.side == if self.is_buyer_market_maker { TradeSide::Sell } else { TradeSide::Buy }
+            && other.raw_side == self.is_buyer_market_maker.to_string()
             && other.price == self.price
             && other.amount == self.quantity
-            && other.trade_id.as_ref().unwrap() == &self.trade_id.to_string();
+            && other.trade_id == Some(self.trade_id.to_string());
 
         if !equals {
             warn!(target: "cex-exchanges::binance", "binance trade: {:?}", self);
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::{
@@ -22,7 +23,9 @@ impl<'de> Deserialize<'de> for BinanceWsMessage {
     where
         D: serde::Deserializer<'de>
     {
-        Ok(private::BinanceWsMessageRaw::deserialize(deserializer)?.into())
+        private::BinanceWsMessageRaw::deserialize(deserializer)?
+            .try_into()
+            .map_err(serde::de::Error::custom)
     }
 }
 
@@ -40,6 +43,17 @@ impl BinanceWsMessage {
             }
         }
     }
+
+    /// the exchange's timestamp of the message w/o normalizing it, see
+    /// [NormalizedWsDataTypes::exchange_time]
+    pub fn exchange_time(&self) -> Option<DateTime<Utc>> {
+        match self {
+            BinanceWsMessage::Trade(v) => Some(DateTime::from_timestamp_millis(v.trade_time as i64).unwrap_or_default()),
+            BinanceWsMessage::BookTicker(v) => Some(v.local_update_time),
+            BinanceWsMessage::DiffDepth(v) => Some(DateTime::from_timestamp_millis(v.event_time as i64).unwrap_or_default()),
+            BinanceWsMessage::PartialBookDepth(_) | BinanceWsMessage::SubscriptionResponse(_) => None
+        }
+    }
 }
 
 impl PartialEq<NormalizedWsDataTypes> for BinanceWsMessage {
@@ -48,6 +62,7 @@ impl PartialEq<NormalizedWsDataTypes> for BinanceWsMessage {
             (BinanceWsMessage::Trade(this), NormalizedWsDataTypes::Trade(that)) => this == that,
             (BinanceWsMessage::BookTicker(this), NormalizedWsDataTypes::Quote(that)) => this == that,
             (BinanceWsMessage::DiffDepth(this), NormalizedWsDataTypes::L2(that)) => this == that,
+            (BinanceWsMessage::PartialBookDepth(this), NormalizedWsDataTypes::L2(that)) => this == that,
             (BinanceWsMessage::SubscriptionResponse { .. }, NormalizedWsDataTypes::Other { .. }) => true,
             _ => false
         }
@@ -63,7 +78,10 @@ mod private {
     use serde_with::{serde_as, DisplayFromStr};
 
     use super::*;
-    use crate::binance::{ws::channels::BinancePartialBookDepth, BinanceTradingPair};
+    use crate::{
+        binance::{ws::channels::BinancePartialBookDepth, BinanceTradingPair},
+        normalized::types::Number
+    };
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
     #[serde(untagged)]
@@ -90,30 +108,34 @@ mod private {
         PartialBookDepth(BinancePartialBookDepthRaw)
     }
 
-    impl From<BinanceWsMessageRaw> for BinanceWsMessage {
-        fn from(value: BinanceWsMessageRaw) -> Self {
-            match value {
+    impl TryFrom<BinanceWsMessageRaw> for BinanceWsMessage {
+        type Error = eyre::Report;
+
+        fn try_from(value: BinanceWsMessageRaw) -> Result<Self, Self::Error> {
+            let msg = match value {
                 BinanceWsMessageRaw::DataMsg(msg) => match msg.data {
                     BinanceWsMessageDataRaw::Trade(v) => BinanceWsMessage::Trade(v),
                     BinanceWsMessageDataRaw::BookTicker(v) => BinanceWsMessage::BookTicker(v),
                     BinanceWsMessageDataRaw::DiffDepth(v) => BinanceWsMessage::DiffDepth(v),
-                    BinanceWsMessageDataRaw::PartialBookDepth(v) => BinanceWsMessage::PartialBookDepth((v, parse_stream_to_pair(msg.stream)).into())
+                    BinanceWsMessageDataRaw::PartialBookDepth(v) => BinanceWsMessage::PartialBookDepth((v, parse_stream_to_pair(&msg.stream)?).into())
                 },
                 BinanceWsMessageRaw::OtherMsg(msg) => BinanceWsMessage::SubscriptionResponse(msg),
                 BinanceWsMessageRaw::DiffDepth(v) => BinanceWsMessage::DiffDepth(v),
                 BinanceWsMessageRaw::BookTicker(v) => BinanceWsMessage::BookTicker(v),
                 BinanceWsMessageRaw::Trade(v) => BinanceWsMessage::Trade(v)
-            }
+            };
+
+            Ok(msg)
         }
     }
 
     #[serde_as]
     #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, PartialOrd)]
     struct BinancePartialBookDepthRaw {
-        #[serde_as(as = "Vec<Vec<DisplayFromStr>>")]
-        bids:                Vec<Vec<f64>>,
-        #[serde_as(as = "Vec<Vec<DisplayFromStr>>")]
-        asks:                Vec<Vec<f64>>,
+        #[serde_as(as = "Vec<(DisplayFromStr, DisplayFromStr)>")]
+        bids:                Vec<(Number, Number)>,
+        #[serde_as(as = "Vec<(DisplayFromStr, DisplayFromStr)>")]
+        asks:                Vec<(Number, Number)>,
         #[serde(rename = "lastUpdateId")]
         orderbook_update_id: u64
     }
@@ -125,8 +147,8 @@ mod private {
         }
     }
 
-    fn parse_stream_to_pair(stream: String) -> BinanceTradingPair {
-        let parts = stream.split("@").next().unwrap();
-        BinanceTradingPair::new_checked(parts).unwrap()
+    fn parse_stream_to_pair(stream: &str) -> eyre::Result<BinanceTradingPair> {
+        let pair = stream.split('@').next().unwrap_or_default();
+        BinanceTradingPair::new_checked(pair)
     }
 }
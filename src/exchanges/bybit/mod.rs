@@ -11,12 +11,13 @@ pub mod ws;
 use serde::Deserialize;
 use tokio::net::TcpStream;
 use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::error;
 
 use self::{
     rest_api::{BybitAllCoins, BybitAllInstruments, BybitRestApiResponse},
     ws::{BybitSubscription, BybitWsMessage},
 };
-use super::traits::SpecificWsSubscription;
+use super::{traits::SpecificWsSubscription, ExchangeEnvironment};
 use crate::{
     binance::Binance,
     clients::{rest_api::RestApiError, ws::WsError},
@@ -27,23 +28,26 @@ use crate::{
 
 const WSS_URL: &str = "wss://stream.bybit.com/v5/public/spot";
 const BASE_REST_API_URL: &str = "https://api.bybit.com";
+const TESTNET_WSS_URL: &str = "wss://stream-testnet.bybit.com/v5/public/spot";
+const TESTNET_BASE_REST_API_URL: &str = "https://api-testnet.bybit.com";
 
 #[derive(Debug, Default, Clone)]
 pub struct Bybit {
     subscription: BybitSubscription,
+    environment:  ExchangeEnvironment,
 }
 
 impl Bybit {
     pub fn new_ws_subscription(subscription: BybitSubscription) -> Self {
-        Self { subscription }
+        Self { subscription, environment: ExchangeEnvironment::Production }
     }
 
-    pub async fn get_all_instruments(web_client: &reqwest::Client) -> Result<BybitAllInstruments, RestApiError> {
+    pub async fn get_all_instruments(&self, web_client: &reqwest::Client) -> Result<BybitAllInstruments, RestApiError> {
         let categories = ["linear", "inverse", "option", "spot"];
 
         let mut instruments = Vec::new();
         for cat in categories {
-            let url = format!("{BASE_REST_API_URL}/v5/market/instruments-info?category={cat}");
+            let url = format!("{}/v5/market/instruments-info?category={cat}", self.endpoint_url(BASE_REST_API_URL, Some(TESTNET_BASE_REST_API_URL))?);
             let cat_instruments: BybitAllInstruments = Self::simple_rest_api_request(web_client, url).await?;
             instruments.extend(cat_instruments.instruments);
         }
@@ -68,14 +72,16 @@ impl Bybit {
     //     Ok(val)
     // }
 
-    pub async fn get_all_coins(web_client: &reqwest::Client) -> Result<BybitAllCoins, RestApiError> {
+    pub async fn get_all_coins(&self, web_client: &reqwest::Client) -> Result<BybitAllCoins, RestApiError> {
         let mut binance_coins = Binance::default()
+            .with_environment(self.environment.clone())
             .rest_api_call(web_client, NormalizedRestApiRequest::AllCurrencies)
             .await?
             .take_symbols()
             .unwrap();
 
-        let bybit_instrument_symbols = Self::get_all_instruments(web_client)
+        let bybit_instrument_symbols = self
+            .get_all_instruments(web_client)
             .await?
             .instruments
             .into_iter()
@@ -107,12 +113,26 @@ impl Exchange for Bybit {
     const STREAM_TIMEOUT_MS: Option<u64> = None;
 
     fn remove_bad_pair(&mut self, bad_pair: NormalizedTradingPair) -> bool {
-        let pair = bad_pair.try_into().unwrap();
-        self.subscription.remove_pair(&pair)
+        match BybitTradingPair::try_from(bad_pair) {
+            Ok(pair) => self.subscription.remove_pair(&pair),
+            Err(e) => {
+                error!(target: "cex-exchanges::bybit", "could not convert the bad pair, keeping the subscription: {:?}", e);
+                false
+            }
+        }
+    }
+
+    fn with_environment(mut self, environment: ExchangeEnvironment) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    fn environment(&self) -> &ExchangeEnvironment {
+        &self.environment
     }
 
     async fn make_ws_connection(&self) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WsError> {
-        let (mut ws, _) = tokio_tungstenite::connect_async(WSS_URL).await?;
+        let (mut ws, _) = tokio_tungstenite::connect_async(self.endpoint_url(WSS_URL, Some(TESTNET_WSS_URL))?).await?;
 
         let sub_message = serde_json::to_string(&self.subscription)?;
         ws.send(Message::Text(sub_message)).await?;
@@ -122,8 +142,8 @@ impl Exchange for Bybit {
 
     async fn rest_api_call(&self, web_client: &reqwest::Client, api_channel: NormalizedRestApiRequest) -> Result<BybitRestApiResponse, RestApiError> {
         let api_response = match api_channel {
-            NormalizedRestApiRequest::AllCurrencies => BybitRestApiResponse::Coins(Self::get_all_coins(web_client).await?),
-            NormalizedRestApiRequest::AllInstruments => BybitRestApiResponse::Instruments(Self::get_all_instruments(web_client).await?),
+            NormalizedRestApiRequest::AllCurrencies => BybitRestApiResponse::Coins(self.get_all_coins(web_client).await?),
+            NormalizedRestApiRequest::AllInstruments => BybitRestApiResponse::Instruments(self.get_all_instruments(web_client).await?),
         };
 
         Ok(api_response)
@@ -5,7 +5,7 @@ use tracing::warn;
 
 use crate::{
     bybit::BybitTradingPair,
-    normalized::types::{NormalizedQuote, TimeOrUpdateId},
+    normalized::types::{NormalizedQuote, Number, TimeOrUpdateId},
     CexExchange
 };
 
@@ -35,7 +35,7 @@ impl BybitOrderbook {
                 bid_amount:         b.amount,
                 bid_price:          b.price,
                 orderbook_ids_time: TimeOrUpdateId::new()
-                    .with_time(DateTime::<Utc>::from_timestamp_millis(self.timestamp as i64).unwrap())
+                    .with_time(DateTime::<Utc>::from_timestamp_millis(self.timestamp as i64).unwrap_or_default())
                     .with_first_update_id(self.data.update_id)
             })
         } else {
@@ -65,7 +65,7 @@ impl PartialEq<Vec<NormalizedQuote>> for BybitOrderbook {
                 && other_data.pair == self.data.symbol.normalize()
                 && other_data.orderbook_ids_time
                     == TimeOrUpdateId::new()
-                        .with_time(DateTime::<Utc>::from_timestamp_millis(self.timestamp as i64).unwrap())
+                        .with_time(DateTime::<Utc>::from_timestamp_millis(self.timestamp as i64).unwrap_or_default())
                         .with_first_update_id(self.data.update_id)
                 && data_bids
         });
@@ -96,8 +96,8 @@ pub struct BybitOrderbookInner {
 
 #[derive(Debug, Serialize, Clone, PartialEq, PartialOrd)]
 pub struct BybitBidAsk {
-    pub price:  f64,
-    pub amount: f64
+    pub price:  Number,
+    pub amount: Number
 }
 
 impl<'de> Deserialize<'de> for BybitBidAsk {
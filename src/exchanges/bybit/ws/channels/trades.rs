@@ -4,7 +4,7 @@ use serde_with::{serde_as, DisplayFromStr};
 use tracing::warn;
 
 use crate::{
-    exchanges::{bybit::pairs::BybitTradingPair, normalized::types::NormalizedTrade},
+    exchanges::{bybit::pairs::BybitTradingPair, normalized::types::{NormalizedTrade, Number, TradeSide}},
     CexExchange
 };
 
@@ -26,8 +26,9 @@ impl BybitTrade {
             .map(|inner| NormalizedTrade {
                 exchange: CexExchange::Bybit,
                 pair:     inner.pair.normalize(),
-                time:     DateTime::<Utc>::from_timestamp_millis(inner.timestamp as i64).unwrap(),
-                side:     inner.side.to_lowercase(),
+                time:     DateTime::<Utc>::from_timestamp_millis(inner.timestamp as i64).unwrap_or_default(),
+                side:     TradeSide::parse(&inner.side),
+                raw_side: inner.side,
                 price:    inner.price,
                 amount:   inner.amount,
                 trade_id: Some(inner.trade_id.to_string())
@@ -40,14 +41,14 @@ impl PartialEq<Vec<NormalizedTrade>> for BybitTrade {
     fn eq(&self, other: &Vec<NormalizedTrade>) -> bool {
         let all_equals = self.data.iter().all(|inner| {
             other.iter().any(|other_data| {
-                let equals = other_data.exchange == CexExchange::Bybit
+                other_data.exchange == CexExchange::Bybit
                     && other_data.pair == inner.pair.normalize()
-                    && other_data.time == DateTime::<Utc>::from_timestamp_millis(inner.timestamp as i64).unwrap()
-                    && other_data.side == inner.side.to_lowercase()
+                    && other_data.time == DateTime::<Utc>::from_timestamp_millis(inner.timestamp as i64).unwrap_or_default()
+                    && other_data.side == TradeSide::parse(&inner.side)
+                    && other_data.raw_side == inner.side
                     && other_data.price == inner.price
                     && other_data.amount == inner.amount
-                    && other_data.trade_id.as_ref().unwrap() == &inner.trade_id.to_string();
-                equals
+                    && other_data.trade_id == Some(inner.trade_id.to_string())
             })
         });
 
@@ -71,10 +72,10 @@ pub struct BybitTradeInner {
     pub side: String,
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "v")]
-    pub amount: f64,
+    pub amount: Number,
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "p")]
-    pub price: f64,
+    pub price: Number,
     #[serde(rename = "L")]
     pub direction_of_price_change: Option<String>,
     #[serde_as(as = "DisplayFromStr")]
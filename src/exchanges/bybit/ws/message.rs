@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -26,13 +27,12 @@ impl BybitWsMessage {
             return Ok(Self::OrderbookL1(try_ticker?))
         }
 
-        let conn_id = value.get("conn_id");
-        let success = value.get("success");
+        let conn_id = value.get("conn_id").and_then(Value::as_str);
+        let success = value.get("success").and_then(Value::as_bool);
         let ret_msg = value.get("ret_msg");
-        if let (Some(c), Some(s)) = (conn_id, success) {
-            let success_val = s.as_bool().unwrap();
+        if let (Some(c), Some(success_val)) = (conn_id, success) {
             if success_val {
-                return Ok(Self::SuscriptionResponse { id: c.as_str().unwrap().to_string(), msg: success_val.to_string() })
+                return Ok(Self::SuscriptionResponse { id: c.to_string(), msg: success_val.to_string() })
             } else {
                 if let Some(re) = ret_msg.map(|r| r.as_str()).flatten() {
                     if re.contains("Invalid symbol") {
@@ -41,7 +41,7 @@ impl BybitWsMessage {
                         pair = pair.replace("orderbook.1.", "");
                         pair = pair.replace("publicTrade.", "");
                         if let Ok(bytbit_pair) = BybitTradingPair::new_checked(&pair) {
-                            return Ok(Self::InvalidSymbol { id: c.as_str().unwrap().to_string(), pair: bytbit_pair, msg: re.to_string() })
+                            return Ok(Self::InvalidSymbol { id: c.to_string(), pair: bytbit_pair, msg: re.to_string() })
                         }
                     }
                 }
@@ -78,6 +78,22 @@ impl BybitWsMessage {
             }
         }
     }
+
+    /// the exchange's timestamp of the message w/o normalizing it, see
+    /// [NormalizedWsDataTypes::exchange_time]
+    pub fn exchange_time(&self) -> Option<DateTime<Utc>> {
+        match self {
+            BybitWsMessage::Trade(v) => v
+                .data
+                .iter()
+                .map(|inner| DateTime::from_timestamp_millis(inner.timestamp as i64).unwrap_or_default())
+                .max(),
+            // quotes w/o a bid & ask aren't normalized
+            BybitWsMessage::OrderbookL1(v) => (!v.data.best_bid.is_empty() && !v.data.best_ask.is_empty())
+                .then(|| DateTime::from_timestamp_millis(v.timestamp as i64).unwrap_or_default()),
+            BybitWsMessage::SuscriptionResponse { .. } | BybitWsMessage::InvalidSymbol { .. } => None
+        }
+    }
 }
 
 impl PartialEq<NormalizedWsDataTypes> for BybitWsMessage {
@@ -86,6 +102,7 @@ impl PartialEq<NormalizedWsDataTypes> for BybitWsMessage {
             (BybitWsMessage::Trade(this), NormalizedWsDataTypes::Trades(that)) => this == that,
             (BybitWsMessage::OrderbookL1(this), NormalizedWsDataTypes::Quotes(that)) => this == that,
             (BybitWsMessage::SuscriptionResponse { .. }, NormalizedWsDataTypes::Other { .. }) => true,
+            (BybitWsMessage::InvalidSymbol { pair, .. }, NormalizedWsDataTypes::RemovedPair { bad_pair, .. }) => &pair.normalize() == bad_pair,
             _ => false
         }
     }
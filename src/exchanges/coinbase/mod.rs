@@ -17,7 +17,7 @@ use self::{
     rest_api::CoinbaseRestApiResponse,
     ws::{CoinbaseSubscription, CoinbaseWsMessage},
 };
-use super::traits::SpecificWsSubscription;
+use super::{traits::SpecificWsSubscription, ExchangeEnvironment};
 use crate::{
     clients::{rest_api::RestApiError, ws::WsError},
     normalized::{rest_api::NormalizedRestApiRequest, types::NormalizedTradingPair},
@@ -26,25 +26,30 @@ use crate::{
 
 const WSS_URL: &str = "wss://ws-feed.exchange.coinbase.com";
 const BASE_REST_API_URL: &str = "https://api.exchange.coinbase.com";
+const SANDBOX_WSS_URL: &str = "wss://ws-feed-public.sandbox.exchange.coinbase.com";
+const SANDBOX_BASE_REST_API_URL: &str = "https://api-public.sandbox.exchange.coinbase.com";
 
 #[derive(Debug, Default, Clone)]
 pub struct Coinbase {
     subscription: CoinbaseSubscription,
+    environment:  ExchangeEnvironment,
 }
 
 impl Coinbase {
     pub fn new_ws_subscription(subscription: CoinbaseSubscription) -> Self {
-        Self { subscription }
+        Self { subscription, environment: ExchangeEnvironment::Production }
     }
 
-    pub async fn get_all_currencies(web_client: &reqwest::Client) -> Result<CoinbaseAllCurrencies, RestApiError> {
-        let currencies: CoinbaseAllCurrencies = Self::simple_rest_api_request(web_client, format!("{BASE_REST_API_URL}/currencies")).await?;
+    pub async fn get_all_currencies(&self, web_client: &reqwest::Client) -> Result<CoinbaseAllCurrencies, RestApiError> {
+        let url = format!("{}/currencies", self.endpoint_url(BASE_REST_API_URL, Some(SANDBOX_BASE_REST_API_URL))?);
+        let currencies: CoinbaseAllCurrencies = Self::simple_rest_api_request(web_client, url).await?;
         info!(target: "cex-exchanges::coinbase", "found {} currencies", currencies.currencies.len());
         Ok(currencies)
     }
 
-    pub async fn get_all_products(web_client: &reqwest::Client) -> Result<CoinbaseAllProducts, RestApiError> {
-        let products: CoinbaseAllProducts = Self::simple_rest_api_request(web_client, format!("{BASE_REST_API_URL}/products")).await?;
+    pub async fn get_all_products(&self, web_client: &reqwest::Client) -> Result<CoinbaseAllProducts, RestApiError> {
+        let url = format!("{}/products", self.endpoint_url(BASE_REST_API_URL, Some(SANDBOX_BASE_REST_API_URL))?);
+        let products: CoinbaseAllProducts = Self::simple_rest_api_request(web_client, url).await?;
         info!(target: "cex-exchanges::coinbase", "found {} products", products.products.len());
         Ok(products)
     }
@@ -76,12 +81,26 @@ impl Exchange for Coinbase {
     const STREAM_TIMEOUT_MS: Option<u64> = None;
 
     fn remove_bad_pair(&mut self, bad_pair: NormalizedTradingPair) -> bool {
-        let pair: CoinbaseTradingPair = bad_pair.try_into().unwrap();
-        self.subscription.remove_pair(&pair)
+        match CoinbaseTradingPair::try_from(bad_pair) {
+            Ok(pair) => self.subscription.remove_pair(&pair),
+            Err(e) => {
+                error!(target: "cex-exchanges::coinbase", "could not convert the bad pair, keeping the subscription: {:?}", e);
+                false
+            }
+        }
+    }
+
+    fn with_environment(mut self, environment: ExchangeEnvironment) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    fn environment(&self) -> &ExchangeEnvironment {
+        &self.environment
     }
 
     async fn make_ws_connection(&self) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WsError> {
-        let (mut ws, _) = tokio_tungstenite::connect_async(WSS_URL).await?;
+        let (mut ws, _) = tokio_tungstenite::connect_async(self.endpoint_url(WSS_URL, Some(SANDBOX_WSS_URL))?).await?;
 
         let sub_message = serde_json::to_string(&self.subscription)?;
         ws.send(Message::Text(sub_message)).await?;
@@ -95,10 +114,10 @@ impl Exchange for Coinbase {
         api_channel: NormalizedRestApiRequest,
     ) -> Result<CoinbaseRestApiResponse, RestApiError> {
         let api_response = match api_channel {
-            NormalizedRestApiRequest::AllCurrencies => Self::get_all_currencies(web_client)
+            NormalizedRestApiRequest::AllCurrencies => self.get_all_currencies(web_client)
                 .await
                 .map(|v| CoinbaseRestApiResponse::Currencies(v)),
-            NormalizedRestApiRequest::AllInstruments => Self::get_all_products(web_client)
+            NormalizedRestApiRequest::AllInstruments => self.get_all_products(web_client)
                 .await
                 .map(|v| CoinbaseRestApiResponse::Products(v)),
         };
@@ -16,7 +16,10 @@ impl CoinbaseTradingPair {
 
     pub fn normalize(&self) -> NormalizedTradingPair {
         let mut split = self.0.split('-');
-        NormalizedTradingPair::new_base_quote(CexExchange::Coinbase, split.next().unwrap(), split.next().unwrap(), Some('-'), None)
+        match (split.next(), split.next()) {
+            (Some(base), Some(quote)) => NormalizedTradingPair::new_base_quote(CexExchange::Coinbase, base, quote, Some('-'), None),
+            _ => NormalizedTradingPair::new_no_base_quote(CexExchange::Coinbase, &self.0)
+        }
     }
 
     pub fn parse_for_bad_pair(value: &str) -> Option<Self> {
@@ -60,7 +63,9 @@ impl TryFrom<NormalizedTradingPair> for CoinbaseTradingPair {
 
             if let Some(d) = delim {
                 let mut split = raw_pair.split(d);
-                return Ok(CoinbaseTradingPair(format!("{}-{}", split.next().unwrap().to_uppercase(), split.next().unwrap().to_uppercase())));
+                if let (Some(base), Some(quote)) = (split.next(), split.next()) {
+                    return Ok(CoinbaseTradingPair(format!("{}-{}", base.to_uppercase(), quote.to_uppercase())));
+                }
             }
 
             let new_str = raw_pair.replace(['_', '/'], "-");
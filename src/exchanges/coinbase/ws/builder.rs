@@ -7,7 +7,7 @@ use crate::{
     coinbase::Coinbase,
     normalized::ws::NormalizedWsChannels,
     traits::{SpecificWsBuilder, SpecificWsSubscription},
-    CexExchange,
+    CexExchange, ExchangeEnvironment,
 };
 
 #[derive(Debug, Clone, Default)]
@@ -16,10 +16,15 @@ pub struct CoinbaseWsBuilder {
 }
 
 impl CoinbaseWsBuilder {
-    async fn build_from_all_instruments_util(channels: &[CoinbaseWsChannelKind], streams_per_connection: Option<usize>) -> eyre::Result<Self> {
+    async fn build_from_all_instruments_util(
+        channels: &[CoinbaseWsChannelKind],
+        streams_per_connection: Option<usize>,
+        environment: &ExchangeEnvironment,
+    ) -> eyre::Result<Self> {
         let mut this = Self::default();
 
         let all_symbols_vec = ExchangeApi::new()
+            .with_environment(environment.clone())
             .all_instruments::<Coinbase>()
             .await?
             .take_coinbase_instruments(true)
@@ -109,12 +114,13 @@ impl SpecificWsBuilder for CoinbaseWsBuilder {
         Ok(MultiWsStreamBuilder::new(split_exchange))
     }
 
-    async fn build_from_all_instruments<'a>(
+    async fn build_from_all_instruments_with_environment<'a>(
         channels: &'a [<Self::WsChannel as crate::traits::SpecificWsChannel>::ChannelKind],
         streams_per_connection: Option<usize>,
         _: Option<CexExchange>,
+        environment: ExchangeEnvironment,
     ) -> eyre::Result<MultiWsStreamBuilder<Self::CexExchange>> {
-        let this = Self::build_from_all_instruments_util(channels, streams_per_connection).await?;
+        let this = Self::build_from_all_instruments_util(channels, streams_per_connection, &environment).await?;
 
         let all_streams = this
             .channels
@@ -127,7 +133,7 @@ impl SpecificWsBuilder for CoinbaseWsBuilder {
             })
             .collect::<Vec<_>>();
 
-        Ok(MultiWsStreamBuilder::new(all_streams))
+        Ok(MultiWsStreamBuilder::new(all_streams).with_environment(environment))
     }
 
     fn make_from_normalized_map(map: Vec<NormalizedWsChannels>, _: Option<CexExchange>) -> eyre::Result<Self>
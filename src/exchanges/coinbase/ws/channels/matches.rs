@@ -4,7 +4,7 @@ use serde_with::{serde_as, DisplayFromStr};
 use tracing::warn;
 
 use crate::{
-    exchanges::{coinbase::pairs::CoinbaseTradingPair, normalized::types::NormalizedTrade},
+    exchanges::{coinbase::pairs::CoinbaseTradingPair, normalized::types::{NormalizedTrade, Number, TradeSide}},
     CexExchange
 };
 
@@ -18,9 +18,9 @@ pub struct CoinbaseMatches {
     pub time:           DateTime<Utc>,
     pub product_id:     CoinbaseTradingPair,
     #[serde_as(as = "DisplayFromStr")]
-    pub size:           f64,
+    pub size:           Number,
     #[serde_as(as = "DisplayFromStr")]
-    pub price:          f64,
+    pub price:          Number,
     pub side:           String
 }
 
@@ -30,7 +30,8 @@ impl CoinbaseMatches {
             exchange: CexExchange::Coinbase,
             pair:     self.product_id.normalize(),
             time:     self.time,
-            side:     self.side.to_lowercase(),
+            side:     TradeSide::parse(&self.side).opposite(),
+            raw_side: self.side,
             price:    self.price,
             amount:   self.size,
             trade_id: Some(self.trade_id.to_string())
@@ -43,10 +44,11 @@ impl PartialEq<NormalizedTrade> for CoinbaseMatches {
         let equals = other.exchange == CexExchange::Coinbase
             && other.pair == self.product_id.normalize()
             && other.time == self.time
-            && other.side == self.side.to_lowercase()
+            && other.side == TradeSide::parse(&self.side).opposite()
+            && other.raw_side == self.side
             && other.price == self.price
             && other.amount == self.size
-            && other.trade_id.as_ref().unwrap() == &self.trade_id.to_string();
+            && other.trade_id == Some(self.trade_id.to_string());
 
         if !equals {
             warn!(target: "cex-exchanges::coinbase", "coinbase matches: {:?}", self);
@@ -5,7 +5,7 @@ use tracing::warn;
 
 use crate::{
     exchanges::{coinbase::pairs::CoinbaseTradingPair, normalized::types::NormalizedQuote},
-    normalized::types::TimeOrUpdateId,
+    normalized::types::{Number, TimeOrUpdateId},
     CexExchange
 };
 
@@ -15,29 +15,29 @@ pub struct CoinbaseTicker {
     pub sequence:      Option<u64>,
     pub product_id:    CoinbaseTradingPair,
     #[serde_as(as = "DisplayFromStr")]
-    pub price:         f64,
+    pub price:         Number,
     #[serde_as(as = "DisplayFromStr")]
-    pub open_24h:      f64,
+    pub open_24h:      Number,
     #[serde_as(as = "DisplayFromStr")]
-    pub low_24h:       f64,
+    pub low_24h:       Number,
     #[serde_as(as = "DisplayFromStr")]
-    pub high_24h:      f64,
+    pub high_24h:      Number,
     #[serde_as(as = "DisplayFromStr")]
-    pub volume_30d:    f64,
+    pub volume_30d:    Number,
     #[serde_as(as = "DisplayFromStr")]
-    pub best_bid:      f64,
+    pub best_bid:      Number,
     #[serde_as(as = "DisplayFromStr")]
-    pub best_bid_size: f64,
+    pub best_bid_size: Number,
     #[serde_as(as = "DisplayFromStr")]
-    pub best_ask:      f64,
+    pub best_ask:      Number,
     #[serde_as(as = "DisplayFromStr")]
-    pub best_ask_size: f64,
+    pub best_ask_size: Number,
     pub side:          Option<String>,
     // #[serde(default = "Utc::now")]
     pub time:          DateTime<Utc>,
     pub trade_id:      Option<u64>,
     #[serde_as(as = "Option<DisplayFromStr>")]
-    pub last_size:     Option<f64>
+    pub last_size:     Option<Number>
 }
 
 impl CoinbaseTicker {
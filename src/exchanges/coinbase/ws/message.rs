@@ -1,3 +1,5 @@
+use chrono::{DateTime, Utc};
+
 use super::channels::{CoinbaseMatches, CoinbaseStatus, CoinbaseTicker};
 use crate::{clients::ws::CriticalWsMessage, coinbase::CoinbaseTradingPair, exchanges::normalized::ws::NormalizedWsDataTypes, CexExchange};
 
@@ -29,6 +31,16 @@ impl CoinbaseWsMessage {
             }
         }
     }
+
+    /// the exchange's timestamp of the message w/o normalizing it, see
+    /// [NormalizedWsDataTypes::exchange_time]
+    pub fn exchange_time(&self) -> Option<DateTime<Utc>> {
+        match self {
+            CoinbaseWsMessage::Matches(v) => Some(v.time),
+            CoinbaseWsMessage::Ticker(v) => Some(v.time),
+            CoinbaseWsMessage::Status(_) | CoinbaseWsMessage::Subscriptions(_) | CoinbaseWsMessage::Error(_) => None
+        }
+    }
 }
 
 impl PartialEq<NormalizedWsDataTypes> for CoinbaseWsMessage {
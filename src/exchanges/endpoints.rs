@@ -0,0 +1,132 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::CexExchange;
+
+/// the deployment of an exchange that the ws & rest requests are sent to
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExchangeEnvironment {
+    #[default]
+    Production,
+    /// the exchange's testnet, sandbox or demo trading deployment:
+    /// - binance: `testnet.binance.vision`
+    /// - coinbase: `sandbox.exchange.coinbase.com`
+    /// - okex: demo trading (`wspap.okx.com` & the `x-simulated-trading`
+    ///   header)
+    /// - bybit: `api-testnet.bybit.com`
+    /// - kucoin: none
+    Testnet,
+    /// every request is sent to `<base_url>/<exchange>`, keeping the path &
+    /// query of the exchange's url (ex: a local `MockExchangeServer` w/ the
+    /// `mock` feature)
+    Custom(String)
+}
+
+impl ExchangeEnvironment {
+    /// resolves one of the exchange's urls for this environment, where
+    /// `testnet_url` is `None` if the exchange has no testnet for it
+    pub(crate) fn resolve_url(
+        &self,
+        exchange: CexExchange,
+        production_url: &str,
+        testnet_url: Option<&str>
+    ) -> Result<String, UnsupportedEnvironment> {
+        match self {
+            ExchangeEnvironment::Production => Ok(production_url.to_string()),
+            ExchangeEnvironment::Testnet => testnet_url
+                .map(ToString::to_string)
+                .ok_or_else(|| UnsupportedEnvironment { exchange, environment: self.clone() }),
+            ExchangeEnvironment::Custom(base_url) => Ok(custom_url(exchange, production_url, base_url))
+        }
+    }
+}
+
+impl Display for ExchangeEnvironment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExchangeEnvironment::Production => write!(f, "production"),
+            ExchangeEnvironment::Testnet => write!(f, "testnet"),
+            ExchangeEnvironment::Custom(base_url) => write!(f, "custom ({base_url})")
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{exchange} has no {environment} environment")]
+pub struct UnsupportedEnvironment {
+    pub exchange:    CexExchange,
+    pub environment: ExchangeEnvironment
+}
+
+/// rewrites one of the exchange's urls to `<base_url>/<exchange><path>`,
+/// keeping the path & query of the original url
+///
+/// ws urls keep a ws scheme, ex: `wss://stream.binance.com:443/stream` with a
+/// base url of `http://127.0.0.1:8080` becomes
+/// `ws://127.0.0.1:8080/binance/stream`
+fn custom_url(exchange: CexExchange, url: &str, base_url: &str) -> String {
+    let (scheme, host_and_path) = url.split_once("://").unwrap_or(("https", url));
+    let path = host_and_path
+        .find('/')
+        .map(|idx| &host_and_path[idx..])
+        .unwrap_or_default();
+
+    let base_url = base_url.trim_end_matches('/');
+    let base_url = if scheme.starts_with("ws") {
+        if let Some(rest) = base_url.strip_prefix("https://") {
+            format!("wss://{rest}")
+        } else if let Some(rest) = base_url.strip_prefix("http://") {
+            format!("ws://{rest}")
+        } else {
+            base_url.to_string()
+        }
+    } else {
+        base_url.to_string()
+    };
+
+    format!("{base_url}/{exchange}{path}")
+}
+
+#[cfg(test)]
+#[cfg(feature = "us")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_url() {
+        let base_url = "http://127.0.0.1:8080/";
+
+        assert_eq!(custom_url(CexExchange::Okex, "wss://ws.okx.com:8443/ws/v5/public", base_url), "ws://127.0.0.1:8080/okex/ws/v5/public");
+        assert_eq!(custom_url(CexExchange::Coinbase, "wss://ws-feed.exchange.coinbase.com", base_url), "ws://127.0.0.1:8080/coinbase");
+        assert_eq!(
+            custom_url(CexExchange::Okex, "https://www.okx.com/api/v5/public/instruments?instType=SPOT", base_url),
+            "http://127.0.0.1:8080/okex/api/v5/public/instruments?instType=SPOT"
+        );
+        assert_eq!(
+            custom_url(CexExchange::Coinbase, "https://api.exchange.coinbase.com/products", "https://mock.local"),
+            "https://mock.local/coinbase/products"
+        );
+    }
+
+    #[test]
+    fn test_resolve_url() {
+        let production = "wss://ws.okx.com:8443/ws/v5/public";
+        let testnet = "wss://wspap.okx.com:8443/ws/v5/public";
+
+        assert_eq!(ExchangeEnvironment::Production.resolve_url(CexExchange::Okex, production, Some(testnet)).unwrap(), production);
+        assert_eq!(ExchangeEnvironment::Testnet.resolve_url(CexExchange::Okex, production, Some(testnet)).unwrap(), testnet);
+        assert_eq!(
+            ExchangeEnvironment::Custom("http://127.0.0.1:8080".to_string())
+                .resolve_url(CexExchange::Okex, production, Some(testnet))
+                .unwrap(),
+            "ws://127.0.0.1:8080/okex/ws/v5/public"
+        );
+        assert_eq!(
+            ExchangeEnvironment::Testnet.resolve_url(CexExchange::Okex, production, None),
+            Err(UnsupportedEnvironment { exchange: CexExchange::Okex, environment: ExchangeEnvironment::Testnet })
+        );
+    }
+}
@@ -9,12 +9,13 @@ pub mod ws;
 use serde::Deserialize;
 use tokio::net::TcpStream;
 use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::error;
 
 use self::{
     rest_api::KucoinRestApiResponse,
     ws::{KucoinMultiSubscription, KucoinSubscription, KucoinWsEndpointResponse, KucoinWsMessage},
 };
-use super::traits::SpecificWsSubscription;
+use super::{traits::SpecificWsSubscription, ExchangeEnvironment};
 use crate::{
     clients::{rest_api::RestApiError, ws::WsError},
     exchanges::Exchange,
@@ -23,20 +24,24 @@ use crate::{
 };
 
 const BASE_REST_API_URL: &str = "https://api.kucoin.com";
+/// kucoin's sandbox was shut down, so there is no testnet
+const TESTNET_BASE_REST_API_URL: Option<&str> = None;
 
 #[derive(Debug, Default, Clone)]
 pub struct Kucoin {
     subscriptions: Vec<KucoinSubscription>,
+    environment:   ExchangeEnvironment,
 }
 
 impl Kucoin {
     pub fn new_ws_subscription(subscription: KucoinMultiSubscription) -> Self {
-        Self { subscriptions: subscription.all_subscriptions() }
+        Self { subscriptions: subscription.all_subscriptions(), environment: ExchangeEnvironment::Production }
     }
 
-    pub async fn get_websocket_endpoint() -> Result<KucoinWsEndpointResponse, WsError> {
+    pub async fn get_websocket_endpoint(&self) -> Result<KucoinWsEndpointResponse, WsError> {
+        let url = format!("{}/api/v1/bullet-public", self.endpoint_url(BASE_REST_API_URL, TESTNET_BASE_REST_API_URL)?);
         let data: KucoinWsEndpointResponse = reqwest::Client::new()
-            .post(&format!("{BASE_REST_API_URL}/api/v1/bullet-public"))
+            .post(url)
             .send()
             .await
             .map_err(|e| WsError::WebInitializationError(e.to_string()))?
@@ -64,14 +69,29 @@ impl Exchange for Kucoin {
     const STREAM_TIMEOUT_MS: Option<u64> = None;
 
     fn remove_bad_pair(&mut self, bad_pair: NormalizedTradingPair) -> bool {
-        let pair = bad_pair.try_into().unwrap();
+        let pair = match KucoinTradingPair::try_from(bad_pair) {
+            Ok(pair) => pair,
+            Err(e) => {
+                error!(target: "cex-exchanges::kucoin", "could not convert the bad pair, keeping the subscription: {:?}", e);
+                return false
+            }
+        };
         self.subscriptions.retain_mut(|sub| !sub.remove_pair(&pair));
 
         self.subscriptions.is_empty()
     }
 
+    fn with_environment(mut self, environment: ExchangeEnvironment) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    fn environment(&self) -> &ExchangeEnvironment {
+        &self.environment
+    }
+
     async fn make_ws_connection(&self) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WsError> {
-        let dyn_url = Self::get_websocket_endpoint().await?;
+        let dyn_url = self.get_websocket_endpoint().await?;
 
         let wss_endpoint = dyn_url
             .get_ws_endpoint()
@@ -96,10 +116,12 @@ impl Exchange for Kucoin {
     ) -> Result<KucoinRestApiResponse, RestApiError> {
         let api_response = match api_channel {
             NormalizedRestApiRequest::AllCurrencies => {
-                KucoinRestApiResponse::Currencies(Self::simple_rest_api_request(web_client, format!("{BASE_REST_API_URL}/api/v3/currencies")).await?)
+                let url = format!("{}/api/v3/currencies", self.endpoint_url(BASE_REST_API_URL, TESTNET_BASE_REST_API_URL)?);
+                KucoinRestApiResponse::Currencies(Self::simple_rest_api_request(web_client, url).await?)
             }
             NormalizedRestApiRequest::AllInstruments => {
-                KucoinRestApiResponse::Symbols(Self::simple_rest_api_request(web_client, format!("{BASE_REST_API_URL}/api/v2/symbols")).await?)
+                let url = format!("{}/api/v2/symbols", self.endpoint_url(BASE_REST_API_URL, TESTNET_BASE_REST_API_URL)?);
+                KucoinRestApiResponse::Symbols(Self::simple_rest_api_request(web_client, url).await?)
             }
         };
 
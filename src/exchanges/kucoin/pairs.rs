@@ -16,7 +16,10 @@ impl KucoinTradingPair {
 
     pub fn normalize(&self) -> NormalizedTradingPair {
         let mut split = self.0.split('-');
-        NormalizedTradingPair::new_base_quote(CexExchange::Kucoin, split.next().unwrap(), split.next().unwrap(), Some('-'), None)
+        match (split.next(), split.next()) {
+            (Some(base), Some(quote)) => NormalizedTradingPair::new_base_quote(CexExchange::Kucoin, base, quote, Some('-'), None),
+            _ => NormalizedTradingPair::new_no_base_quote(CexExchange::Kucoin, &self.0)
+        }
     }
 }
 
@@ -56,7 +59,9 @@ impl TryFrom<NormalizedTradingPair> for KucoinTradingPair {
 
             if let Some(d) = delim {
                 let mut split = raw_pair.split(d);
-                return Ok(KucoinTradingPair(format!("{}-{}", split.next().unwrap().to_uppercase(), split.next().unwrap().to_uppercase())));
+                if let (Some(base), Some(quote)) = (split.next(), split.next()) {
+                    return Ok(KucoinTradingPair(format!("{}-{}", base.to_uppercase(), quote.to_uppercase())));
+                }
             }
 
             let new_str = raw_pair.replace(['_', '/'], "-");
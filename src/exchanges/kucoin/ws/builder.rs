@@ -7,7 +7,7 @@ use crate::{
     kucoin::Kucoin,
     normalized::ws::NormalizedWsChannels,
     traits::SpecificWsBuilder,
-    CexExchange,
+    CexExchange, ExchangeEnvironment,
 };
 
 /// There is a limit of 300 connections per attempt every 5 minutes per IP.
@@ -21,10 +21,15 @@ pub struct KucoinWsBuilder {
 }
 
 impl KucoinWsBuilder {
-    async fn build_from_all_instruments_util(channels: &[KucoinWsChannelKind], streams_per_connection: Option<usize>) -> eyre::Result<Self> {
+    async fn build_from_all_instruments_util(
+        channels: &[KucoinWsChannelKind],
+        streams_per_connection: Option<usize>,
+        environment: &ExchangeEnvironment,
+    ) -> eyre::Result<Self> {
         let mut this = Self::default();
 
         let mut all_symbols_vec = ExchangeApi::new()
+            .with_environment(environment.clone())
             .all_instruments::<Kucoin>()
             .await?
             .take_kucoin_instruments(true)
@@ -117,12 +122,13 @@ impl SpecificWsBuilder for KucoinWsBuilder {
         Ok(MultiWsStreamBuilder::new(split_exchange))
     }
 
-    async fn build_from_all_instruments<'a>(
+    async fn build_from_all_instruments_with_environment<'a>(
         channels: &'a [<Self::WsChannel as crate::traits::SpecificWsChannel>::ChannelKind],
         streams_per_connection: Option<usize>,
         _: Option<CexExchange>,
+        environment: ExchangeEnvironment,
     ) -> eyre::Result<MultiWsStreamBuilder<Self::CexExchange>> {
-        let this = Self::build_from_all_instruments_util(channels, streams_per_connection).await?;
+        let this = Self::build_from_all_instruments_util(channels, streams_per_connection, &environment).await?;
 
         let all_streams = this
             .channels
@@ -135,7 +141,7 @@ impl SpecificWsBuilder for KucoinWsBuilder {
             })
             .collect::<Vec<_>>();
 
-        Ok(MultiWsStreamBuilder::new(all_streams))
+        Ok(MultiWsStreamBuilder::new(all_streams).with_environment(environment))
     }
 
     fn make_from_normalized_map(map: Vec<NormalizedWsChannels>, _: Option<CexExchange>) -> eyre::Result<Self>
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use tracing::warn;
 
-use crate::{kucoin::KucoinTradingPair, normalized::types::NormalizedTrade, CexExchange};
+use crate::{kucoin::KucoinTradingPair, normalized::types::{NormalizedTrade, Number, TradeSide}, CexExchange};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, PartialOrd)]
@@ -21,7 +21,8 @@ impl KucoinMatch {
             exchange: CexExchange::Kucoin,
             pair:     self.data.symbol.normalize(),
             time:     DateTime::<Utc>::from_timestamp_nanos(self.data.timestamp as i64),
-            side:     self.data.side.to_lowercase(),
+            side:     TradeSide::parse(&self.data.side),
+            raw_side: self.data.side,
             price:    self.data.price,
             amount:   self.data.size,
             trade_id: Some(self.data.trade_id)
@@ -34,10 +35,11 @@ impl PartialEq<NormalizedTrade> for KucoinMatch {
         let equals = other.exchange == CexExchange::Kucoin
             && other.pair == self.data.symbol.normalize()
             && other.time == DateTime::<Utc>::from_timestamp_nanos(self.data.timestamp as i64)
-            && other.side == self.data.side.to_lowercase()
+            && other.side == TradeSide::parse(&self.data.side)
+            && other.raw_side == self.data.side
             && other.price == self.data.price
             && other.amount == self.data.size
-            && other.trade_id.as_ref().unwrap() == &self.data.trade_id.to_string();
+            && other.trade_id == Some(self.data.trade_id.to_string());
 
         if !equals {
             warn!(target: "cex-exchanges::kucoin", "kucoin match: {:?}", self);
@@ -58,9 +60,9 @@ pub struct KucoinMatchInner {
     pub symbol:         KucoinTradingPair,
     pub side:           String,
     #[serde_as(as = "DisplayFromStr")]
-    pub price:          f64,
+    pub price:          Number,
     #[serde_as(as = "DisplayFromStr")]
-    pub size:           f64,
+    pub size:           Number,
     #[serde(rename = "tradeId")]
     pub trade_id:       String,
     #[serde(rename = "takerOrderId")]
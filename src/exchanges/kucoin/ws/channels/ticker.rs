@@ -5,7 +5,7 @@ use tracing::warn;
 
 use crate::{
     kucoin::KucoinTradingPair,
-    normalized::types::{NormalizedQuote, TimeOrUpdateId},
+    normalized::types::{NormalizedQuote, Number, TimeOrUpdateId},
     CexExchange
 };
 
@@ -63,21 +63,21 @@ pub struct KucoinTickerInner {
     #[serde_as(as = "DisplayFromStr")]
     pub sequence:       u64,
     #[serde_as(as = "DisplayFromStr")]
-    pub price:          f64,
+    pub price:          Number,
     #[serde_as(as = "DisplayFromStr")]
-    pub size:           f64,
+    pub size:           Number,
     #[serde(rename = "bestAsk")]
     #[serde_as(as = "DisplayFromStr")]
-    pub best_ask_price: f64,
+    pub best_ask_price: Number,
     #[serde(rename = "bestAskSize")]
     #[serde_as(as = "DisplayFromStr")]
-    pub best_ask_size:  f64,
+    pub best_ask_size:  Number,
     #[serde(rename = "bestBid")]
     #[serde_as(as = "DisplayFromStr")]
-    pub best_bid_price: f64,
+    pub best_bid_price: Number,
     #[serde(rename = "bestBidSize")]
     #[serde_as(as = "DisplayFromStr")]
-    pub best_bid_size:  f64,
+    pub best_bid_size:  Number,
     #[serde(rename = "time")]
     pub timestamp:      u64
 }
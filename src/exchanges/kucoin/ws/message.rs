@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use eyre::Ok;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -26,10 +27,10 @@ impl KucoinWsMessage {
             return Ok(Self::Ticker(try_ticker?))
         }
 
-        let id = value.get("id");
-        let msg = value.get("type");
+        let id = value.get("id").and_then(Value::as_str);
+        let msg = value.get("type").and_then(Value::as_str);
         if let (Some(i), Some(m)) = (id, msg) {
-            return Ok(Self::SuscriptionResponse { id: i.as_str().unwrap().to_string(), msg: m.as_str().unwrap().to_string() })
+            return Ok(Self::SuscriptionResponse { id: i.to_string(), msg: m.to_string() })
         }
 
         Err(eyre::ErrReport::msg(format!("Could not deserialize kucoin ws message: {:?}", value)))
@@ -57,6 +58,16 @@ impl KucoinWsMessage {
             }
         }
     }
+
+    /// the exchange's timestamp of the message w/o normalizing it, see
+    /// [NormalizedWsDataTypes::exchange_time]
+    pub fn exchange_time(&self) -> Option<DateTime<Utc>> {
+        match self {
+            KucoinWsMessage::Match(v) => Some(DateTime::from_timestamp_nanos(v.data.timestamp as i64)),
+            KucoinWsMessage::Ticker(v) => Some(DateTime::from_timestamp_nanos(v.data.timestamp as i64)),
+            KucoinWsMessage::SuscriptionResponse { .. } => None
+        }
+    }
 }
 
 impl PartialEq<NormalizedWsDataTypes> for KucoinWsMessage {
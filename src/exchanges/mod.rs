@@ -2,6 +2,9 @@ pub mod normalized;
 
 pub mod traits;
 
+mod endpoints;
+pub use endpoints::*;
+
 #[cfg(feature = "non-us")]
 pub mod binance;
 
@@ -19,12 +22,11 @@ pub mod okex;
 
 use std::{
     fmt::{Debug, Display},
-    pin::Pin,
     str::FromStr,
 };
 
 use clap::ValueEnum;
-use futures::{Future, Stream};
+use futures::Future;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
@@ -51,7 +53,7 @@ use self::{
 use crate::{
     clients::{
         rest_api::{ExchangeApi, RestApiError},
-        ws::{CriticalWsMessage, MultiWsStream, WsError, WsStreamConfig},
+        ws::{BoxedWsStream, CriticalWsMessage, MultiWsStream, PayloadCompression, WsError, WsStreamConfig, WsThreadHandle},
     },
     exchanges::normalized::rest_api::CombinedRestApiResponse,
     traits::ExchangeFilter,
@@ -82,11 +84,13 @@ impl CexExchange {
         config: WsStreamConfig,
         connections_per_stream: Option<usize>,
         exch_currency_proxy: Option<CexExchange>,
+        environment: &ExchangeEnvironment,
     ) -> eyre::Result<MultiWsStream> {
         let res = match self {
             #[cfg(feature = "us")]
             CexExchange::Coinbase => CoinbaseWsBuilder::make_from_normalized_map(map, None)?
                 .build_many_packed(connections_per_stream)?
+                .with_environment(environment.clone())
                 .build_multistream_unconnected(config),
             #[cfg(feature = "us")]
             CexExchange::Okex => OkexWsBuilder::make_from_normalized_map(
@@ -97,24 +101,45 @@ impl CexExchange {
                 Some(exch_currency_proxy.unwrap_or(CexExchange::Binance)),
             )?
             .build_many_packed(connections_per_stream)?
+            .with_environment(environment.clone())
             .build_multistream_unconnected(config),
             #[cfg(feature = "non-us")]
             CexExchange::Binance => BinanceWsBuilder::make_from_normalized_map(map, None)?
                 .build_many_packed(connections_per_stream)?
+                .with_environment(environment.clone())
                 .build_multistream_unconnected(config),
             #[cfg(feature = "non-us")]
             CexExchange::Kucoin => KucoinWsBuilder::make_from_normalized_map(map, None)?
                 .build_many_packed(connections_per_stream)?
+                .with_environment(environment.clone())
                 .build_multistream_unconnected(config),
             #[cfg(feature = "non-us")]
             CexExchange::Bybit => BybitWsBuilder::make_from_normalized_map(map, None)?
                 .build_many_packed(connections_per_stream)?
+                .with_environment(environment.clone())
                 .build_multistream_unconnected(config),
         };
 
         Ok(res)
     }
 
+    /// the streams [Self::build_multistream_ws_from_normalized] packs into a
+    /// connection w/o `connections_per_stream`
+    pub(crate) fn max_streams_per_connection(self) -> usize {
+        match self {
+            #[cfg(feature = "us")]
+            CexExchange::Coinbase => CoinbaseWsBuilder::MAX_STREAMS_PER_CONNECTION,
+            #[cfg(feature = "us")]
+            CexExchange::Okex => OkexWsBuilder::MAX_STREAMS_PER_CONNECTION,
+            #[cfg(feature = "non-us")]
+            CexExchange::Binance => BinanceWsBuilder::MAX_STREAMS_PER_CONNECTION,
+            #[cfg(feature = "non-us")]
+            CexExchange::Kucoin => KucoinWsBuilder::MAX_STREAMS_PER_CONNECTION,
+            #[cfg(feature = "non-us")]
+            CexExchange::Bybit => BybitWsBuilder::MAX_STREAMS_PER_CONNECTION,
+        }
+    }
+
     pub(crate) fn build_multithreaded_multistream_ws_from_normalized(
         self,
         map: Vec<NormalizedWsChannels>,
@@ -122,11 +147,13 @@ impl CexExchange {
         config: WsStreamConfig,
         connections_per_stream: Option<usize>,
         number_threads: usize,
-    ) -> eyre::Result<UnboundedReceiver<CombinedWsMessage>> {
+        environment: &ExchangeEnvironment,
+    ) -> eyre::Result<(UnboundedReceiver<CombinedWsMessage>, WsThreadHandle)> {
         let res = match self {
             #[cfg(feature = "us")]
             CexExchange::Coinbase => CoinbaseWsBuilder::make_from_normalized_map(map, None)?
                 .build_many_packed(connections_per_stream)?
+                .with_environment(environment.clone())
                 .spawn_multithreaded(number_threads, config),
             #[cfg(feature = "us")]
             CexExchange::Okex => OkexWsBuilder::make_from_normalized_map(
@@ -137,18 +164,22 @@ impl CexExchange {
                 Some(exch_currency_proxy.unwrap_or(CexExchange::Binance)),
             )?
             .build_many_packed(connections_per_stream)?
+            .with_environment(environment.clone())
             .spawn_multithreaded(number_threads, config),
             #[cfg(feature = "non-us")]
             CexExchange::Binance => BinanceWsBuilder::make_from_normalized_map(map, None)?
                 .build_many_packed(connections_per_stream)?
+                .with_environment(environment.clone())
                 .spawn_multithreaded(number_threads, config),
             #[cfg(feature = "non-us")]
             CexExchange::Kucoin => KucoinWsBuilder::make_from_normalized_map(map, None)?
                 .build_many_packed(connections_per_stream)?
+                .with_environment(environment.clone())
                 .spawn_multithreaded(number_threads, config),
             #[cfg(feature = "non-us")]
             CexExchange::Bybit => BybitWsBuilder::make_from_normalized_map(map, None)?
                 .build_many_packed(connections_per_stream)?
+                .with_environment(environment.clone())
                 .spawn_multithreaded(number_threads, config),
         };
 
@@ -161,11 +192,13 @@ impl CexExchange {
         exch_currency_proxy: Option<CexExchange>,
         config: WsStreamConfig,
         connections_per_stream: Option<usize>,
-    ) -> eyre::Result<Vec<Pin<Box<dyn Stream<Item = CombinedWsMessage> + Send>>>> {
+        environment: &ExchangeEnvironment,
+    ) -> eyre::Result<Vec<BoxedWsStream>> {
         let res = match self {
             #[cfg(feature = "us")]
             CexExchange::Coinbase => CoinbaseWsBuilder::make_from_normalized_map(map, None)?
                 .build_many_packed(connections_per_stream)?
+                .with_environment(environment.clone())
                 .build_multistream_unconnected_raw(config),
             #[cfg(feature = "us")]
             CexExchange::Okex => OkexWsBuilder::make_from_normalized_map(
@@ -176,18 +209,22 @@ impl CexExchange {
                 Some(exch_currency_proxy.unwrap_or(CexExchange::Binance)),
             )?
             .build_many_packed(connections_per_stream)?
+            .with_environment(environment.clone())
             .build_multistream_unconnected_raw(config),
             #[cfg(feature = "non-us")]
             CexExchange::Binance => BinanceWsBuilder::make_from_normalized_map(map, None)?
                 .build_many_packed(connections_per_stream)?
+                .with_environment(environment.clone())
                 .build_multistream_unconnected_raw(config),
             #[cfg(feature = "non-us")]
             CexExchange::Kucoin => KucoinWsBuilder::make_from_normalized_map(map, None)?
                 .build_many_packed(connections_per_stream)?
+                .with_environment(environment.clone())
                 .build_multistream_unconnected_raw(config),
             #[cfg(feature = "non-us")]
             CexExchange::Bybit => BybitWsBuilder::make_from_normalized_map(map, None)?
                 .build_many_packed(connections_per_stream)?
+                .with_environment(environment.clone())
                 .build_multistream_unconnected_raw(config),
         };
 
@@ -205,7 +242,19 @@ impl CexExchange {
     where
         F: ExchangeFilter<NormalizedCurrency>,
     {
-        let exchange_api = ExchangeApi::new();
+        self.get_all_currencies_with_api(&ExchangeApi::new(), filter)
+            .await
+    }
+
+    /// [Self::get_all_currencies] using the endpoints of `exchange_api`
+    pub(crate) async fn get_all_currencies_with_api<F>(
+        self,
+        exchange_api: &ExchangeApi,
+        filter: Option<F>,
+    ) -> Result<Vec<NormalizedCurrency>, RestApiError>
+    where
+        F: ExchangeFilter<NormalizedCurrency>,
+    {
         let out = match self {
             #[cfg(feature = "us")]
             CexExchange::Coinbase => exchange_api
@@ -258,7 +307,19 @@ impl CexExchange {
     where
         F: ExchangeFilter<NormalizedInstrument>,
     {
-        let exchange_api = ExchangeApi::new();
+        self.get_all_instruments_with_api(&ExchangeApi::new(), filter)
+            .await
+    }
+
+    /// [Self::get_all_instruments] using the endpoints of `exchange_api`
+    pub(crate) async fn get_all_instruments_with_api<F>(
+        self,
+        exchange_api: &ExchangeApi,
+        filter: Option<F>,
+    ) -> Result<Vec<NormalizedInstrument>, RestApiError>
+    where
+        F: ExchangeFilter<NormalizedInstrument>,
+    {
         let out = match self {
             #[cfg(feature = "us")]
             CexExchange::Coinbase => exchange_api
@@ -333,6 +394,21 @@ impl CexExchange {
         Ok(out)
     }
 
+    /// if the exchange's ws can subscribe to the channel, only binance
+    /// implements l2 (5, 10 or 20 levels or the diff depth, every 100ms or
+    /// 1000ms)
+    #[allow(unreachable_patterns)]
+    pub fn supports_ws_channel(self, channel: &NormalizedWsChannels) -> bool {
+        match (self, channel) {
+            (_, NormalizedWsChannels::Trades(_) | NormalizedWsChannels::Quotes(_)) => true,
+            #[cfg(feature = "non-us")]
+            (CexExchange::Binance, NormalizedWsChannels::L2(depth, update_speed, _)) => {
+                matches!(depth, None | Some(5 | 10 | 20)) && matches!(update_speed, None | Some(100 | 1000))
+            }
+            _ => false,
+        }
+    }
+
     #[allow(unreachable_patterns)]
     pub fn bad_pair(self, msg: String) -> Option<NormalizedTradingPair> {
         match self {
@@ -390,6 +466,27 @@ pub trait Exchange: Clone + Default + Send + Unpin + 'static {
 
     fn remove_bad_pair(&mut self, bad_pair: NormalizedTradingPair) -> bool;
 
+    /// sets the deployment of the exchange the ws & rest requests are sent to
+    fn with_environment(self, environment: ExchangeEnvironment) -> Self;
+
+    fn environment(&self) -> &ExchangeEnvironment;
+
+    /// resolves one of the exchange's urls for its environment, where
+    /// `testnet_url` is `None` if the exchange has no testnet for it
+    fn endpoint_url(&self, production_url: &str, testnet_url: Option<&str>) -> Result<String, UnsupportedEnvironment> {
+        self.environment()
+            .resolve_url(Self::EXCHANGE, production_url, testnet_url)
+    }
+
+    /// decodes a binary ws frame into the text passed to the
+    /// [Self::WsMessage] deserializer
+    ///
+    /// by default gzip/zlib/deflate compressed frames are inflated, override
+    /// for exchanges with a custom binary encoding
+    fn decode_binary_message(payload: &[u8]) -> std::io::Result<String> {
+        PayloadCompression::Auto.decode(payload)
+    }
+
     fn make_ws_connection(&self) -> impl Future<Output = Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WsError>> + Send;
 
     fn make_owned_ws_connection(self) -> impl Future<Output = Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WsError>> + Send {
@@ -1,3 +1,4 @@
+pub mod registry;
 pub mod rest_api;
 pub mod types;
 pub mod ws;
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+/// maps the symbols of the same asset to a single symbol, ex: 'XBT' to 'BTC'
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetAliases {
+    aliases: HashMap<String, String>
+}
+
+impl Default for AssetAliases {
+    /// 'XBT' is 'BTC'
+    fn default() -> Self {
+        Self::none().with_alias("XBT", "BTC")
+    }
+}
+
+impl AssetAliases {
+    /// every symbol is its own asset
+    pub fn none() -> Self {
+        Self { aliases: HashMap::new() }
+    }
+
+    pub fn with_alias(mut self, symbol: &str, canonical: &str) -> Self {
+        self.aliases
+            .insert(symbol.to_uppercase(), canonical.to_uppercase());
+        self
+    }
+
+    /// treats 'USDT' & 'USDC' as 'USD', so 'BTC-USD' on coinbase & 'BTCUSDT' on
+    /// binance are the same pair
+    pub fn with_usd_stablecoins(self) -> Self {
+        self.with_alias("USDT", "USD").with_alias("USDC", "USD")
+    }
+
+    /// the canonical symbol of the asset
+    pub fn resolve(&self, symbol: &str) -> String {
+        let symbol = symbol.to_uppercase();
+        self.aliases.get(&symbol).cloned().unwrap_or(symbol)
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
+
+use futures::future::try_join_all;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clients::rest_api::{ExchangeApi, RestApiError},
+    exchanges::normalized::types::{Blockchain, NormalizedCurrency},
+    traits::EmptyFilter,
+    CexExchange
+};
+
+/// a token contract on a blockchain
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ContractAddress {
+    pub blockchain: Blockchain,
+    pub address:    String
+}
+
+impl ContractAddress {
+    /// `None` for an empty address, evm ('0x' hex) addresses are lowercased as
+    /// they are case insensitive
+    pub fn new(blockchain: Blockchain, address: &str) -> Option<Self> {
+        let address = address.trim();
+        if address.is_empty() {
+            return None
+        }
+
+        let address = if is_evm_address(address) { address.to_lowercase() } else { address.to_string() };
+        Some(Self { blockchain, address })
+    }
+}
+
+fn is_evm_address(address: &str) -> bool {
+    address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .is_some_and(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// the currencies of every exchange that are the same asset
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UnifiedAsset {
+    pub contracts:  Vec<ContractAddress>,
+    pub currencies: Vec<NormalizedCurrency>
+}
+
+impl UnifiedAsset {
+    /// the symbols of the asset across the exchanges
+    pub fn symbols(&self) -> Vec<&str> {
+        let mut symbols = self
+            .currencies
+            .iter()
+            .map(|curr| curr.symbol.as_str())
+            .collect::<Vec<_>>();
+        symbols.sort();
+        symbols.dedup();
+
+        symbols
+    }
+
+    pub fn exchanges(&self) -> Vec<CexExchange> {
+        let mut exchanges = self
+            .currencies
+            .iter()
+            .map(|curr| curr.exchange)
+            .collect::<Vec<_>>();
+        exchanges.sort();
+        exchanges.dedup();
+
+        exchanges
+    }
+}
+
+/// a symbol with different contract addresses on the same blockchain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolCollision {
+    pub symbol:     String,
+    pub blockchain: Blockchain,
+    /// the exchanges listing each address
+    pub addresses:  BTreeMap<String, Vec<CexExchange>>
+}
+
+/// merges the currencies of every exchange into [UnifiedAsset]s, currencies
+/// sharing a contract address are the same asset
+///
+/// the blockchains of a wrapped currency combined into its unwrapped currency
+/// (ex: WETH's into ETH's) are skipped, they are the wrapped asset's contracts
+#[derive(Debug, Clone, Default)]
+pub struct CurrencyReconciler {
+    assets:             Vec<UnifiedAsset>,
+    by_contract:        HashMap<ContractAddress, usize>,
+    by_exchange_symbol: HashMap<(CexExchange, String), usize>,
+    collisions:         Vec<SymbolCollision>
+}
+
+impl CurrencyReconciler {
+    pub fn new(currencies: Vec<NormalizedCurrency>) -> Self {
+        let mut parents = (0..currencies.len()).collect::<Vec<_>>();
+        let mut first_with_contract = HashMap::new();
+        currencies.iter().enumerate().for_each(|(idx, curr)| {
+            contracts(curr).for_each(|contract| match first_with_contract.entry(contract) {
+                Entry::Occupied(entry) => union(&mut parents, *entry.get(), idx),
+                Entry::Vacant(entry) => {
+                    entry.insert(idx);
+                }
+            })
+        });
+
+        let mut this = Self { collisions: find_collisions(&currencies), ..Default::default() };
+        let mut roots = HashMap::new();
+        currencies.into_iter().enumerate().for_each(|(idx, curr)| {
+            let asset_idx = *roots.entry(find(&mut parents, idx)).or_insert_with(|| {
+                this.assets.push(UnifiedAsset::default());
+                this.assets.len() - 1
+            });
+
+            contracts(&curr).for_each(|contract| {
+                this.by_contract.insert(contract.clone(), asset_idx);
+                this.assets[asset_idx].contracts.push(contract);
+            });
+            this.by_exchange_symbol
+                .insert((curr.exchange, curr.symbol.to_uppercase()), asset_idx);
+            this.assets[asset_idx].currencies.push(curr);
+        });
+
+        this.assets.iter_mut().for_each(|asset| {
+            asset.contracts.sort();
+            asset.contracts.dedup();
+        });
+
+        this
+    }
+
+    /// reconciles the currencies of every exchange
+    pub async fn from_exchanges(exchanges: &[CexExchange]) -> Result<Self, RestApiError> {
+        Self::from_exchanges_with_api(&ExchangeApi::new(), exchanges).await
+    }
+
+    /// [Self::from_exchanges] using the endpoints of `exchange_api`
+    pub async fn from_exchanges_with_api(exchange_api: &ExchangeApi, exchanges: &[CexExchange]) -> Result<Self, RestApiError> {
+        let currencies = try_join_all(
+            exchanges
+                .iter()
+                .map(|exchange| exchange.get_all_currencies_with_api(exchange_api, None::<EmptyFilter>))
+        )
+        .await?;
+
+        Ok(Self::new(currencies.into_iter().flatten().collect()))
+    }
+
+    pub fn assets(&self) -> &[UnifiedAsset] {
+        &self.assets
+    }
+
+    pub fn by_address(&self, blockchain: Blockchain, address: &str) -> Option<&UnifiedAsset> {
+        let contract = ContractAddress::new(blockchain, address)?;
+        self.by_contract
+            .get(&contract)
+            .map(|idx| &self.assets[*idx])
+    }
+
+    /// every asset with a currency of the symbol, more than one if the symbol
+    /// is used for different assets
+    pub fn by_symbol(&self, symbol: &str) -> Vec<&UnifiedAsset> {
+        self.assets
+            .iter()
+            .filter(|asset| {
+                asset
+                    .currencies
+                    .iter()
+                    .any(|curr| curr.symbol.eq_ignore_ascii_case(symbol))
+            })
+            .collect()
+    }
+
+    pub fn by_exchange_symbol(&self, exchange: CexExchange, symbol: &str) -> Option<&UnifiedAsset> {
+        self.by_exchange_symbol
+            .get(&(exchange, symbol.to_uppercase()))
+            .map(|idx| &self.assets[*idx])
+    }
+
+    /// if the currencies of the exchanges are the same asset, ex: (kucoin,
+    /// 'USDT') & (coinbase, 'USDT')
+    pub fn is_same_asset(&self, a: (CexExchange, &str), b: (CexExchange, &str)) -> bool {
+        match (
+            self.by_exchange_symbol.get(&(a.0, a.1.to_uppercase())),
+            self.by_exchange_symbol.get(&(b.0, b.1.to_uppercase()))
+        ) {
+            (Some(a), Some(b)) => a == b,
+            _ => false
+        }
+    }
+
+    pub fn collisions(&self) -> &[SymbolCollision] {
+        &self.collisions
+    }
+}
+
+/// the contracts of the currency itself, w/o the combined wrapped currencies
+fn contracts(currency: &NormalizedCurrency) -> impl Iterator<Item = ContractAddress> + '_ {
+    currency
+        .blockchains
+        .iter()
+        .filter(|chain| chain.wrapped_currency.is_none())
+        .filter_map(|chain| ContractAddress::new(chain.blockchain.clone(), chain.address.as_deref()?))
+}
+
+fn find_collisions(currencies: &[NormalizedCurrency]) -> Vec<SymbolCollision> {
+    let mut addresses = BTreeMap::<(String, Blockchain), BTreeMap<String, Vec<CexExchange>>>::new();
+    currencies.iter().for_each(|curr| {
+        contracts(curr).for_each(|contract| {
+            let exchanges = addresses
+                .entry((curr.symbol.to_uppercase(), contract.blockchain))
+                .or_default()
+                .entry(contract.address)
+                .or_default();
+            if !exchanges.contains(&curr.exchange) {
+                exchanges.push(curr.exchange)
+            }
+        })
+    });
+
+    addresses
+        .into_iter()
+        .filter(|(_, addresses)| addresses.len() > 1)
+        .map(|((symbol, blockchain), addresses)| SymbolCollision { symbol, blockchain, addresses })
+        .collect()
+}
+
+fn find(parents: &mut [usize], idx: usize) -> usize {
+    let mut root = idx;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[idx] = root;
+
+    root
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (root_a, root_b) = (find(parents, a), find(parents, b));
+    parents[root_b] = root_a;
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "us", feature = "non-us"))]
+mod tests {
+    use super::*;
+    use crate::normalized::types::{BlockchainCurrency, WrappedCurrency};
+
+    const USDT_ETH: &str = "0xdAC17F958D2ee523a2206206994597C13D831ec7";
+    const USDT_TRON: &str = "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t";
+
+    fn currency(exchange: CexExchange, symbol: &str, chains: &[(Blockchain, &str)]) -> NormalizedCurrency {
+        NormalizedCurrency {
+            exchange,
+            symbol: symbol.to_string(),
+            name: symbol.to_string(),
+            display_name: None,
+            status: String::new(),
+            blockchains: chains
+                .iter()
+                .map(|(blockchain, address)| BlockchainCurrency {
+                    blockchain:       blockchain.clone(),
+                    address:          Some(address.to_string()),
+                    is_wrapped:       false,
+                    wrapped_currency: None
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_reconcile_by_address() {
+        let mut eth = currency(CexExchange::Kucoin, "ETH", &[(Blockchain::Ethereum, "")]);
+        eth.blockchains.push(BlockchainCurrency {
+            blockchain:       Blockchain::Ethereum,
+            address:          Some("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".to_string()),
+            is_wrapped:       true,
+            wrapped_currency: Some(WrappedCurrency { symbol: "WETH".to_string(), name: "Wrapped Ether".to_string() })
+        });
+
+        let reconciler = CurrencyReconciler::new(vec![
+            currency(CexExchange::Kucoin, "USDT", &[(Blockchain::Ethereum, USDT_ETH), (Blockchain::Tron, USDT_TRON)]),
+            currency(CexExchange::Coinbase, "usdt", &[(Blockchain::Ethereum, &USDT_ETH.to_lowercase())]),
+            currency(CexExchange::Binance, "USDT", &[(Blockchain::Tron, USDT_TRON)]),
+            currency(CexExchange::Okex, "WETH", &[(Blockchain::Ethereum, "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2")]),
+            eth,
+        ]);
+
+        assert_eq!(reconciler.assets().len(), 3);
+        assert!(reconciler.is_same_asset((CexExchange::Kucoin, "USDT"), (CexExchange::Coinbase, "USDT")));
+        assert!(reconciler.is_same_asset((CexExchange::Coinbase, "usdt"), (CexExchange::Binance, "USDT")));
+        assert!(!reconciler.is_same_asset((CexExchange::Kucoin, "ETH"), (CexExchange::Okex, "WETH")));
+
+        let usdt = reconciler
+            .by_address(Blockchain::Ethereum, "0xDAC17F958D2EE523A2206206994597C13D831EC7")
+            .unwrap();
+        assert_eq!(usdt.exchanges(), vec![CexExchange::Coinbase, CexExchange::Binance, CexExchange::Kucoin]);
+        assert_eq!(usdt.symbols(), vec!["USDT", "usdt"]);
+        assert_eq!(usdt.contracts.len(), 2);
+
+        // tron addresses are case sensitive
+        assert!(reconciler
+            .by_address(Blockchain::Tron, &USDT_TRON.to_lowercase())
+            .is_none());
+        assert_eq!(reconciler.by_symbol("WETH").len(), 1);
+        assert!(reconciler.collisions().is_empty());
+    }
+
+    #[test]
+    fn test_symbol_collisions() {
+        let reconciler = CurrencyReconciler::new(vec![
+            currency(CexExchange::Kucoin, "PEPE", &[(Blockchain::Ethereum, "0x6982508145454ce325ddbe47a25d4ec3d2311933")]),
+            currency(CexExchange::Okex, "PEPE", &[(Blockchain::Ethereum, "0x6982508145454Ce325dDbE47a25d4ec3d2311933")]),
+            currency(CexExchange::Bybit, "PEPE", &[(Blockchain::Ethereum, "0x0000000000000000000000000000000000000001")]),
+        ]);
+
+        assert_eq!(reconciler.by_symbol("pepe").len(), 2);
+        assert_eq!(
+            reconciler.collisions(),
+            &[SymbolCollision {
+                symbol:     "PEPE".to_string(),
+                blockchain: Blockchain::Ethereum,
+                addresses:  BTreeMap::from([
+                    ("0x0000000000000000000000000000000000000001".to_string(), vec![CexExchange::Bybit]),
+                    ("0x6982508145454ce325ddbe47a25d4ec3d2311933".to_string(), vec![CexExchange::Kucoin, CexExchange::Okex]),
+                ])
+            }]
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+
+use futures::{future::try_join_all, stream, Stream, StreamExt};
+
+use super::AssetAliases;
+use crate::{
+    clients::rest_api::{ExchangeApi, RestApiError},
+    exchanges::normalized::{
+        types::{CanonicalInstrument, CanonicalPair, NormalizedInstrument, NormalizedTradingPair, NormalizedTradingType},
+        ws::{CombinedWsMessage, NormalizedWsDataTypes}
+    },
+    traits::EmptyFilter,
+    CexExchange
+};
+
+/// maps the pairs of every exchange to their [CanonicalInstrument]
+///
+/// the ws streams are spot, so an exchange pair that is listed as spot & as
+/// another trading type (ex: bybit's 'BTCUSDT') maps to the spot instrument
+#[derive(Debug, Clone, Default)]
+pub struct InstrumentRegistry {
+    /// (exchange, exchange's pair) -> canonical instrument
+    instruments: HashMap<(CexExchange, String), CanonicalInstrument>,
+    aliases:     AssetAliases
+}
+
+impl InstrumentRegistry {
+    pub fn new(aliases: AssetAliases) -> Self {
+        Self { instruments: HashMap::new(), aliases }
+    }
+
+    /// adds the instruments of every exchange
+    pub async fn from_exchanges(exchanges: &[CexExchange], aliases: AssetAliases) -> Result<Self, RestApiError> {
+        Self::from_exchanges_with_api(&ExchangeApi::new(), exchanges, aliases).await
+    }
+
+    /// [Self::from_exchanges] using the endpoints of `exchange_api`
+    pub async fn from_exchanges_with_api(exchange_api: &ExchangeApi, exchanges: &[CexExchange], aliases: AssetAliases) -> Result<Self, RestApiError> {
+        let instruments = try_join_all(
+            exchanges
+                .iter()
+                .map(|exchange| exchange.get_all_instruments_with_api(exchange_api, None::<EmptyFilter>))
+        )
+        .await?;
+
+        let mut this = Self::new(aliases);
+        instruments
+            .into_iter()
+            .flatten()
+            .for_each(|instrument| this.insert(instrument));
+
+        Ok(this)
+    }
+
+    pub fn insert(&mut self, instrument: NormalizedInstrument) {
+        let canonical = CanonicalInstrument {
+            pair:           CanonicalPair::new(
+                &self.aliases.resolve(&instrument.base_asset_symbol),
+                &self.aliases.resolve(&instrument.quote_asset_symbol)
+            ),
+            trading_type:   instrument.trading_type,
+            futures_expiry: instrument.futures_expiry
+        };
+
+        let key = (instrument.exchange, Self::exchange_pair(&instrument.trading_pair));
+        match self.instruments.get(&key) {
+            Some(existing) if existing.trading_type == NormalizedTradingType::Spot => (),
+            _ => {
+                self.instruments.insert(key, canonical);
+            }
+        }
+    }
+
+    /// the canonical instrument of an exchange's pair
+    pub fn canonical(&self, pair: &NormalizedTradingPair) -> Option<&CanonicalInstrument> {
+        self.instruments
+            .get(&(pair.exchange(), Self::exchange_pair(pair)))
+    }
+
+    /// the canonical pair of an exchange's spot pair, ex: as the pair mapper of
+    /// the [NbboAggregator](crate::analytics::NbboAggregator)
+    pub fn spot_pair(&self, pair: &NormalizedTradingPair) -> Option<CanonicalPair> {
+        self.canonical(pair)
+            .filter(|instrument| instrument.trading_type == NormalizedTradingType::Spot)
+            .map(|instrument| instrument.pair.clone())
+    }
+
+    /// the pairs of the canonical instrument on every exchange, ex: 'BTC-USD'
+    /// on coinbase & 'BTCUSDT' on binance
+    pub fn exchange_pairs(&self, instrument: &CanonicalInstrument) -> Vec<(CexExchange, String)> {
+        let mut pairs = self
+            .instruments
+            .iter()
+            .filter(|(_, canonical)| *canonical == instrument)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        pairs.sort();
+
+        pairs
+    }
+
+    pub fn len(&self) -> usize {
+        self.instruments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instruments.is_empty()
+    }
+
+    /// splits the message into one message per pair, each with the pair's
+    /// canonical instrument
+    pub fn enrich(&self, data: NormalizedWsDataTypes) -> Vec<CanonicalWsData> {
+        let split = match data {
+            NormalizedWsDataTypes::Trades(trades) => split_by_pair(trades, |trade| &trade.pair)
+                .into_iter()
+                .map(NormalizedWsDataTypes::Trades)
+                .collect(),
+            NormalizedWsDataTypes::Quotes(quotes) => split_by_pair(quotes, |quote| &quote.pair)
+                .into_iter()
+                .map(NormalizedWsDataTypes::Quotes)
+                .collect(),
+            data => vec![data]
+        };
+
+        split
+            .into_iter()
+            .map(|data| {
+                let instrument = first_pair(&data).and_then(|pair| self.canonical(pair).cloned());
+                CanonicalWsData { instrument, data }
+            })
+            .collect()
+    }
+
+    /// normalizes & enriches every message of the stream
+    pub fn into_stream<S>(self, stream: S) -> impl Stream<Item = CanonicalWsData>
+    where
+        S: Stream<Item = CombinedWsMessage>
+    {
+        stream.flat_map(move |msg| stream::iter(self.enrich(msg.normalize())))
+    }
+
+    fn exchange_pair(pair: &NormalizedTradingPair) -> String {
+        pair.exchange()
+            .denormalize_raw_trading_pair(pair.clone())
+            .unwrap_or_else(|_| pair.make_pair())
+    }
+}
+
+/// a normalized message with the canonical instrument of its pair, `None` for
+/// messages w/o a pair or with a pair that isn't in the [InstrumentRegistry]
+#[derive(Debug, Clone)]
+pub struct CanonicalWsData {
+    pub instrument: Option<CanonicalInstrument>,
+    pub data:       NormalizedWsDataTypes
+}
+
+fn first_pair(data: &NormalizedWsDataTypes) -> Option<&NormalizedTradingPair> {
+    match data {
+        NormalizedWsDataTypes::Trade(trade) => Some(&trade.pair),
+        NormalizedWsDataTypes::Trades(trades) => trades.first().map(|trade| &trade.pair),
+        NormalizedWsDataTypes::Quote(quote) => Some(&quote.pair),
+        NormalizedWsDataTypes::Quotes(quotes) => quotes.first().map(|quote| &quote.pair),
+        NormalizedWsDataTypes::L2(l2) => Some(&l2.pair),
+        NormalizedWsDataTypes::RemovedPair { bad_pair, .. } => Some(bad_pair),
+        NormalizedWsDataTypes::Disconnect { .. } | NormalizedWsDataTypes::Other { .. } => None
+    }
+}
+
+/// groups consecutive values of the same pair
+fn split_by_pair<T, F>(values: Vec<T>, pair: F) -> Vec<Vec<T>>
+where
+    F: Fn(&T) -> &NormalizedTradingPair
+{
+    let mut groups: Vec<Vec<T>> = Vec::new();
+    values.into_iter().for_each(|value| match groups.last_mut() {
+        Some(group) if pair(&group[0]) == pair(&value) => group.push(value),
+        _ => groups.push(vec![value])
+    });
+
+    groups
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "us", feature = "non-us"))]
+mod tests {
+    use chrono::{NaiveDate, Utc};
+
+    use super::*;
+    use crate::normalized::types::{num, NormalizedTrade, TradeSide};
+
+    fn instrument(exchange: CexExchange, pair: NormalizedTradingPair, trading_type: NormalizedTradingType) -> NormalizedInstrument {
+        NormalizedInstrument {
+            exchange,
+            base_asset_symbol: pair.base().unwrap().clone(),
+            quote_asset_symbol: pair.quote().unwrap().clone(),
+            trading_pair: pair,
+            trading_type,
+            active: true,
+            futures_expiry: None
+        }
+    }
+
+    fn test_registry(aliases: AssetAliases) -> InstrumentRegistry {
+        let mut registry = InstrumentRegistry::new(aliases);
+        registry.insert(instrument(
+            CexExchange::Coinbase,
+            NormalizedTradingPair::new_base_quote(CexExchange::Coinbase, "BTC", "USD", Some('-'), None),
+            NormalizedTradingType::Spot
+        ));
+        registry.insert(instrument(
+            CexExchange::Binance,
+            NormalizedTradingPair::new_base_quote(CexExchange::Binance, "BTC", "USDT", None, None),
+            NormalizedTradingType::Spot
+        ));
+        registry.insert(instrument(
+            CexExchange::Bybit,
+            NormalizedTradingPair::new_base_quote(CexExchange::Bybit, "BTC", "USDT", None, None),
+            NormalizedTradingType::Spot
+        ));
+        registry.insert(instrument(
+            CexExchange::Bybit,
+            NormalizedTradingPair::new_base_quote(CexExchange::Bybit, "BTC", "USDT", None, None),
+            NormalizedTradingType::Perpetual
+        ));
+        registry.insert(NormalizedInstrument {
+            futures_expiry: NaiveDate::from_ymd_opt(2024, 3, 29),
+            ..instrument(
+                CexExchange::Okex,
+                NormalizedTradingPair::new_base_quote(CexExchange::Okex, "XBT", "USD", Some('-'), Some("240329".to_string())),
+                NormalizedTradingType::Futures
+            )
+        });
+
+        registry
+    }
+
+    #[test]
+    fn test_canonical_instruments() {
+        let registry = test_registry(AssetAliases::default());
+        let btc_usdt = CanonicalInstrument {
+            pair:           CanonicalPair::new("BTC", "USDT"),
+            trading_type:   NormalizedTradingType::Spot,
+            futures_expiry: None
+        };
+
+        // binance's ws pairs have no base & quote
+        let binance_pair = NormalizedTradingPair::new_no_base_quote(CexExchange::Binance, "btcusdt");
+        assert_eq!(registry.canonical(&binance_pair), Some(&btc_usdt));
+        assert_eq!(registry.spot_pair(&binance_pair), Some(CanonicalPair::new("BTC", "USDT")));
+
+        let bybit_pair = NormalizedTradingPair::new_no_base_quote(CexExchange::Bybit, "BTCUSDT");
+        assert_eq!(registry.canonical(&bybit_pair), Some(&btc_usdt));
+        assert_eq!(
+            registry.exchange_pairs(&btc_usdt),
+            vec![(CexExchange::Binance, "BTCUSDT".to_string()), (CexExchange::Bybit, "BTCUSDT".to_string())]
+        );
+
+        let okex_pair = NormalizedTradingPair::new_base_quote(CexExchange::Okex, "XBT", "USD", Some('-'), Some("240329".to_string()));
+        let okex_futures = registry.canonical(&okex_pair).unwrap();
+        assert_eq!(okex_futures.to_string(), "BTC/USD FUTURES 2024-03-29");
+        assert_eq!(registry.spot_pair(&okex_pair), None);
+
+        let coinbase_pair = NormalizedTradingPair::new_base_quote(CexExchange::Coinbase, "BTC", "USD", Some('-'), None);
+        assert_eq!(registry.spot_pair(&coinbase_pair), Some(CanonicalPair::new("BTC", "USD")));
+
+        let registry = test_registry(AssetAliases::default().with_usd_stablecoins());
+        assert_eq!(registry.spot_pair(&coinbase_pair), registry.spot_pair(&binance_pair));
+        assert_eq!(registry.exchange_pairs(registry.canonical(&coinbase_pair).unwrap()).len(), 3);
+    }
+
+    #[test]
+    fn test_enrich() {
+        let registry = test_registry(AssetAliases::default());
+        let trade = |pair: &str| NormalizedTrade {
+            exchange: CexExchange::Binance,
+            pair:     NormalizedTradingPair::new_no_base_quote(CexExchange::Binance, pair),
+            time:     Utc::now(),
+            side:     TradeSide::Buy,
+            raw_side: "buy".to_string(),
+            price:    num(1.0),
+            amount:   num(1.0),
+            trade_id: None
+        };
+
+        let enriched = registry.enrich(NormalizedWsDataTypes::Trades(vec![trade("BTCUSDT"), trade("BTCUSDT"), trade("ETHUSDT")]));
+        assert_eq!(enriched.len(), 2);
+        assert_eq!(enriched[0].instrument.as_ref().unwrap().pair, CanonicalPair::new("BTC", "USDT"));
+        assert!(matches!(&enriched[0].data, NormalizedWsDataTypes::Trades(trades) if trades.len() == 2));
+        assert_eq!(enriched[1].instrument, None);
+
+        let other = NormalizedWsDataTypes::Other { exchange: CexExchange::Binance, kind: String::new(), value: String::new() };
+        assert_eq!(registry.enrich(other)[0].instrument, None);
+    }
+}
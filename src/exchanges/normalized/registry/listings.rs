@@ -0,0 +1,228 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration
+};
+
+use futures::{future::join_all, stream, Stream, StreamExt};
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::{
+    clients::rest_api::{ExchangeApi, RestApiError},
+    exchanges::normalized::types::{NormalizedInstrument, NormalizedTradingPair, NormalizedTradingType},
+    traits::EmptyFilter,
+    CexExchange, ExchangeEnvironment
+};
+
+/// a change of an exchange's instruments between two polls
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum InstrumentEvent {
+    /// an instrument that wasn't in the previous poll
+    Listed(NormalizedInstrument),
+    /// an instrument of the previous poll that the exchange no longer returns
+    Delisted(NormalizedInstrument),
+    /// an instrument that was activated or deactivated, ex: a halted pair
+    StatusChanged { previous: NormalizedInstrument, current: NormalizedInstrument }
+}
+
+impl InstrumentEvent {
+    /// the instrument, the current one for status changes
+    pub fn instrument(&self) -> &NormalizedInstrument {
+        match self {
+            InstrumentEvent::Listed(instrument) | InstrumentEvent::Delisted(instrument) => instrument,
+            InstrumentEvent::StatusChanged { current, .. } => current
+        }
+    }
+
+    pub fn exchange(&self) -> CexExchange {
+        self.instrument().exchange
+    }
+}
+
+/// an exchange's pair can be listed w/ multiple trading types
+type InstrumentKey = (NormalizedTradingPair, NormalizedTradingType);
+
+/// polls the instruments of the exchanges, diffing every poll w/ the previous
+/// one into [InstrumentEvent]s
+///
+/// the first poll of an exchange only stores its instruments & a failed poll
+/// keeps the previous instruments, so an exchange's outage isn't a delisting
+#[derive(Debug, Clone)]
+pub struct InstrumentPoller {
+    exchanges:    Vec<CexExchange>,
+    interval:     Duration,
+    /// exchanges without an entry are queried on
+    /// [ExchangeEnvironment::Production]
+    environments: HashMap<CexExchange, ExchangeEnvironment>,
+    /// the instruments of the last successful poll of every exchange
+    snapshots:    HashMap<CexExchange, HashMap<InstrumentKey, NormalizedInstrument>>
+}
+
+impl InstrumentPoller {
+    pub fn new(exchanges: &[CexExchange], interval: Duration) -> Self {
+        Self { exchanges: exchanges.to_vec(), interval, environments: HashMap::new(), snapshots: HashMap::new() }
+    }
+
+    /// queries the exchange's instruments from `environment`
+    pub fn with_environment(mut self, exchange: CexExchange, environment: ExchangeEnvironment) -> Self {
+        self.environments.insert(exchange, environment);
+        self
+    }
+
+    /// queries the instruments of every exchange w/o an environment from
+    /// `<base_url>/<exchange>`
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.exchanges.iter().for_each(|exch| {
+            self.environments
+                .entry(*exch)
+                .or_insert_with(|| ExchangeEnvironment::Custom(base_url.to_string()));
+        });
+        self
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// queries the instruments of every exchange, returning the changes since
+    /// the last poll. exchanges whose query fails are skipped until the next
+    /// poll
+    pub async fn poll(&mut self) -> Vec<InstrumentEvent> {
+        let all_instruments = join_all(self.exchanges.iter().map(|exch| {
+            let exchange_api = ExchangeApi::new().with_environment(self.environments.get(exch).cloned().unwrap_or_default());
+            async move {
+                exch.get_all_instruments_with_api(&exchange_api, None::<EmptyFilter>)
+                    .await
+            }
+        }))
+        .await;
+
+        self.exchanges
+            .clone()
+            .into_iter()
+            .zip(all_instruments)
+            .flat_map(|(exchange, instruments)| match instruments {
+                Ok(instruments) => self.update(exchange, instruments),
+                Err(e) => {
+                    warn!(target: "cex-exchanges::listings", exchange=?exchange, "error polling the instruments: {:?}", e);
+                    Vec::new()
+                }
+            })
+            .collect()
+    }
+
+    /// [Self::poll] for a single exchange
+    pub async fn poll_exchange(&mut self, exchange: CexExchange) -> Result<Vec<InstrumentEvent>, RestApiError> {
+        let exchange_api = ExchangeApi::new().with_environment(self.environments.get(&exchange).cloned().unwrap_or_default());
+        let instruments = exchange
+            .get_all_instruments_with_api(&exchange_api, None::<EmptyFilter>)
+            .await?;
+
+        Ok(self.update(exchange, instruments))
+    }
+
+    /// diffs the exchange's instruments w/ its previous ones, ex: to seed the
+    /// poller w/ already queried instruments
+    pub fn update(&mut self, exchange: CexExchange, instruments: Vec<NormalizedInstrument>) -> Vec<InstrumentEvent> {
+        let current = instruments
+            .into_iter()
+            .map(|instrument| ((instrument.trading_pair.clone(), instrument.trading_type), instrument))
+            .collect::<HashMap<_, _>>();
+
+        let Some(previous) = self.snapshots.insert(exchange, current) else {
+            debug!(target: "cex-exchanges::listings", exchange=?exchange, "stored the first poll of the instruments");
+            return Vec::new()
+        };
+        let current = &self.snapshots[&exchange];
+
+        let mut events = current
+            .iter()
+            .filter_map(|(key, instrument)| match previous.get(key) {
+                None => Some(InstrumentEvent::Listed(instrument.clone())),
+                Some(prev) if prev.active != instrument.active => {
+                    Some(InstrumentEvent::StatusChanged { previous: prev.clone(), current: instrument.clone() })
+                }
+                Some(_) => None
+            })
+            .collect::<Vec<_>>();
+
+        let current_keys = current.keys().collect::<HashSet<_>>();
+        events.extend(
+            previous
+                .into_iter()
+                .filter(|(key, _)| !current_keys.contains(key))
+                .map(|(_, instrument)| InstrumentEvent::Delisted(instrument))
+        );
+        events.sort_by(|a, b| {
+            (&a.instrument().trading_pair, a.instrument().trading_type).cmp(&(&b.instrument().trading_pair, b.instrument().trading_type))
+        });
+
+        if !events.is_empty() {
+            debug!(target: "cex-exchanges::listings", exchange=?exchange, "found {} instrument changes", events.len());
+        }
+
+        events
+    }
+
+    /// polls every `interval`, starting immediately, streaming the changes
+    pub fn into_stream(self) -> impl Stream<Item = InstrumentEvent> + Send {
+        stream::unfold((self, true), |(mut this, is_first)| async move {
+            if !is_first {
+                tokio::time::sleep(this.interval).await;
+            }
+            let events = this.poll().await;
+            Some((stream::iter(events), (this, false)))
+        })
+        .flatten()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "us")]
+mod tests {
+    use super::*;
+
+    fn instrument(base: &str, trading_type: NormalizedTradingType, active: bool) -> NormalizedInstrument {
+        NormalizedInstrument {
+            exchange: CexExchange::Okex,
+            trading_pair: NormalizedTradingPair::new_base_quote(CexExchange::Okex, base, "USDT", Some('-'), None),
+            trading_type,
+            base_asset_symbol: base.to_string(),
+            quote_asset_symbol: "USDT".to_string(),
+            active,
+            futures_expiry: None
+        }
+    }
+
+    #[test]
+    fn test_instrument_events() {
+        let mut poller = InstrumentPoller::new(&[CexExchange::Okex], Duration::from_secs(60));
+
+        let first = vec![
+            instrument("BTC", NormalizedTradingType::Spot, true),
+            instrument("ETH", NormalizedTradingType::Spot, true),
+            instrument("ETH", NormalizedTradingType::Perpetual, true)
+        ];
+        assert!(poller.update(CexExchange::Okex, first.clone()).is_empty());
+        assert!(poller.update(CexExchange::Okex, first).is_empty());
+
+        let second = vec![
+            instrument("BTC", NormalizedTradingType::Spot, false),
+            instrument("ETH", NormalizedTradingType::Spot, true),
+            instrument("SOL", NormalizedTradingType::Spot, true)
+        ];
+        let events = poller.update(CexExchange::Okex, second);
+        assert_eq!(
+            events,
+            vec![
+                InstrumentEvent::StatusChanged {
+                    previous: instrument("BTC", NormalizedTradingType::Spot, true),
+                    current:  instrument("BTC", NormalizedTradingType::Spot, false)
+                },
+                InstrumentEvent::Delisted(instrument("ETH", NormalizedTradingType::Perpetual, true)),
+                InstrumentEvent::Listed(instrument("SOL", NormalizedTradingType::Spot, true))
+            ]
+        );
+        assert!(events.iter().all(|event| event.exchange() == CexExchange::Okex));
+    }
+}
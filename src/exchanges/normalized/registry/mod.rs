@@ -0,0 +1,11 @@
+mod aliases;
+pub use aliases::*;
+
+mod currencies;
+pub use currencies::*;
+
+mod instruments;
+pub use instruments::*;
+
+mod listings;
+pub use listings::*;
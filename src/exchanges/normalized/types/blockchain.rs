@@ -4,9 +4,9 @@ use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
 use super::NormalizedCurrency;
-use crate::traits::ExchangeFilter;
+use crate::traits::{ExchangeFilter, FilterExt};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, PartialOrd, Ord, ValueEnum)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, PartialOrd, Ord, ValueEnum)]
 pub enum Blockchain {
     Bitcoin,
     Ethereum,
@@ -182,3 +182,5 @@ impl ExchangeFilter<NormalizedCurrency> for Blockchain {
         val.has_blockchain(self)
     }
 }
+
+impl FilterExt for Blockchain {}
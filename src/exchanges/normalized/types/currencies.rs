@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 use super::Blockchain;
-use crate::exchanges::CexExchange;
+use crate::{
+    exchanges::CexExchange,
+    traits::{ExchangeFilter, FilterExt}
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
 pub struct NormalizedCurrency {
@@ -57,7 +60,7 @@ impl NormalizedCurrency {
     fn combine_wrapped_assets(&self, unwrapped_currencies: &[NormalizedCurrency]) -> (Self, Option<Self>) {
         let un = self.name.to_lowercase().replace("wrapped", "");
         let unwrapped_name = un.trim();
-        let unwrapped_symbol = self.symbol[1..].to_string();
+        let unwrapped_symbol = self.symbol.chars().skip(1).collect::<String>();
 
         unwrapped_currencies
             .iter()
@@ -106,6 +109,41 @@ pub struct WrappedCurrency {
     pub name:   String
 }
 
+/// filters currencies, see [InstrumentFilter](super::InstrumentFilter) for
+/// instruments
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CurrencyFilter {
+    Symbol(String),
+    SymbolIn(Vec<String>),
+    HasBlockchain(Blockchain)
+}
+
+impl CurrencyFilter {
+    pub fn symbol(v: String) -> Self {
+        Self::Symbol(v)
+    }
+
+    pub fn symbol_in(v: Vec<String>) -> Self {
+        Self::SymbolIn(v)
+    }
+
+    pub fn has_blockchain(v: Blockchain) -> Self {
+        Self::HasBlockchain(v)
+    }
+}
+
+impl ExchangeFilter<NormalizedCurrency> for CurrencyFilter {
+    fn matches(&self, val: &NormalizedCurrency) -> bool {
+        match self {
+            CurrencyFilter::Symbol(v) => &val.symbol == v,
+            CurrencyFilter::SymbolIn(v) => v.contains(&val.symbol),
+            CurrencyFilter::HasBlockchain(v) => val.has_blockchain(v)
+        }
+    }
+}
+
+impl FilterExt for CurrencyFilter {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,4 +312,31 @@ mod tests {
 
         assert_eq!(combined, vec![expected])
     }
+
+    #[test]
+    fn test_currency_filter() {
+        let currency = |symbol: &str, blockchains: Vec<Blockchain>| NormalizedCurrency {
+            exchange:     TEST_EXCHANGE,
+            symbol:       symbol.to_string(),
+            name:         symbol.to_string(),
+            display_name: None,
+            status:       String::new(),
+            blockchains:  blockchains
+                .into_iter()
+                .map(|blockchain| BlockchainCurrency { blockchain, address: None, is_wrapped: false, wrapped_currency: None })
+                .collect()
+        };
+        let mut currencies = vec![
+            currency("USDT", vec![Blockchain::Ethereum, Blockchain::Tron]),
+            currency("BTC", vec![Blockchain::Bitcoin]),
+            currency("USDC", vec![Blockchain::Solana])
+        ];
+
+        let off_ethereum_stables = CurrencyFilter::symbol_in(vec!["USDT".to_string(), "USDC".to_string()])
+            .and(CurrencyFilter::has_blockchain(Blockchain::Ethereum).not());
+        off_ethereum_stables.filter_matches(&mut currencies);
+
+        assert_eq!(currencies.len(), 1);
+        assert_eq!(currencies[0].symbol, "USDC");
+    }
 }
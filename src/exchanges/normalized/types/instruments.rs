@@ -4,8 +4,11 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
-use super::{NormalizedCurrency, NormalizedTradingPair};
-use crate::{exchanges::CexExchange, traits::ExchangeFilter};
+use super::{CanonicalPair, NormalizedTradingPair};
+use crate::{
+    exchanges::CexExchange,
+    traits::{ExchangeFilter, FilterExt}
+};
 
 #[derive(Debug, Clone, Serialize, PartialEq, PartialOrd)]
 pub struct NormalizedInstrument {
@@ -18,6 +21,26 @@ pub struct NormalizedInstrument {
     pub futures_expiry:     Option<NaiveDate>
 }
 
+/// an instrument that is the same on every exchange, ex: the 'BTC/USDT' spot
+/// pair
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
+pub struct CanonicalInstrument {
+    pub pair:           CanonicalPair,
+    pub trading_type:   NormalizedTradingType,
+    pub futures_expiry: Option<NaiveDate>
+}
+
+impl Display for CanonicalInstrument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.pair, self.trading_type)?;
+        if let Some(expiry) = self.futures_expiry {
+            write!(f, " {expiry}")?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, Serialize, PartialEq, Eq, Hash, EnumIter, PartialOrd, Ord)]
 pub enum NormalizedTradingType {
     Spot,
@@ -87,14 +110,20 @@ impl Display for NormalizedTradingType {
     }
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+/// filters instruments, see [CurrencyFilter](super::CurrencyFilter) for currencies
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum InstrumentFilter {
     Pair(String),
     BaseOrQuote(String),
     BaseAndQuote { base: String, quote: String },
     BaseOnly(String),
     QuoteOnly(String),
-    Active
+    /// the quote is any of the symbols
+    QuoteIn(Vec<String>),
+    Active,
+    TradingType(NormalizedTradingType),
+    /// futures expiring in the (inclusive) range
+    FuturesExpiry { from: Option<NaiveDate>, to: Option<NaiveDate> }
 }
 
 impl InstrumentFilter {
@@ -117,6 +146,18 @@ impl InstrumentFilter {
     pub fn quote_only(v: String) -> Self {
         Self::QuoteOnly(v)
     }
+
+    pub fn quote_in(v: Vec<String>) -> Self {
+        Self::QuoteIn(v)
+    }
+
+    pub fn trading_type(v: NormalizedTradingType) -> Self {
+        Self::TradingType(v)
+    }
+
+    pub fn futures_expiry(from: Option<NaiveDate>, to: Option<NaiveDate>) -> Self {
+        Self::FuturesExpiry { from, to }
+    }
 }
 
 impl ExchangeFilter<NormalizedInstrument> for InstrumentFilter {
@@ -127,19 +168,14 @@ impl ExchangeFilter<NormalizedInstrument> for InstrumentFilter {
             InstrumentFilter::BaseAndQuote { base, quote } => &val.base_asset_symbol == base && &val.quote_asset_symbol == quote,
             InstrumentFilter::BaseOnly(v) => &val.base_asset_symbol == v,
             InstrumentFilter::QuoteOnly(v) => &val.quote_asset_symbol == v,
-            InstrumentFilter::Active => val.active
+            InstrumentFilter::QuoteIn(v) => v.contains(&val.quote_asset_symbol),
+            InstrumentFilter::Active => val.active,
+            InstrumentFilter::TradingType(v) => &val.trading_type == v,
+            InstrumentFilter::FuturesExpiry { from, to } => val.futures_expiry.is_some_and(|expiry| {
+                from.is_none_or(|from| expiry >= from) && to.is_none_or(|to| expiry <= to)
+            })
         }
     }
 }
 
-impl ExchangeFilter<NormalizedCurrency> for InstrumentFilter {
-    fn matches(&self, val: &NormalizedCurrency) -> bool {
-        match self {
-            InstrumentFilter::BaseOrQuote(v) => &val.symbol == v,
-            InstrumentFilter::BaseOnly(v) => &val.symbol == v,
-            InstrumentFilter::QuoteOnly(v) => &val.symbol == v,
-            InstrumentFilter::BaseAndQuote { .. } | InstrumentFilter::Pair(_) => false,
-            InstrumentFilter::Active => true
-        }
-    }
-}
+impl FilterExt for InstrumentFilter {}
@@ -1,7 +1,9 @@
+use std::cmp::Ordering;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::{pairs::NormalizedTradingPair, NormalizedQuote};
+use super::{pairs::NormalizedTradingPair, NormalizedQuote, Number};
 use crate::CexExchange;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
@@ -18,12 +20,12 @@ impl NormalizedL2 {
         if let (Some(bid), Some(ask)) = (
             self.bids
                 .iter()
-                .filter(|v| v.amount != 0.0)
-                .max_by(|a, b| a.price.partial_cmp(&b.price).unwrap()),
+                .filter(|v| v.amount != Number::default())
+                .max_by(|a, b| a.price.partial_cmp(&b.price).unwrap_or(Ordering::Equal)),
             self.asks
                 .iter()
-                .filter(|v| v.amount != 0.0)
-                .min_by(|a, b| a.price.partial_cmp(&b.price).unwrap())
+                .filter(|v| v.amount != Number::default())
+                .min_by(|a, b| a.price.partial_cmp(&b.price).unwrap_or(Ordering::Equal))
         ) {
             Some(NormalizedQuote {
                 exchange:           self.exchange,
@@ -42,12 +44,12 @@ impl NormalizedL2 {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
 pub struct BidAsk {
-    pub price:  f64,
-    pub amount: f64
+    pub price:  Number,
+    pub amount: Number
 }
 
 impl BidAsk {
-    pub fn new(price: f64, amount: f64) -> Self {
+    pub fn new(price: Number, amount: Number) -> Self {
         Self { price, amount }
     }
 }
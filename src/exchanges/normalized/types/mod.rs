@@ -18,3 +18,6 @@ pub use instruments::*;
 
 mod l2;
 pub use l2::*;
+
+mod number;
+pub use number::*;
@@ -0,0 +1,63 @@
+/// the prices & amounts of the ws messages, an exact decimal that keeps the
+/// exchange's precision with the `decimal` feature & `f64` otherwise
+#[cfg(not(feature = "decimal"))]
+pub type Number = f64;
+
+/// the prices & amounts of the ws messages, an exact decimal that keeps the
+/// exchange's precision with the `decimal` feature & `f64` otherwise
+#[cfg(feature = "decimal")]
+pub type Number = rust_decimal::Decimal;
+
+/// [Number] operations that compile with & w/o the `decimal` feature
+pub trait NumberExt: Sized {
+    /// lossy for decimals that aren't representable as a `f64`
+    fn to_f64(self) -> f64;
+
+    /// `None` for decimals if the value is nan or infinite
+    fn from_f64(value: f64) -> Option<Self>;
+}
+
+#[cfg(not(feature = "decimal"))]
+impl NumberExt for Number {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        Some(value)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl NumberExt for Number {
+    fn to_f64(self) -> f64 {
+        rust_decimal::prelude::ToPrimitive::to_f64(&self).unwrap_or_default()
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        rust_decimal::prelude::FromPrimitive::from_f64(value)
+    }
+}
+
+/// a [Number] from a `f64` literal in tests
+#[cfg(test)]
+pub(crate) fn num(value: f64) -> Number {
+    Number::from_f64(value).unwrap()
+}
+
+#[cfg(test)]
+#[cfg(feature = "decimal")]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_decimal_precision() {
+        let price = Number::from_str("0.00000001").unwrap();
+        assert_eq!(price.to_string(), "0.00000001");
+        assert_eq!(price + price, Number::from_str("0.00000002").unwrap());
+        assert_eq!(Number::from_str("37000.10").unwrap(), Number::from_str("37000.1").unwrap());
+        assert_eq!(price.to_f64(), 1e-8);
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::CexExchange;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
+#[serde(try_from = "NormalizedTradingPairRaw")]
 pub struct NormalizedTradingPair {
     exchange:   CexExchange,
     pair:       Option<String>,
@@ -76,27 +77,54 @@ impl NormalizedTradingPair {
     }
 
     pub fn extra_data(&self) -> Option<String> {
-        if let (Some(pair), Some((base, quote))) = (self.pair(), self.base_quote()) {
-            let mut ed = pair.clone();
-            if let Some(del) = self.delimiter() {
-                ed = ed.replace(&format!("{base}{del}{quote}"), "");
-                if ed.is_empty() {
-                    None
-                } else {
-                    ed = ed[1..].to_string();
-                    Some(ed)
-                }
-            } else {
-                ed = ed.replace(&format!("{base}{quote}"), "");
-                if ed.is_empty() {
-                    None
-                } else {
-                    Some(ed)
-                }
-            }
+        let (Some(pair), Some((base, quote))) = (self.pair(), self.base_quote()) else { return None };
+
+        let ed = if let Some(del) = self.delimiter() {
+            let ed = pair.strip_prefix(&format!("{base}{del}{quote}"))?;
+            ed.strip_prefix(del).unwrap_or(ed)
         } else {
+            pair.strip_prefix(&format!("{base}{quote}"))?
+        };
+
+        if ed.is_empty() {
             None
+        } else {
+            Some(ed.to_string())
+        }
+    }
+
+    /// the exchange independent pair, `None` w/o a base & quote or with extra
+    /// data (futures, swaps, ...)
+    pub fn canonical_pair(&self) -> Option<CanonicalPair> {
+        if self.extra_data().is_some() {
+            return None
+        }
+
+        self.base_quote()
+            .as_ref()
+            .map(|(base, quote)| CanonicalPair::new(base, quote))
+    }
+}
+
+/// a [NormalizedTradingPair] needs either a pair or a base & quote
+#[derive(Deserialize)]
+struct NormalizedTradingPairRaw {
+    exchange:   CexExchange,
+    pair:       Option<String>,
+    base_quote: Option<(String, String)>,
+    delimiter:  Option<char>
+}
+
+impl TryFrom<NormalizedTradingPairRaw> for NormalizedTradingPair {
+    type Error = String;
+
+    fn try_from(value: NormalizedTradingPairRaw) -> Result<Self, Self::Error> {
+        let NormalizedTradingPairRaw { exchange, pair, base_quote, delimiter } = value;
+        if pair.is_none() && base_quote.is_none() {
+            return Err(format!("{exchange} normalized trading pair has neither a 'pair' nor a 'base_quote'"))
         }
+
+        Ok(Self { exchange, pair, base_quote, delimiter })
     }
 }
 
@@ -138,13 +166,24 @@ impl RawTradingPair {
         RawTradingPair::RawNoDelim { pair: pair.to_uppercase() }
     }
 
+    /// 'BTC-USDT', 'btc_usdt' & 'BTC/USDT' can be used for every exchange,
+    /// 'BTCUSDT' only for the exchanges without a delimiter
+    pub fn parse(pair: &str) -> Self {
+        match pair.chars().find(|c| ['-', '_', '/'].contains(c)) {
+            Some(delimiter) => RawTradingPair::new_raw(pair, delimiter),
+            None => RawTradingPair::new_no_delim(pair)
+        }
+    }
+
     pub fn get_normalized_pair(&self, exchange: CexExchange) -> NormalizedTradingPair {
         let this = self.clone();
         match this {
             RawTradingPair::Split { base, quote, extra_data } => NormalizedTradingPair::new_base_quote(exchange, &base, &quote, None, extra_data),
             RawTradingPair::RawDelim { pair, delimiter } => {
                 let mut split = pair.split(delimiter);
-                let (base, quote) = (split.next().unwrap(), split.next().unwrap());
+                let (Some(base), Some(quote)) = (split.next(), split.next()) else {
+                    return NormalizedTradingPair::new_no_base_quote(exchange, &pair)
+                };
                 let extra_data = split.collect::<Vec<_>>();
                 let ed = if extra_data.is_empty() { None } else { Some(extra_data.join(&delimiter.to_string())) };
                 NormalizedTradingPair::new_base_quote(exchange, base, quote, Some(delimiter), ed)
@@ -177,3 +216,22 @@ impl PartialEq<&str> for NormalizedTradingPair {
         other.to_uppercase() == self.make_pair()
     }
 }
+
+/// a pair that is the same on every exchange, ex: 'BTC/USDT'
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
+pub struct CanonicalPair {
+    pub base:  String,
+    pub quote: String
+}
+
+impl CanonicalPair {
+    pub fn new(base: &str, quote: &str) -> Self {
+        Self { base: base.to_uppercase(), quote: quote.to_uppercase() }
+    }
+}
+
+impl Display for CanonicalPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.base, self.quote)
+    }
+}
@@ -1,15 +1,15 @@
 use serde::{Deserialize, Serialize};
 
-use super::{pairs::NormalizedTradingPair, TimeOrUpdateId};
+use super::{pairs::NormalizedTradingPair, Number, TimeOrUpdateId};
 use crate::CexExchange;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
 pub struct NormalizedQuote {
     pub exchange:           CexExchange,
     pub pair:               NormalizedTradingPair,
-    pub ask_amount:         f64,
-    pub ask_price:          f64,
-    pub bid_amount:         f64,
-    pub bid_price:          f64,
+    pub ask_amount:         Number,
+    pub ask_price:          Number,
+    pub bid_amount:         Number,
+    pub bid_price:          Number,
     pub orderbook_ids_time: TimeOrUpdateId
 }
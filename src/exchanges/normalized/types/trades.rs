@@ -1,7 +1,9 @@
+use std::fmt::Display;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::pairs::NormalizedTradingPair;
+use super::{pairs::NormalizedTradingPair, Number, NumberExt};
 use crate::CexExchange;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
@@ -9,8 +11,71 @@ pub struct NormalizedTrade {
     pub exchange: CexExchange,
     pub pair:     NormalizedTradingPair,
     pub time:     DateTime<Utc>,
-    pub side:     String,
-    pub price:    f64,
-    pub amount:   f64,
+    /// side of the taker
+    pub side:     TradeSide,
+    /// the exchange's side, ex: the maker's side for coinbase & the buyer is
+    /// maker flag for binance
+    pub raw_side: String,
+    pub price:    Number,
+    pub amount:   Number,
     pub trade_id: Option<String>
 }
+
+impl NormalizedTrade {
+    /// amount signed by the taker's side, positive for buys & negative for
+    /// sells
+    pub fn signed_amount(&self) -> f64 {
+        self.side.sign() * self.amount.to_f64()
+    }
+}
+
+/// side of the taker (aggressor) of a trade
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeSide {
+    Buy,
+    Sell,
+    /// the exchange's side isn't 'buy' or 'sell'
+    Unknown
+}
+
+impl TradeSide {
+    /// parses a 'buy' or 'sell' side, case insensitive
+    pub fn parse(side: &str) -> Self {
+        if side.eq_ignore_ascii_case("buy") {
+            TradeSide::Buy
+        } else if side.eq_ignore_ascii_case("sell") {
+            TradeSide::Sell
+        } else {
+            TradeSide::Unknown
+        }
+    }
+
+    /// the other side, ex: the taker's side of a maker's side
+    pub fn opposite(self) -> Self {
+        match self {
+            TradeSide::Buy => TradeSide::Sell,
+            TradeSide::Sell => TradeSide::Buy,
+            TradeSide::Unknown => TradeSide::Unknown
+        }
+    }
+
+    /// 1 for buys, -1 for sells & 0 for unknown
+    pub fn sign(self) -> f64 {
+        match self {
+            TradeSide::Buy => 1.0,
+            TradeSide::Sell => -1.0,
+            TradeSide::Unknown => 0.0
+        }
+    }
+}
+
+impl Display for TradeSide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TradeSide::Buy => write!(f, "buy"),
+            TradeSide::Sell => write!(f, "sell"),
+            TradeSide::Unknown => write!(f, "unknown")
+        }
+    }
+}
@@ -1,25 +1,39 @@
-use std::{collections::HashMap, pin::Pin};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    time::Duration,
+};
 
-use futures::Stream;
+use futures::{future::try_join_all, Stream};
 use tokio_stream::wrappers::UnboundedReceiverStream;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use super::CombinedWsMessage;
+use super::{resolver::InstrumentPairs, CombinedWsMessage, ExchangeFeedConfig, RefreshingWsStream, WsFeedConfig, WsFeedConfigError};
 use crate::{
-    clients::ws::{MultiWsStream, WsStreamConfig},
+    clients::{
+        rest_api::RestApiError,
+        ws::{MultiWsStream, WsStreamConfig, WsThreadHandle},
+    },
     exchanges::normalized::{
-        types::RawTradingPair,
+        registry::InstrumentPoller,
+        types::{NormalizedInstrument, RawTradingPair},
         ws::channels::{NormalizedWsChannelKinds, NormalizedWsChannels},
     },
-    CexExchange,
+    traits::ExchangeFilter,
+    CexExchange, ExchangeEnvironment,
 };
 
+/// combined stream of the spawned threads
+pub type MultithreadedWsStream = Pin<Box<dyn Stream<Item = CombinedWsMessage> + Send>>;
+
 #[derive(Debug, Default, Clone)]
 pub struct NormalizedExchangeBuilder {
     pub(crate) ws_exchanges: HashMap<CexExchange, HashMap<NormalizedWsChannelKinds, NormalizedWsChannels>>,
     /// proxy exchange to get symbols for exchanges that don't have a direct api
     /// link
     exch_currency_proxy: Option<CexExchange>,
+    /// exchanges without an entry connect to [ExchangeEnvironment::Production]
+    environments: HashMap<CexExchange, ExchangeEnvironment>,
 }
 
 impl NormalizedExchangeBuilder {
@@ -27,6 +41,36 @@ impl NormalizedExchangeBuilder {
         Self::default()
     }
 
+    /// validates the config & adds its exchanges, channels & pairs, querying the
+    /// instruments of every exchange to check the pairs & resolve the
+    /// instrument filters. the streams are then built with the config's
+    /// `stream`, `threads` & `connections_per_stream`
+    pub async fn from_config(config: &WsFeedConfig) -> Result<Self, WsFeedConfigError> {
+        config.validate()?;
+        let all_pairs = try_join_all(config.exchanges.iter().map(ExchangeFeedConfig::resolve_pairs)).await?;
+
+        let mut this = Self { exch_currency_proxy: config.currency_proxy, ..Default::default() };
+        config
+            .exchanges
+            .iter()
+            .zip(all_pairs)
+            .for_each(|(exch_config, pairs)| {
+                let exchange = exch_config.exchange;
+                debug!(target: "cex-exchanges::live-stream", exchange=?exchange, "configured {} pairs", pairs.len());
+
+                let channels = exch_config
+                    .channels
+                    .iter()
+                    .map(|channel| (channel.kind, channel.normalized(exchange, &pairs)))
+                    .collect();
+                this.ws_exchanges.insert(exchange, channels);
+                this.environments
+                    .insert(exchange, exch_config.environment.clone());
+            });
+
+        Ok(this)
+    }
+
     pub fn add_channels_one_exchange(mut self, exchange: CexExchange, channels: &[NormalizedWsChannelKinds]) -> Self {
         self.ws_exchanges.entry(exchange).or_insert_with(|| {
             channels
@@ -56,6 +100,44 @@ impl NormalizedExchangeBuilder {
         self
     }
 
+    /// adds the channels to the exchanges w/ the pairs of every instrument that
+    /// matches the filter, querying the instruments of each exchange from its
+    /// environment. ex: the active usdt & usdc spot pairs
+    /// `InstrumentFilter::Active.and(InstrumentFilter::trading_type(NormalizedTradingType::Spot))`
+    /// `.and(InstrumentFilter::quote_in(vec!["USDT".to_string(), "USDC".to_string()]))`
+    ///
+    /// exchanges w/o any matching instrument are skipped
+    pub async fn add_channels_from_instruments<F>(
+        mut self,
+        exchanges: &[CexExchange],
+        channels: &[NormalizedWsChannelKinds],
+        filter: F,
+    ) -> eyre::Result<Self>
+    where
+        F: ExchangeFilter<NormalizedInstrument>,
+    {
+        let all_pairs = try_join_all(exchanges.iter().map(|exch| async {
+            let mut resolver = InstrumentPairs::query(*exch, self.environments.get(exch).cloned().unwrap_or_default()).await?;
+            resolver.add_matching(&filter);
+            Ok::<_, RestApiError>(resolver.into_pairs())
+        }))
+        .await?;
+
+        exchanges
+            .iter()
+            .zip(all_pairs)
+            .for_each(|(exch, pairs)| {
+                if pairs.is_empty() {
+                    warn!(target: "cex-exchanges::live-stream", exchange=?exch, "no instruments match the filter");
+                } else {
+                    debug!(target: "cex-exchanges::live-stream", exchange=?exch, "filtered {} pairs", pairs.len());
+                    self.add_pairs_all_channels(*exch, channels, &pairs);
+                }
+            });
+
+        Ok(self)
+    }
+
     /// sets the proxy exchange to get symbols for exchanges that don't have a
     /// direct api link
     pub fn exchange_currency_proxy(mut self, exch_currency_proxy: CexExchange) -> Self {
@@ -63,6 +145,24 @@ impl NormalizedExchangeBuilder {
         self
     }
 
+    /// connects the exchange's streams to `environment`
+    pub fn with_environment(mut self, exchange: CexExchange, environment: ExchangeEnvironment) -> Self {
+        self.environments.insert(exchange, environment);
+        self
+    }
+
+    /// connects the streams of every exchange to `<base_url>/<exchange>`, see
+    /// [ExchangeEnvironment::Custom]. exchanges already set w/
+    /// [Self::with_environment] keep their environment
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        CexExchange::vec_all().into_iter().for_each(|exch| {
+            self.environments
+                .entry(exch)
+                .or_insert_with(|| ExchangeEnvironment::Custom(base_url.to_string()));
+        });
+        self
+    }
+
     /// adds trading pairs to all channels for all exchanges
     pub fn add_pairs_all_channels_all_exchanges<S>(
         &mut self,
@@ -123,13 +223,8 @@ impl NormalizedExchangeBuilder {
     pub fn build_all_multistream(self, config: WsStreamConfig, connections_per_stream: Option<usize>) -> eyre::Result<Option<MultiWsStream>> {
         let mut multistream_ws: Option<MultiWsStream> = None;
 
-        self.ws_exchanges.into_iter().try_for_each(|(exch, map)| {
-            let channel_map = map
-                .into_values()
-                .flat_map(|channel| channel.make_many_single())
-                .collect::<Vec<_>>();
-
-            let new_stream = exch.build_multistream_ws_from_normalized(channel_map, config, connections_per_stream, self.exch_currency_proxy)?;
+        self.ws_exchanges.keys().try_for_each(|exch| {
+            let new_stream = self.build_exchange_multistream(*exch, config.clone(), connections_per_stream)?;
             if let Some(ws) = multistream_ws.take() {
                 multistream_ws = Some(ws.combine_other(new_stream))
             } else {
@@ -142,13 +237,70 @@ impl NormalizedExchangeBuilder {
         Ok(multistream_ws)
     }
 
-    /// builds the multithreaded multistream ws client
+    /// builds the multistream ws client that follows the listings of the
+    /// exchanges, polling their instruments every `interval` to subscribe the
+    /// exchange's channels to the new instruments that match `filter` & to
+    /// unsubscribe from the delisted & deactivated ones, see
+    /// [RefreshingWsStream]
+    pub fn build_refreshing_multistream<F>(
+        self,
+        filter: F,
+        interval: Duration,
+        config: WsStreamConfig,
+        connections_per_stream: Option<usize>,
+    ) -> eyre::Result<RefreshingWsStream>
+    where
+        F: ExchangeFilter<NormalizedInstrument> + Send + 'static,
+    {
+        let exchanges = self.ws_exchanges.keys().copied().collect::<Vec<_>>();
+        let poller = self
+            .environments
+            .iter()
+            .fold(InstrumentPoller::new(&exchanges, interval), |poller, (exch, environment)| {
+                poller.with_environment(*exch, environment.clone())
+            });
+
+        RefreshingWsStream::new(self, poller.into_stream(), filter, config, connections_per_stream)
+    }
+
+    /// builds the multistream of a single exchange's channels
+    pub(crate) fn build_exchange_multistream(
+        &self,
+        exchange: CexExchange,
+        config: WsStreamConfig,
+        connections_per_stream: Option<usize>,
+    ) -> eyre::Result<MultiWsStream> {
+        let channel_map = self
+            .ws_exchanges
+            .get(&exchange)
+            .into_iter()
+            .flat_map(|map| map.values().cloned())
+            .flat_map(|channel| channel.make_many_single())
+            .collect::<Vec<_>>();
+
+        self.build_channels_multistream(exchange, channel_map, config, connections_per_stream)
+    }
+
+    /// builds the multistream of some of the exchange's single channels
+    pub(crate) fn build_channels_multistream(
+        &self,
+        exchange: CexExchange,
+        channels: Vec<NormalizedWsChannels>,
+        config: WsStreamConfig,
+        connections_per_stream: Option<usize>,
+    ) -> eyre::Result<MultiWsStream> {
+        let environment = self.environments.get(&exchange).cloned().unwrap_or_default();
+        exchange.build_multistream_ws_from_normalized(channels, config, connections_per_stream, self.exch_currency_proxy, &environment)
+    }
+
+    /// builds the multithreaded multistream ws client, returning the combined
+    /// stream and a handle to shutdown/join the spawned threads
     pub fn build_all_multithreaded(
         self,
         number_threads: usize,
         config: WsStreamConfig,
         connections_per_stream: Option<usize>,
-    ) -> eyre::Result<Option<Pin<Box<dyn Stream<Item = CombinedWsMessage> + Send>>>> {
+    ) -> eyre::Result<Option<(MultithreadedWsStream, WsThreadHandle)>> {
         let all_streams = self
             .ws_exchanges
             .into_iter()
@@ -160,8 +312,14 @@ impl NormalizedExchangeBuilder {
 
                 debug!(target: "cex-exchanges::live-stream",exchange=?exch, "made {} channels", channel_map.len());
 
-                let streams =
-                    exch.build_multistream_unconnected_raw_ws_from_normalized(channel_map, self.exch_currency_proxy, config, connections_per_stream)?;
+                let environment = self.environments.get(&exch).cloned().unwrap_or_default();
+                let streams = exch.build_multistream_unconnected_raw_ws_from_normalized(
+                    channel_map,
+                    self.exch_currency_proxy,
+                    config.clone(),
+                    connections_per_stream,
+                    &environment,
+                )?;
 
                 debug!(target: "cex-exchanges::live-stream",exchange=?exch, "made {} streams", streams.len());
 
@@ -193,14 +351,14 @@ impl NormalizedExchangeBuilder {
                 owned_stream_chks.push(std::mem::take(&mut temp_chunk));
             }
 
+            let mut handle = WsThreadHandle::new();
             owned_stream_chks.into_iter().for_each(|stream_chk| {
                 debug!(target: "cex-exchanges::live-stream", "made {} streams in stream chunk", stream_chk.len());
-                let tx = tx.clone();
                 let multi = MultiWsStream::build_from_raw(stream_chk);
-                multi.spawn_on_new_thread(tx.clone());
+                handle.push_thread(multi.spawn_on_new_thread(tx.clone(), handle.shutdown_rx()));
             });
 
-            Ok(Some(Box::pin(UnboundedReceiverStream::new(rx))))
+            Ok(Some((Box::pin(UnboundedReceiverStream::new(rx)), handle)))
         } else {
             Ok(None)
         }
@@ -1,4 +1,5 @@
 use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     exchanges::normalized::types::{NormalizedTradingPair, RawTradingPair},
@@ -88,9 +89,25 @@ impl NormalizedWsChannels {
             NormalizedWsChannels::L2(_, _, p) => p.extend(split_pairs)
         }
     }
+
+    pub fn pairs(&self) -> &[NormalizedTradingPair] {
+        match self {
+            NormalizedWsChannels::Trades(p) | NormalizedWsChannels::Quotes(p) | NormalizedWsChannels::L2(_, _, p) => p
+        }
+    }
+
+    /// removes the pairs `f` returns false for
+    pub fn retain_pairs<F>(&mut self, f: F)
+    where
+        F: FnMut(&NormalizedTradingPair) -> bool
+    {
+        match self {
+            NormalizedWsChannels::Trades(p) | NormalizedWsChannels::Quotes(p) | NormalizedWsChannels::L2(_, _, p) => p.retain(f)
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, ValueEnum)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, ValueEnum, Serialize, Deserialize)]
 pub enum NormalizedWsChannelKinds {
     Trades,
     Quotes,
@@ -0,0 +1,366 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf}
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{resolver::InstrumentPairs, NormalizedWsChannelKinds, NormalizedWsChannels};
+use crate::{
+    clients::{rest_api::RestApiError, ws::WsStreamConfig},
+    exchanges::normalized::types::{InstrumentFilter, NormalizedInstrument, NormalizedTradingType, RawTradingPair},
+    traits::{ExchangeFilter, FilterFn},
+    CexExchange, ExchangeEnvironment
+};
+
+/// declarative description of the streams of a
+/// [NormalizedExchangeBuilder](super::NormalizedExchangeBuilder), see
+/// [NormalizedExchangeBuilder::from_config](super::NormalizedExchangeBuilder::from_config)
+///
+/// ```toml
+/// threads = 2
+/// currency_proxy = "Binance"
+///
+/// [stream]
+/// max_retries = 10
+///
+/// [[exchanges]]
+/// exchange = "Binance"
+/// pairs = ["BTC-USDT", "eth_usdt"]
+/// channels = [{ kind = "Trades" }, { kind = "L2", depth = 10, update_speed = 100 }]
+///
+/// [[exchanges]]
+/// exchange = "Okex"
+/// environment = "testnet"
+/// channels = [{ kind = "Quotes" }]
+/// instrument_filters = [{ QuoteOnly = "USDT" }, "Active"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WsFeedConfig {
+    pub exchanges:              Vec<ExchangeFeedConfig>,
+    /// proxy exchange to get symbols for exchanges that don't have a direct api
+    /// link
+    #[serde(default)]
+    pub currency_proxy:         Option<CexExchange>,
+    /// spreads the streams over this many threads, by default they all run on
+    /// the current runtime
+    #[serde(default)]
+    pub threads:                Option<usize>,
+    /// max channels per ws connection, defaults to each exchange's limit
+    #[serde(default)]
+    pub connections_per_stream: Option<usize>,
+    #[serde(default)]
+    pub stream:                 WsStreamConfig
+}
+
+impl WsFeedConfig {
+    pub fn from_toml_str(config: &str) -> Result<Self, WsFeedConfigError> {
+        Ok(toml::from_str(config)?)
+    }
+
+    /// variants with values are yaml tags, ex: `environment: !custom
+    /// http://127.0.0.1:8080` or `instrument_filters: [!QuoteOnly USDT, Active]`
+    pub fn from_yaml_str(config: &str) -> Result<Self, WsFeedConfigError> {
+        Ok(serde_yaml::from_str(config)?)
+    }
+
+    /// reads a `.toml`, `.yaml` or `.yml` file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, WsFeedConfigError> {
+        let path = path.as_ref();
+        let config = fs::read_to_string(path).map_err(|e| WsFeedConfigError::Io(path.to_path_buf(), e))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&config),
+            Some("yaml" | "yml") => Self::from_yaml_str(&config),
+            _ => Err(WsFeedConfigError::UnknownFormat(path.to_path_buf()))
+        }
+    }
+
+    /// checks the config w/o querying the exchanges, the pairs are checked by
+    /// [NormalizedExchangeBuilder::from_config](super::NormalizedExchangeBuilder::from_config)
+    pub fn validate(&self) -> Result<(), WsFeedConfigError> {
+        if self.exchanges.is_empty() {
+            return Err(WsFeedConfigError::NoExchanges)
+        }
+        if self.threads == Some(0) {
+            return Err(WsFeedConfigError::Zero("threads"))
+        }
+        if self.connections_per_stream == Some(0) {
+            return Err(WsFeedConfigError::Zero("connections_per_stream"))
+        }
+
+        let mut exchanges = HashSet::new();
+        self.exchanges.iter().try_for_each(|exch_config| {
+            if !exchanges.insert(exch_config.exchange) {
+                return Err(WsFeedConfigError::DuplicateExchange(exch_config.exchange))
+            }
+            exch_config.validate()
+        })
+    }
+}
+
+/// the channels & pairs of a single exchange
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExchangeFeedConfig {
+    pub exchange:           CexExchange,
+    #[serde(default)]
+    pub environment:        ExchangeEnvironment,
+    pub channels:           Vec<ChannelFeedConfig>,
+    /// subscribed to on every channel, ex: 'BTC-USDT', 'eth_usdc' or the
+    /// exchange's 'BTCUSDT'
+    #[serde(default)]
+    pub pairs:              Vec<String>,
+    /// also subscribes to every instrument matching all of the filters, only
+    /// spot instruments w/o a [InstrumentFilter::TradingType] filter
+    #[serde(default)]
+    pub instrument_filters: Vec<InstrumentFilter>
+}
+
+impl ExchangeFeedConfig {
+    fn validate(&self) -> Result<(), WsFeedConfigError> {
+        if self.channels.is_empty() {
+            return Err(WsFeedConfigError::NoChannels(self.exchange))
+        }
+        if self.pairs.is_empty() && self.instrument_filters.is_empty() {
+            return Err(WsFeedConfigError::NoPairs(self.exchange))
+        }
+
+        let mut kinds = HashSet::new();
+        self.channels.iter().try_for_each(|channel| {
+            if !kinds.insert(channel.kind) {
+                return Err(WsFeedConfigError::DuplicateChannel { exchange: self.exchange, channel: channel.kind })
+            }
+            if !self
+                .exchange
+                .supports_ws_channel(&channel.normalized(self.exchange, &[]))
+            {
+                return Err(WsFeedConfigError::UnsupportedChannel { exchange: self.exchange, channel: *channel })
+            }
+            Ok(())
+        })
+    }
+
+    /// the explicit pairs, which have to be instruments of the exchange, & the
+    /// instruments matching the filters
+    pub(crate) async fn resolve_pairs(&self) -> Result<Vec<RawTradingPair>, WsFeedConfigError> {
+        let mut resolver = InstrumentPairs::query(self.exchange, self.environment.clone())
+            .await
+            .map_err(|error| WsFeedConfigError::RestApi { exchange: self.exchange, error })?;
+
+        let unknown = self
+            .pairs
+            .iter()
+            .filter(|pair| !resolver.add_instrument_pair(RawTradingPair::parse(pair)))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            return Err(WsFeedConfigError::UnknownPairs { exchange: self.exchange, pairs: unknown })
+        }
+
+        if !self.instrument_filters.is_empty() {
+            // spot only, unless the filters select the trading types
+            let spot_only = !self
+                .instrument_filters
+                .iter()
+                .any(|filter| matches!(filter, InstrumentFilter::TradingType(_)));
+
+            resolver.add_matching(&FilterFn(|instrument: &NormalizedInstrument| {
+                (!spot_only || instrument.trading_type == NormalizedTradingType::Spot)
+                    && self
+                        .instrument_filters
+                        .iter()
+                        .all(|filter| filter.matches(instrument))
+            }));
+        }
+
+        let pairs = resolver.into_pairs();
+        if pairs.is_empty() {
+            return Err(WsFeedConfigError::NoPairs(self.exchange))
+        }
+
+        Ok(pairs)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelFeedConfig {
+    pub kind:         NormalizedWsChannelKinds,
+    /// l2 only, the number of levels of the book or the diffs if `None`
+    #[serde(default)]
+    pub depth:        Option<u64>,
+    /// l2 only, in ms
+    #[serde(default = "ChannelFeedConfig::default_update_speed")]
+    pub update_speed: Option<u64>
+}
+
+impl ChannelFeedConfig {
+    /// trades/quotes, or l2 diffs every 100ms
+    pub fn new(kind: NormalizedWsChannelKinds) -> Self {
+        Self { kind, depth: None, update_speed: Self::default_update_speed() }
+    }
+
+    pub fn with_depth(mut self, depth: u64) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    pub fn with_update_speed(mut self, update_speed: Option<u64>) -> Self {
+        self.update_speed = update_speed;
+        self
+    }
+
+    pub(crate) fn normalized(&self, exchange: CexExchange, pairs: &[RawTradingPair]) -> NormalizedWsChannels {
+        NormalizedWsChannels::new_with_pairs(exchange, self.kind, pairs, Some((self.depth, self.update_speed)))
+    }
+
+    fn default_update_speed() -> Option<u64> {
+        Some(100)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WsFeedConfigError {
+    #[error("failed to read {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("unknown format of {0}, expected a .toml, .yaml or .yml file")]
+    UnknownFormat(PathBuf),
+    #[error("failed to parse the toml config: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("failed to parse the yaml config: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("no exchanges configured")]
+    NoExchanges,
+    #[error("{0} is configured more than once")]
+    DuplicateExchange(CexExchange),
+    #[error("`{0}` must be at least 1")]
+    Zero(&'static str),
+    #[error("no channels configured for {0}")]
+    NoChannels(CexExchange),
+    #[error("{channel:?} is configured more than once for {exchange}")]
+    DuplicateChannel { exchange: CexExchange, channel: NormalizedWsChannelKinds },
+    #[error("{exchange} does not support {channel:?}")]
+    UnsupportedChannel { exchange: CexExchange, channel: ChannelFeedConfig },
+    #[error("no pairs to subscribe to on {0}, `pairs` & `instrument_filters` are empty or match no instruments")]
+    NoPairs(CexExchange),
+    #[error("{exchange} has no instruments {pairs:?}")]
+    UnknownPairs { exchange: CexExchange, pairs: Vec<String> },
+    #[error("failed to query the {exchange} instruments: {error}")]
+    RestApi { exchange: CexExchange, error: RestApiError }
+}
+
+#[cfg(test)]
+#[cfg(feature = "non-us")]
+mod tests {
+    use super::*;
+
+    const CONFIG_TOML: &str = r#"
+        threads = 2
+
+        [stream]
+        max_retries = 10
+
+        [stream.recorder]
+        dir = "recordings"
+        max_file_age_secs = 600
+
+        [[exchanges]]
+        exchange = "Binance"
+        pairs = ["BTC-USDT", "eth_usdt"]
+        channels = [{ kind = "Trades" }, { kind = "L2", depth = 10 }]
+
+        [[exchanges]]
+        exchange = "Kucoin"
+        environment = { custom = "http://127.0.0.1:8080" }
+        channels = [{ kind = "Quotes" }]
+        instrument_filters = [{ QuoteOnly = "USDT" }, "Active"]
+    "#;
+
+    const CONFIG_YAML: &str = r#"
+        threads: 2
+        stream:
+          max_retries: 10
+          recorder:
+            dir: recordings
+            max_file_age_secs: 600
+        exchanges:
+          - exchange: Binance
+            pairs: [BTC-USDT, eth_usdt]
+            channels: [{ kind: Trades }, { kind: L2, depth: 10 }]
+          - exchange: Kucoin
+            environment: !custom http://127.0.0.1:8080
+            channels: [{ kind: Quotes }]
+            instrument_filters: [!QuoteOnly USDT, Active]
+    "#;
+
+    fn binance_config(channels: Vec<ChannelFeedConfig>) -> ExchangeFeedConfig {
+        ExchangeFeedConfig {
+            exchange: CexExchange::Binance,
+            environment: ExchangeEnvironment::Production,
+            channels,
+            pairs: vec!["BTC-USDT".to_string()],
+            instrument_filters: Vec::new()
+        }
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = WsFeedConfig::from_toml_str(CONFIG_TOML).unwrap();
+        assert_eq!(config, WsFeedConfig::from_yaml_str(CONFIG_YAML).unwrap());
+        config.validate().unwrap();
+
+        assert_eq!(config.threads, Some(2));
+        assert_eq!(config.stream.max_retries, Some(10));
+        let recorder = config.stream.recorder.unwrap();
+        assert_eq!(recorder.max_file_age, Some(std::time::Duration::from_secs(600)));
+        assert_eq!(recorder.max_file_bytes, Some(256 * 1024 * 1024));
+
+        assert_eq!(
+            config.exchanges[0].channels,
+            vec![ChannelFeedConfig::new(NormalizedWsChannelKinds::Trades), ChannelFeedConfig::new(NormalizedWsChannelKinds::L2).with_depth(10)]
+        );
+        assert_eq!(config.exchanges[1].environment, ExchangeEnvironment::Custom("http://127.0.0.1:8080".to_string()));
+        assert_eq!(config.exchanges[1].instrument_filters, vec![InstrumentFilter::QuoteOnly("USDT".to_string()), InstrumentFilter::Active]);
+
+        assert!(matches!(WsFeedConfig::from_toml_str("threads = 2\nexchanges = []\nunknown = 1"), Err(WsFeedConfigError::Toml(_))));
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let config = |exchanges| WsFeedConfig { exchanges, ..Default::default() };
+        let l2 = ChannelFeedConfig::new(NormalizedWsChannelKinds::L2);
+
+        assert!(config(vec![binance_config(vec![l2.with_depth(20).with_update_speed(Some(1000))])])
+            .validate()
+            .is_ok());
+        assert!(matches!(config(Vec::new()).validate(), Err(WsFeedConfigError::NoExchanges)));
+        assert!(matches!(
+            config(vec![binance_config(vec![l2.with_depth(15)])]).validate(),
+            Err(WsFeedConfigError::UnsupportedChannel { exchange: CexExchange::Binance, .. })
+        ));
+        assert!(matches!(
+            config(vec![binance_config(vec![l2, l2.with_depth(5)])]).validate(),
+            Err(WsFeedConfigError::DuplicateChannel { channel: NormalizedWsChannelKinds::L2, .. })
+        ));
+        assert!(matches!(
+            config(vec![binance_config(vec![l2]), binance_config(vec![l2])]).validate(),
+            Err(WsFeedConfigError::DuplicateExchange(CexExchange::Binance))
+        ));
+
+        let kucoin = ExchangeFeedConfig { exchange: CexExchange::Kucoin, ..binance_config(vec![l2]) };
+        assert!(matches!(
+            config(vec![kucoin.clone()]).validate(),
+            Err(WsFeedConfigError::UnsupportedChannel { exchange: CexExchange::Kucoin, .. })
+        ));
+
+        let no_pairs = ExchangeFeedConfig { pairs: Vec::new(), channels: vec![ChannelFeedConfig::new(NormalizedWsChannelKinds::Trades)], ..kucoin };
+        assert!(matches!(config(vec![no_pairs]).validate(), Err(WsFeedConfigError::NoPairs(CexExchange::Kucoin))));
+
+        let threads = WsFeedConfig { threads: Some(0), ..config(vec![binance_config(vec![l2])]) };
+        assert!(matches!(threads.validate(), Err(WsFeedConfigError::Zero("threads"))));
+    }
+}
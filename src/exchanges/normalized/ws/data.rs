@@ -1,35 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
 use crate::{
+    clients::ws::{ReceiveTime, WsErrorKind},
     normalized::types::{NormalizedL2, NormalizedQuote, NormalizedTrade, NormalizedTradingPair},
     CexExchange, Exchange
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum NormalizedWsDataTypes {
     Trade(NormalizedTrade),
     Trades(Vec<NormalizedTrade>),
     Quote(NormalizedQuote),
     Quotes(Vec<NormalizedQuote>),
     L2(NormalizedL2),
-    Disconnect { exchange: CexExchange, message: String, raw_message: String },
+    Disconnect { exchange: CexExchange, kind: WsErrorKind, message: String, raw_message: String },
     RemovedPair { exchange: CexExchange, bad_pair: NormalizedTradingPair, raw_message: String },
     Other { exchange: CexExchange, kind: String, value: String }
 }
 
+impl NormalizedWsDataTypes {
+    /// timestamp set by the exchange, for multiple values the latest timestamp
+    /// is used
+    pub fn exchange_time(&self) -> Option<DateTime<Utc>> {
+        match self {
+            NormalizedWsDataTypes::Trade(t) => Some(t.time),
+            NormalizedWsDataTypes::Trades(ts) => ts.iter().map(|t| t.time).max(),
+            NormalizedWsDataTypes::Quote(q) => q.orderbook_ids_time.time,
+            NormalizedWsDataTypes::Quotes(qs) => qs.iter().filter_map(|q| q.orderbook_ids_time.time).max(),
+            NormalizedWsDataTypes::L2(l2) => l2.orderbook_ids_time.time,
+            NormalizedWsDataTypes::Disconnect { .. } | NormalizedWsDataTypes::RemovedPair { .. } | NormalizedWsDataTypes::Other { .. } => None
+        }
+    }
+}
+
 pub(crate) enum MessageOrPing<T: Exchange> {
-    Message(T::WsMessage),
+    Message(T::WsMessage, ReceiveTime),
     Ping(Vec<u8>),
+    /// pongs & raw frames that don't need to be handled
+    Skip,
     Close
 }
 
 impl<T: Exchange> MessageOrPing<T> {
-    pub(crate) fn new_message(msg: T::WsMessage) -> Self {
-        MessageOrPing::Message(msg)
+    pub(crate) fn new_message(msg: T::WsMessage, receive_time: ReceiveTime) -> Self {
+        MessageOrPing::Message(msg, receive_time)
     }
 
     pub(crate) fn new_ping(ping: Vec<u8>) -> Self {
         MessageOrPing::Ping(ping)
     }
 
+    pub(crate) fn new_skip() -> Self {
+        MessageOrPing::Skip
+    }
+
     pub(crate) fn new_close() -> Self {
         MessageOrPing::Close
     }
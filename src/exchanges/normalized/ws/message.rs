@@ -1,24 +1,31 @@
+use chrono::{DateTime, Utc};
+
 use super::NormalizedWsDataTypes;
 #[cfg(feature = "non-us")]
 use crate::{binance::ws::BinanceWsMessage, bybit::ws::BybitWsMessage, kucoin::ws::KucoinWsMessage};
 #[cfg(feature = "us")]
 use crate::{exchanges::coinbase::ws::CoinbaseWsMessage, exchanges::okex::ws::OkexWsMessage};
-use crate::{normalized::types::NormalizedTradingPair, CexExchange};
+use crate::{
+    clients::ws::{ReceiveTime, WsErrorKind},
+    normalized::types::NormalizedTradingPair,
+    CexExchange
+};
 
 #[derive(Debug, Clone)]
 pub enum CombinedWsMessage {
     #[cfg(feature = "us")]
-    Coinbase(CoinbaseWsMessage),
+    Coinbase(CoinbaseWsMessage, ReceiveTime),
     #[cfg(feature = "us")]
-    Okex(OkexWsMessage),
+    Okex(OkexWsMessage, ReceiveTime),
     #[cfg(feature = "non-us")]
-    Binance(BinanceWsMessage),
+    Binance(BinanceWsMessage, ReceiveTime),
     #[cfg(feature = "non-us")]
-    Kucoin(KucoinWsMessage),
+    Kucoin(KucoinWsMessage, ReceiveTime),
     #[cfg(feature = "non-us")]
-    Bybit(BybitWsMessage),
+    Bybit(BybitWsMessage, ReceiveTime),
     Disconnect {
         exchange:    CexExchange,
+        kind:        WsErrorKind,
         message:     String,
         raw_message: String,
         bad_pair:    Option<NormalizedTradingPair>
@@ -34,22 +41,92 @@ impl CombinedWsMessage {
     pub fn normalize(self) -> NormalizedWsDataTypes {
         match self {
             #[cfg(feature = "us")]
-            CombinedWsMessage::Coinbase(c) => c.normalize(),
+            CombinedWsMessage::Coinbase(c, _) => c.normalize(),
             #[cfg(feature = "us")]
-            CombinedWsMessage::Okex(c) => c.normalize(),
+            CombinedWsMessage::Okex(c, _) => c.normalize(),
             #[cfg(feature = "non-us")]
-            CombinedWsMessage::Binance(c) => c.normalize(),
+            CombinedWsMessage::Binance(c, _) => c.normalize(),
             #[cfg(feature = "non-us")]
-            CombinedWsMessage::Kucoin(c) => c.normalize(),
+            CombinedWsMessage::Kucoin(c, _) => c.normalize(),
             #[cfg(feature = "non-us")]
-            CombinedWsMessage::Bybit(c) => c.normalize(),
-            CombinedWsMessage::Disconnect { exchange, message, raw_message, .. } => {
-                NormalizedWsDataTypes::Disconnect { exchange, message, raw_message }
+            CombinedWsMessage::Bybit(c, _) => c.normalize(),
+            CombinedWsMessage::Disconnect { exchange, kind, message, raw_message, .. } => {
+                NormalizedWsDataTypes::Disconnect { exchange, kind, message, raw_message }
             }
             CombinedWsMessage::BadPair { exchange, raw_message, bad_pair } => NormalizedWsDataTypes::RemovedPair { exchange, raw_message, bad_pair }
         }
     }
 
+    /// the exchange's timestamp of the message w/o normalizing it, see
+    /// [NormalizedWsDataTypes::exchange_time]
+    pub fn exchange_time(&self) -> Option<DateTime<Utc>> {
+        match self {
+            #[cfg(feature = "us")]
+            CombinedWsMessage::Coinbase(c, _) => c.exchange_time(),
+            #[cfg(feature = "us")]
+            CombinedWsMessage::Okex(c, _) => c.exchange_time(),
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Binance(c, _) => c.exchange_time(),
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Kucoin(c, _) => c.exchange_time(),
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Bybit(c, _) => c.exchange_time(),
+            CombinedWsMessage::Disconnect { .. } | CombinedWsMessage::BadPair { .. } => None
+        }
+    }
+
+    pub fn exchange(&self) -> CexExchange {
+        match self {
+            #[cfg(feature = "us")]
+            CombinedWsMessage::Coinbase(..) => CexExchange::Coinbase,
+            #[cfg(feature = "us")]
+            CombinedWsMessage::Okex(..) => CexExchange::Okex,
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Binance(..) => CexExchange::Binance,
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Kucoin(..) => CexExchange::Kucoin,
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Bybit(..) => CexExchange::Bybit,
+            CombinedWsMessage::Disconnect { exchange, .. } => *exchange,
+            CombinedWsMessage::BadPair { exchange, .. } => *exchange
+        }
+    }
+
+    /// local time the message was recieved, `None` for errors
+    pub fn receive_time(&self) -> Option<ReceiveTime> {
+        match self {
+            #[cfg(feature = "us")]
+            CombinedWsMessage::Coinbase(_, t) => Some(*t),
+            #[cfg(feature = "us")]
+            CombinedWsMessage::Okex(_, t) => Some(*t),
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Binance(_, t) => Some(*t),
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Kucoin(_, t) => Some(*t),
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Bybit(_, t) => Some(*t),
+            CombinedWsMessage::Disconnect { .. } | CombinedWsMessage::BadPair { .. } => None
+        }
+    }
+
+    pub(crate) fn with_receive_time(mut self, receive_time: ReceiveTime) -> Self {
+        match &mut self {
+            #[cfg(feature = "us")]
+            CombinedWsMessage::Coinbase(_, t) => *t = receive_time,
+            #[cfg(feature = "us")]
+            CombinedWsMessage::Okex(_, t) => *t = receive_time,
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Binance(_, t) => *t = receive_time,
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Kucoin(_, t) => *t = receive_time,
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Bybit(_, t) => *t = receive_time,
+            CombinedWsMessage::Disconnect { .. } | CombinedWsMessage::BadPair { .. } => ()
+        }
+
+        self
+    }
+
     pub fn is_ok(&self) -> bool {
         !self.is_err()
     }
@@ -58,11 +135,41 @@ impl CombinedWsMessage {
         matches!(self, CombinedWsMessage::Disconnect { .. })
     }
 
+    /// kind of the error for disconnects and errors sent by the exchange,
+    /// `None` for all other messages
+    pub fn error_kind(&self) -> Option<WsErrorKind> {
+        match self {
+            #[cfg(feature = "us")]
+            CombinedWsMessage::Coinbase(CoinbaseWsMessage::Error(err), _) => Some(if err.bad_pair.is_some() {
+                WsErrorKind::InvalidSymbol
+            } else {
+                WsErrorKind::from_exchange_message(CexExchange::Coinbase, &err.reason).unwrap_or(WsErrorKind::Protocol)
+            }),
+            #[cfg(feature = "us")]
+            CombinedWsMessage::Okex(OkexWsMessage::Error { error, raw_msg, bad_pair }, _) => Some(if bad_pair.is_some() {
+                WsErrorKind::InvalidSymbol
+            } else {
+                WsErrorKind::from_exchange_message(CexExchange::Okex, raw_msg)
+                    .or_else(|| WsErrorKind::from_exchange_message(CexExchange::Okex, error))
+                    .unwrap_or(WsErrorKind::Protocol)
+            }),
+            #[cfg(feature = "non-us")]
+            CombinedWsMessage::Bybit(BybitWsMessage::InvalidSymbol { .. }, _) => Some(WsErrorKind::InvalidSymbol),
+            CombinedWsMessage::Disconnect { kind, .. } => Some(*kind),
+            CombinedWsMessage::BadPair { .. } => Some(WsErrorKind::InvalidSymbol),
+            _ => None
+        }
+    }
+
     pub fn bad_pair(&self) -> Option<NormalizedTradingPair> {
         match self {
             CombinedWsMessage::Disconnect { bad_pair, .. } => bad_pair.clone(),
+            #[cfg(feature = "us")]
+            CombinedWsMessage::Coinbase(CoinbaseWsMessage::Error(err), _) => err.bad_pair.clone().map(|p| p.normalize()),
+            #[cfg(feature = "us")]
+            CombinedWsMessage::Okex(OkexWsMessage::Error { bad_pair, .. }, _) => bad_pair.clone().map(|p| p.normalize()),
             #[cfg(feature = "non-us")]
-            CombinedWsMessage::Bybit(BybitWsMessage::InvalidSymbol { pair, .. }) => Some(pair.clone().normalize()),
+            CombinedWsMessage::Bybit(BybitWsMessage::InvalidSymbol { pair, .. }, _) => Some(pair.clone().normalize()),
             _ => None
         }
     }
@@ -73,7 +180,7 @@ macro_rules! combined_ws {
         paste::paste! {
             impl From<[<$exchange WsMessage>]> for CombinedWsMessage {
                 fn from(value: [<$exchange WsMessage>]) -> Self {
-                    Self::$exchange(value)
+                    Self::$exchange(value, ReceiveTime::now())
                 }
             }
         }
@@ -99,15 +206,15 @@ impl PartialEq<NormalizedWsDataTypes> for CombinedWsMessage {
     fn eq(&self, other: &NormalizedWsDataTypes) -> bool {
         match self {
             #[cfg(feature = "us")]
-            CombinedWsMessage::Coinbase(vals) => vals == other,
+            CombinedWsMessage::Coinbase(vals, _) => vals == other,
             #[cfg(feature = "us")]
-            CombinedWsMessage::Okex(vals) => vals == other,
+            CombinedWsMessage::Okex(vals, _) => vals == other,
             #[cfg(feature = "non-us")]
-            CombinedWsMessage::Binance(vals) => vals == other,
+            CombinedWsMessage::Binance(vals, _) => vals == other,
             #[cfg(feature = "non-us")]
-            CombinedWsMessage::Kucoin(vals) => vals == other,
+            CombinedWsMessage::Kucoin(vals, _) => vals == other,
             #[cfg(feature = "non-us")]
-            CombinedWsMessage::Bybit(vals) => vals == other,
+            CombinedWsMessage::Bybit(vals, _) => vals == other,
             CombinedWsMessage::Disconnect { .. } => true,
             CombinedWsMessage::BadPair { .. } => true
         }
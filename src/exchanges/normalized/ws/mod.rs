@@ -4,8 +4,16 @@ pub use builder::*;
 mod channels;
 pub use channels::*;
 
+mod config;
+pub use config::*;
+
 mod message;
 pub use message::*;
 
 mod data;
 pub use data::*;
+
+mod refresh;
+pub use refresh::*;
+
+mod resolver;
@@ -0,0 +1,396 @@
+use std::{
+    collections::{HashMap, HashSet},
+    pin::Pin,
+    task::{Context, Poll}
+};
+
+use futures::{Stream, StreamExt};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_stream::StreamMap;
+use tracing::{debug, error, info, warn};
+
+use super::{CombinedWsMessage, NormalizedExchangeBuilder, NormalizedWsChannels};
+use crate::{
+    clients::ws::{MultiWsStream, WsError, WsStreamConfig},
+    exchanges::normalized::{
+        registry::InstrumentEvent,
+        types::{NormalizedInstrument, NormalizedTradingPair, RawTradingPair}
+    },
+    traits::ExchangeFilter,
+    CexExchange
+};
+
+type InstrumentEventStream = Pin<Box<dyn Stream<Item = InstrumentEvent> + Send>>;
+
+/// multistream that follows the listings of its exchanges, built w/
+/// [NormalizedExchangeBuilder::build_refreshing_multistream]
+///
+/// a listed instrument that matches the filter is added to every channel of
+/// its exchange & a status change adds or removes the instrument if it starts
+/// or stops matching (ex: w/ [InstrumentFilter::Active](crate::normalized::types::InstrumentFilter::Active)).
+/// a delisted instrument is always removed, even if it was subscribed
+/// explicitly w/o matching the filter.
+/// like the bad pair path, the subscription is changed by reconnecting: only
+/// the connections that lost a pair are replaced & the listed pairs get new
+/// connections, every other connection is kept
+pub struct RefreshingWsStream {
+    builder:                NormalizedExchangeBuilder,
+    filter:                 Box<dyn ExchangeFilter<NormalizedInstrument> + Send>,
+    events:                 InstrumentEventStream,
+    events_tx:              Option<UnboundedSender<InstrumentEvent>>,
+    /// every connection is it's own stream, keyed by it's id
+    streams:                StreamMap<usize, MultiWsStream>,
+    /// the exchange & single channels of every connection
+    connections:            HashMap<usize, (CexExchange, Vec<NormalizedWsChannels>)>,
+    next_connection_id:     usize,
+    /// exchanges w/o pairs after a refresh, waiting for a listing
+    emptied:                HashSet<CexExchange>,
+    closed:                 bool,
+    config:                 WsStreamConfig,
+    connections_per_stream: Option<usize>
+}
+
+impl RefreshingWsStream {
+    pub(crate) fn new<S, F>(
+        builder: NormalizedExchangeBuilder,
+        events: S,
+        filter: F,
+        config: WsStreamConfig,
+        connections_per_stream: Option<usize>
+    ) -> eyre::Result<Self>
+    where
+        S: Stream<Item = InstrumentEvent> + Send + 'static,
+        F: ExchangeFilter<NormalizedInstrument> + Send + 'static
+    {
+        let mut this = Self {
+            builder,
+            filter: Box::new(filter),
+            events: Box::pin(events),
+            events_tx: None,
+            streams: StreamMap::new(),
+            connections: HashMap::new(),
+            next_connection_id: 0,
+            emptied: HashSet::new(),
+            closed: false,
+            config,
+            connections_per_stream
+        };
+
+        let exchanges = this.builder.ws_exchanges.keys().copied().collect::<Vec<_>>();
+        exchanges.into_iter().try_for_each(|exch| {
+            let connections = this.build_connections(exch, this.single_channels(exch))?;
+            this.insert_connections(exch, connections);
+            Ok::<_, eyre::ErrReport>(())
+        })?;
+
+        Ok(this)
+    }
+
+    /// forwards the instrument events to `events_tx` after they are applied
+    pub fn with_event_sender(mut self, events_tx: UnboundedSender<InstrumentEvent>) -> Self {
+        self.events_tx = Some(events_tx);
+        self
+    }
+
+    pub fn stream_count(&self) -> usize {
+        self.streams
+            .values()
+            .map(MultiWsStream::stream_count)
+            .sum()
+    }
+
+    /// the currently subscribed pairs of every channel of the exchange
+    pub fn subscribed_pairs(&self, exchange: CexExchange) -> Vec<NormalizedTradingPair> {
+        let mut pairs = self
+            .builder
+            .ws_exchanges
+            .get(&exchange)
+            .into_iter()
+            .flat_map(|channels| channels.values())
+            .flat_map(|channel| channel.pairs().iter().cloned())
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs.dedup();
+
+        pairs
+    }
+
+    /// sends a `Close` frame on every inner stream, returning the first error.
+    /// the stream ends afterwards
+    pub async fn close(&mut self) -> Result<(), WsError> {
+        self.closed = true;
+        futures::future::join_all(self.streams.values_mut().map(MultiWsStream::close))
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(())
+    }
+
+    /// updates the exchange's channels w/ the event, returning true if its
+    /// subscription changed
+    fn apply(&mut self, event: &InstrumentEvent) -> bool {
+        let (subscribe, instrument) = match event {
+            InstrumentEvent::Listed(instrument) if self.filter.matches(instrument) => (true, instrument),
+            InstrumentEvent::Delisted(instrument) => (false, instrument),
+            InstrumentEvent::StatusChanged { previous, current } => match (self.filter.matches(previous), self.filter.matches(current)) {
+                (false, true) => (true, current),
+                (true, false) => (false, current),
+                _ => return false
+            },
+            _ => return false
+        };
+
+        let exchange = instrument.exchange;
+        let Some(channels) = self.builder.ws_exchanges.get_mut(&exchange) else { return false };
+
+        let raw_pair = RawTradingPair::from(instrument.trading_pair.clone());
+        let native = native_pair(exchange, &raw_pair.get_normalized_pair(exchange));
+        let is_subscribed = channels
+            .values()
+            .any(|channel| channel.pairs().iter().any(|pair| native_pair(exchange, pair) == native));
+
+        if subscribe && !is_subscribed {
+            info!(target: "cex-exchanges::live-stream", exchange=?exchange, "subscribing to the listed pair {:?}", instrument.trading_pair);
+            channels
+                .values_mut()
+                .for_each(|channel| channel.add_pairs(exchange, std::slice::from_ref(&raw_pair)));
+            true
+        } else if !subscribe && is_subscribed {
+            info!(target: "cex-exchanges::live-stream", exchange=?exchange, "unsubscribing from the delisted pair {:?}", instrument.trading_pair);
+            channels
+                .values_mut()
+                .for_each(|channel| channel.retain_pairs(|pair| native_pair(exchange, pair) != native));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// replaces the exchange's connections that lost a channel & connects the
+    /// new channels, keeping the old connections if the new ones can't be
+    /// built
+    fn refresh(&mut self, exchange: CexExchange) {
+        let mut channels = self.single_channels(exchange);
+        let current = channels.iter().cloned().collect::<HashSet<_>>();
+
+        // ended connections are reconnected too
+        let changed = self
+            .connections
+            .iter()
+            .filter(|(id, (exch, connection))| {
+                *exch == exchange && (!self.streams.contains_key(id) || !connection.iter().all(|channel| current.contains(channel)))
+            })
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        let kept = self
+            .connections
+            .iter()
+            .filter(|(id, (exch, _))| *exch == exchange && !changed.contains(id))
+            .flat_map(|(_, (_, connection))| connection)
+            .collect::<HashSet<_>>();
+        channels.retain(|channel| !kept.contains(channel));
+
+        let new_connections = match self.build_connections(exchange, channels) {
+            Ok(new_connections) => new_connections,
+            Err(e) => {
+                error!(target: "cex-exchanges::live-stream", exchange=?exchange, "error refreshing the streams: {:?}", e);
+                return
+            }
+        };
+        let (replaced, connected) = (changed.len(), new_connections.len());
+        debug!(target: "cex-exchanges::live-stream", exchange=?exchange, "replaced {replaced} connections w/ {connected} new ones");
+
+        if current.is_empty() {
+            warn!(target: "cex-exchanges::live-stream", exchange=?exchange, "no pairs left to subscribe to");
+            self.emptied.insert(exchange);
+        } else {
+            self.emptied.remove(&exchange);
+        }
+
+        changed.into_iter().for_each(|id| {
+            self.connections.remove(&id);
+            if let Some(mut old) = self.streams.remove(&id) {
+                tokio::spawn(async move {
+                    if let Err(e) = old.close().await {
+                        warn!(target: "cex-exchanges::live-stream", exchange=?exchange, "error closing the refreshed streams: {:?}", e);
+                    }
+                });
+            }
+        });
+        self.insert_connections(exchange, new_connections);
+    }
+
+    /// the exchange's channels w/ a single pair each
+    fn single_channels(&self, exchange: CexExchange) -> Vec<NormalizedWsChannels> {
+        self.builder
+            .ws_exchanges
+            .get(&exchange)
+            .into_iter()
+            .flat_map(|channels| channels.values().cloned())
+            .flat_map(NormalizedWsChannels::make_many_single)
+            .collect()
+    }
+
+    /// builds a stream for every connection of the channels
+    fn build_connections(
+        &self,
+        exchange: CexExchange,
+        channels: Vec<NormalizedWsChannels>
+    ) -> eyre::Result<Vec<(Vec<NormalizedWsChannels>, MultiWsStream)>> {
+        let streams_per_connection = self
+            .connections_per_stream
+            .unwrap_or(exchange.max_streams_per_connection());
+
+        channels
+            .chunks(streams_per_connection)
+            .map(|connection| {
+                let stream = self.builder.build_channels_multistream(
+                    exchange,
+                    connection.to_vec(),
+                    self.config.clone(),
+                    Some(streams_per_connection)
+                )?;
+                Ok((connection.to_vec(), stream))
+            })
+            .collect()
+    }
+
+    fn insert_connections(&mut self, exchange: CexExchange, connections: Vec<(Vec<NormalizedWsChannels>, MultiWsStream)>) {
+        connections.into_iter().for_each(|(channels, stream)| {
+            let id = self.next_connection_id;
+            self.next_connection_id += 1;
+
+            self.connections.insert(id, (exchange, channels));
+            self.streams.insert(id, stream);
+        });
+    }
+}
+
+impl Stream for RefreshingWsStream {
+    type Item = CombinedWsMessage;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.closed {
+            return Poll::Ready(None)
+        }
+
+        let mut refreshed = HashSet::new();
+        while let Poll::Ready(Some(event)) = this.events.poll_next_unpin(cx) {
+            if this.apply(&event) {
+                refreshed.insert(event.exchange());
+            }
+            if let Some(tx) = this.events_tx.as_ref() {
+                let _ = tx.send(event);
+            }
+        }
+        refreshed
+            .into_iter()
+            .for_each(|exchange| this.refresh(exchange));
+
+        match this.streams.poll_next_unpin(cx) {
+            Poll::Ready(Some((_, msg))) => Poll::Ready(Some(msg)),
+            // ends w/ it's connections, unless a refresh removed every pair of
+            // an exchange, which waits for the next listing
+            Poll::Ready(None) if this.emptied.is_empty() => Poll::Ready(None),
+            _ => Poll::Pending
+        }
+    }
+}
+
+fn native_pair(exchange: CexExchange, pair: &NormalizedTradingPair) -> Option<String> {
+    exchange
+        .denormalize_raw_trading_pair(pair.clone())
+        .ok()
+}
+
+#[cfg(test)]
+#[cfg(feature = "non-us")]
+mod tests {
+    use futures::stream;
+
+    use super::*;
+    use crate::{
+        normalized::{
+            types::{InstrumentFilter, NormalizedTradingType},
+            ws::NormalizedWsChannelKinds
+        },
+        traits::FilterExt
+    };
+
+    fn instrument(base: &str, quote: &str, active: bool) -> NormalizedInstrument {
+        NormalizedInstrument {
+            exchange: CexExchange::Binance,
+            trading_pair: NormalizedTradingPair::new_base_quote(CexExchange::Binance, base, quote, None, None),
+            trading_type: NormalizedTradingType::Spot,
+            base_asset_symbol: base.to_string(),
+            quote_asset_symbol: quote.to_string(),
+            active,
+            futures_expiry: None
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_instrument_events() {
+        let mut builder = NormalizedExchangeBuilder::new();
+        builder.add_pairs_all_channels(
+            CexExchange::Binance,
+            &[NormalizedWsChannelKinds::Trades, NormalizedWsChannelKinds::Quotes],
+            &[RawTradingPair::new_base_quote("BTC", "USDT", None)]
+        );
+        let filter = InstrumentFilter::Active.and(InstrumentFilter::quote_only("USDT".to_string()));
+        let mut stream = RefreshingWsStream::new(builder, stream::pending(), filter, WsStreamConfig::default(), Some(1)).unwrap();
+        assert_eq!(stream.stream_count(), 2);
+        let initial = stream.streams.keys().copied().collect::<HashSet<_>>();
+
+        let events = [
+            InstrumentEvent::Listed(instrument("ETH", "USDT", true)),
+            InstrumentEvent::Listed(instrument("ETH", "BTC", true)),
+            InstrumentEvent::Listed(instrument("BTC", "USDT", true)),
+            InstrumentEvent::StatusChanged { previous: instrument("SOL", "USDT", false), current: instrument("SOL", "USDT", true) }
+        ];
+        let refreshed = events
+            .iter()
+            .filter(|event| stream.apply(event))
+            .count();
+        assert_eq!(refreshed, 2);
+        assert_eq!(stream.subscribed_pairs(CexExchange::Binance).len(), 3);
+
+        stream.refresh(CexExchange::Binance);
+        assert_eq!(stream.stream_count(), 6);
+        assert!(initial.iter().all(|id| stream.streams.contains_key(id)));
+
+        assert!(stream.apply(&InstrumentEvent::Delisted(instrument("ETH", "USDT", true))));
+        assert!(stream.apply(&InstrumentEvent::StatusChanged {
+            previous: instrument("SOL", "USDT", true),
+            current:  instrument("SOL", "USDT", false)
+        }));
+        assert!(!stream.apply(&InstrumentEvent::Delisted(instrument("DOGE", "USDT", true))));
+        assert_eq!(stream.subscribed_pairs(CexExchange::Binance).len(), 1);
+
+        // only the connections of the removed pairs are dropped
+        stream.refresh(CexExchange::Binance);
+        assert_eq!(stream.streams.keys().copied().collect::<HashSet<_>>(), initial);
+    }
+
+    #[tokio::test]
+    async fn test_delist_unmatched_pair() {
+        let mut builder = NormalizedExchangeBuilder::new();
+        builder.add_pairs_all_channels(
+            CexExchange::Binance,
+            &[NormalizedWsChannelKinds::Trades],
+            &[RawTradingPair::new_base_quote("BTC", "USDT", None), RawTradingPair::new_base_quote("ETH", "BTC", None)]
+        );
+        let filter = InstrumentFilter::quote_only("USDT".to_string());
+        let mut stream = RefreshingWsStream::new(builder, stream::pending(), filter, WsStreamConfig::default(), Some(1)).unwrap();
+        assert_eq!(stream.stream_count(), 2);
+
+        assert!(stream.apply(&InstrumentEvent::Delisted(instrument("ETH", "BTC", true))));
+        assert_eq!(stream.subscribed_pairs(CexExchange::Binance).len(), 1);
+
+        stream.refresh(CexExchange::Binance);
+        assert_eq!(stream.stream_count(), 1);
+    }
+}
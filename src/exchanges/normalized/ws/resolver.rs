@@ -0,0 +1,88 @@
+use std::collections::HashSet;
+
+use tracing::warn;
+
+use crate::{
+    clients::rest_api::{ExchangeApi, RestApiError},
+    exchanges::normalized::types::{NormalizedInstrument, RawTradingPair},
+    traits::{EmptyFilter, ExchangeFilter},
+    CexExchange, ExchangeEnvironment
+};
+
+/// resolves the pairs to subscribe to on an exchange from its instruments,
+/// shared by [NormalizedExchangeBuilder::from_config](super::NormalizedExchangeBuilder::from_config)
+/// & [NormalizedExchangeBuilder::add_channels_from_instruments](super::NormalizedExchangeBuilder::add_channels_from_instruments)
+///
+/// a pair is only added once per native symbol, the instruments of different
+/// trading types can share one (ex: spot & margin)
+pub(crate) struct InstrumentPairs {
+    exchange:         CexExchange,
+    instruments:      Vec<NormalizedInstrument>,
+    /// the native symbols of the instruments
+    instrument_pairs: HashSet<String>,
+    subscribed:       HashSet<String>,
+    pairs:            Vec<RawTradingPair>
+}
+
+impl InstrumentPairs {
+    /// queries the instruments of the exchange from `environment`
+    pub(crate) async fn query(exchange: CexExchange, environment: ExchangeEnvironment) -> Result<Self, RestApiError> {
+        let instruments = exchange
+            .get_all_instruments_with_api(&ExchangeApi::new().with_environment(environment), None::<EmptyFilter>)
+            .await?;
+
+        let instrument_pairs = instruments
+            .iter()
+            .filter_map(|instrument| native_pair(exchange, &RawTradingPair::from(instrument.trading_pair.clone())))
+            .collect();
+
+        Ok(Self { exchange, instruments, instrument_pairs, subscribed: HashSet::new(), pairs: Vec::new() })
+    }
+
+    /// adds the pair if it is one of the exchange's instruments, returning
+    /// false if it isn't
+    pub(crate) fn add_instrument_pair(&mut self, pair: RawTradingPair) -> bool {
+        let Some(native) = native_pair(self.exchange, &pair) else { return false };
+        if !self.instrument_pairs.contains(&native) {
+            return false
+        }
+
+        if self.subscribed.insert(native) {
+            self.pairs.push(pair);
+        }
+        true
+    }
+
+    /// adds the pairs of every instrument that matches the filter, skipping
+    /// the ones the exchange can't denormalize
+    pub(crate) fn add_matching<F>(&mut self, filter: &F)
+    where
+        F: ExchangeFilter<NormalizedInstrument>
+    {
+        self.instruments
+            .iter()
+            .filter(|instrument| filter.matches(instrument))
+            .for_each(|instrument| {
+                let (exchange, pair) = (self.exchange, RawTradingPair::from(instrument.trading_pair.clone()));
+                match native_pair(exchange, &pair) {
+                    Some(native) => {
+                        if self.subscribed.insert(native) {
+                            self.pairs.push(pair);
+                        }
+                    }
+                    None => warn!(target: "cex-exchanges::live-stream", ?exchange, "skipping the invalid pair {:?}", instrument.trading_pair),
+                }
+            });
+    }
+
+    pub(crate) fn into_pairs(self) -> Vec<RawTradingPair> {
+        self.pairs
+    }
+}
+
+/// the exchange's symbol of the pair, `None` if it can't be denormalized
+fn native_pair(exchange: CexExchange, pair: &RawTradingPair) -> Option<String> {
+    exchange
+        .denormalize_raw_trading_pair(pair.get_normalized_pair(exchange))
+        .ok()
+}
@@ -11,14 +11,18 @@ use serde::Deserialize;
 use strum::IntoEnumIterator;
 use tokio::net::TcpStream;
 use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::error;
 
 use self::{
     rest_api::{OkexAllInstruments, OkexAllSymbols, OkexRestApiResponse},
     ws::{OkexSubscription, OkexWsMessage},
 };
-use super::traits::SpecificWsSubscription;
+use super::{traits::SpecificWsSubscription, ExchangeEnvironment};
 use crate::{
-    clients::{rest_api::RestApiError, ws::WsError},
+    clients::{
+        rest_api::{ExchangeApi, RestApiError},
+        ws::WsError,
+    },
     normalized::{
         rest_api::NormalizedRestApiRequest,
         types::{NormalizedTradingPair, NormalizedTradingType},
@@ -30,6 +34,10 @@ use crate::{
 const WSS_PUBLIC_URL: &str = "wss://ws.okx.com:8443/ws/v5/public";
 const WSS_BUSINESS_URL: &str = "wss://ws.okx.com:8443/ws/v5/business";
 const BASE_REST_API_URL: &str = "https://www.okx.com";
+const DEMO_WSS_PUBLIC_URL: &str = "wss://wspap.okx.com:8443/ws/v5/public";
+const DEMO_WSS_BUSINESS_URL: &str = "wss://wspap.okx.com:8443/ws/v5/business";
+/// demo trading uses the production rest api with this header set to `1`
+const DEMO_TRADING_HEADER: &str = "x-simulated-trading";
 
 #[derive(Debug, Clone)]
 pub struct Okex {
@@ -37,11 +45,12 @@ pub struct Okex {
     /// exchange to use to get the symbols (since there is no direct symbols
     /// api) - default is binance
     exch_currency_proxy: CexExchange,
+    environment:         ExchangeEnvironment,
 }
 
 impl Okex {
     pub fn new_ws_subscription(subscription: OkexSubscription, exch_currency_proxy: CexExchange) -> Self {
-        Self { subscription, exch_currency_proxy }
+        Self { subscription, exch_currency_proxy, environment: ExchangeEnvironment::Production }
     }
 
     pub fn get_all_symbols<'a>(
@@ -49,9 +58,11 @@ impl Okex {
         web_client: &'a reqwest::Client,
     ) -> Pin<Box<dyn Future<Output = Result<OkexAllSymbols, RestApiError>> + Send + 'a>> {
         Box::pin(async {
+            let exchange_api = ExchangeApi::new().with_environment(self.environment.clone());
+
             let proxy_symbols = self
                 .exch_currency_proxy
-                .get_all_currencies::<EmptyFilter>(None)
+                .get_all_currencies_with_api::<EmptyFilter>(&exchange_api, None)
                 .await?;
             let instruments = self.get_all_instruments(web_client).await?;
 
@@ -62,8 +73,8 @@ impl Okex {
     pub async fn get_all_instruments(&self, web_client: &reqwest::Client) -> Result<OkexAllInstruments, RestApiError> {
         let complete_instruments = join_all(NormalizedTradingType::iter().map(|t| async move {
             if t != NormalizedTradingType::Rfq {
-                let instruments_with_type: OkexAllInstruments =
-                    Self::simple_rest_api_request(web_client, format!("{BASE_REST_API_URL}/api/v5/public/instruments?instType={t}")).await?;
+                let url = format!("{}/api/v5/public/instruments?instType={t}", self.endpoint_url(BASE_REST_API_URL, Some(BASE_REST_API_URL))?);
+                let instruments_with_type: OkexAllInstruments = self.simple_rest_api_request(web_client, url).await?;
 
                 Ok(instruments_with_type)
             } else {
@@ -80,11 +91,16 @@ impl Okex {
         Ok(OkexAllInstruments { instruments: complete_instruments })
     }
 
-    pub async fn simple_rest_api_request<T>(web_client: &reqwest::Client, url: String) -> Result<T, RestApiError>
+    pub async fn simple_rest_api_request<T>(&self, web_client: &reqwest::Client, url: String) -> Result<T, RestApiError>
     where
         T: for<'de> Deserialize<'de>,
     {
-        let data = web_client.get(&url).send().await?.json().await?;
+        let mut builder = web_client.get(&url);
+        if self.environment == ExchangeEnvironment::Testnet {
+            builder = builder.header(DEMO_TRADING_HEADER, "1");
+        }
+
+        let data = builder.send().await?.json().await?;
 
         Ok(data)
     }
@@ -98,12 +114,30 @@ impl Exchange for Okex {
     const STREAM_TIMEOUT_MS: Option<u64> = None;
 
     fn remove_bad_pair(&mut self, bad_pair: NormalizedTradingPair) -> bool {
-        let pair: OkexTradingPair = bad_pair.try_into().unwrap();
-        self.subscription.remove_pair(&pair)
+        match OkexTradingPair::try_from(bad_pair) {
+            Ok(pair) => self.subscription.remove_pair(&pair),
+            Err(e) => {
+                error!(target: "cex-exchanges::okex", "could not convert the bad pair, keeping the subscription: {:?}", e);
+                false
+            }
+        }
+    }
+
+    fn with_environment(mut self, environment: ExchangeEnvironment) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    fn environment(&self) -> &ExchangeEnvironment {
+        &self.environment
     }
 
     async fn make_ws_connection(&self) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WsError> {
-        let url = if self.subscription.needs_business_ws() { WSS_BUSINESS_URL } else { WSS_PUBLIC_URL };
+        let url = if self.subscription.needs_business_ws() {
+            self.endpoint_url(WSS_BUSINESS_URL, Some(DEMO_WSS_BUSINESS_URL))?
+        } else {
+            self.endpoint_url(WSS_PUBLIC_URL, Some(DEMO_WSS_PUBLIC_URL))?
+        };
 
         let (mut ws, _) = tokio_tungstenite::connect_async(url).await?;
 
@@ -131,6 +165,7 @@ impl Default for Okex {
             exch_currency_proxy: CexExchange::Binance,
             #[cfg(not(feature = "non-us"))]
             exch_currency_proxy: CexExchange::Coinbase,
+            environment: ExchangeEnvironment::Production,
         }
     }
 }
@@ -18,7 +18,9 @@ impl OkexTradingPair {
 
     pub fn normalize(&self) -> NormalizedTradingPair {
         let mut split = self.0.split('-');
-        let (base, quote) = (split.next().unwrap(), split.next().unwrap());
+        let (Some(base), Some(quote)) = (split.next(), split.next()) else {
+            return NormalizedTradingPair::new_no_base_quote(CexExchange::Okex, &self.0)
+        };
 
         let extra_data = split.collect::<Vec<_>>();
         let ed = if !extra_data.is_empty() { Some(extra_data.join("-")) } else { None };
@@ -64,18 +66,9 @@ impl TryFrom<NormalizedTradingPair> for OkexTradingPair {
 
     fn try_from(value: NormalizedTradingPair) -> Result<Self, Self::Error> {
         if let Some((base, quote)) = value.base_quote() {
-            if value.pair().is_none() || format!("{base}-{quote}").len() == value.pair().as_ref().unwrap().len() {
-                if let Ok(this) = Self::new_checked(&format!("{base}-{quote}")) {
-                    return Ok(this)
-                }
-            } else if value.delimiter().is_none() {
-                let mut temp = value.pair().clone().unwrap();
-                temp = temp.replace(&format!("{base}{quote}"), "");
-
-                let new = if !temp.is_empty() { format!("{base}-{quote}-{temp}") } else { format!("{base}-{quote}") };
-                if let Ok(this) = Self::new_checked(&new) {
-                    return Ok(this)
-                }
+            let new = if let Some(ed) = value.extra_data() { format!("{base}-{quote}-{ed}") } else { format!("{base}-{quote}") };
+            if let Ok(this) = Self::new_checked(&new) {
+                return Ok(this)
             }
         }
 
@@ -86,19 +79,20 @@ impl TryFrom<NormalizedTradingPair> for OkexTradingPair {
 
             if let Some(d) = delim {
                 let mut split = raw_pair.split(d);
-                let (base, quote) = (split.next().unwrap().to_uppercase(), split.next().unwrap().to_uppercase());
-
-                let extra_data = split.collect::<Vec<_>>();
-
-                let attempt_pair = if !extra_data.is_empty() {
-                    let ed = extra_data.join("-");
-                    OkexTradingPair::new_checked(&format!("{base}-{quote}-{ed}"))
-                } else {
-                    OkexTradingPair::new_checked(&format!("{base}-{quote}"))
-                };
-
-                if let Ok(v) = attempt_pair {
-                    return Ok(v)
+                if let (Some(base), Some(quote)) = (split.next(), split.next()) {
+                    let (base, quote) = (base.to_uppercase(), quote.to_uppercase());
+                    let extra_data = split.collect::<Vec<_>>();
+
+                    let attempt_pair = if !extra_data.is_empty() {
+                        let ed = extra_data.join("-");
+                        OkexTradingPair::new_checked(&format!("{base}-{quote}-{ed}"))
+                    } else {
+                        OkexTradingPair::new_checked(&format!("{base}-{quote}"))
+                    };
+
+                    if let Ok(v) = attempt_pair {
+                        return Ok(v)
+                    }
                 }
             }
 
@@ -3,11 +3,11 @@ use super::{
     OkexSubscription,
 };
 use crate::{
-    clients::ws::MultiWsStreamBuilder,
+    clients::{rest_api::ExchangeApi, ws::MultiWsStreamBuilder},
     normalized::{types::InstrumentFilter, ws::NormalizedWsChannels},
     okex::Okex,
     traits::{SpecificWsBuilder, SpecificWsSubscription},
-    CexExchange,
+    CexExchange, ExchangeEnvironment,
 };
 
 #[derive(Debug, Clone)]
@@ -27,12 +27,13 @@ impl OkexWsBuilder {
         channels: &[OkexWsChannelKind],
         streams_per_connection: Option<usize>,
         proxy: Option<CexExchange>,
+        environment: &ExchangeEnvironment,
     ) -> eyre::Result<Self> {
         let mut this = Self::new(proxy);
 
         let all_symbols = this
             .exch_currency_proxy
-            .get_all_instruments(Some(InstrumentFilter::Active))
+            .get_all_instruments_with_api(&ExchangeApi::new().with_environment(environment.clone()), Some(InstrumentFilter::Active))
             .await?;
 
         let rest = all_symbols
@@ -118,12 +119,13 @@ impl SpecificWsBuilder for OkexWsBuilder {
         Ok(MultiWsStreamBuilder::new(split_exchange))
     }
 
-    async fn build_from_all_instruments<'a>(
+    async fn build_from_all_instruments_with_environment<'a>(
         channels: &'a [<Self::WsChannel as crate::traits::SpecificWsChannel>::ChannelKind],
         streams_per_connection: Option<usize>,
         exch_currency_proxy: Option<CexExchange>,
+        environment: ExchangeEnvironment,
     ) -> eyre::Result<MultiWsStreamBuilder<Self::CexExchange>> {
-        let this = Self::build_from_all_instruments_util(channels, streams_per_connection, exch_currency_proxy).await?;
+        let this = Self::build_from_all_instruments_util(channels, streams_per_connection, exch_currency_proxy, &environment).await?;
 
         let all_streams = this
             .channels
@@ -136,7 +138,7 @@ impl SpecificWsBuilder for OkexWsBuilder {
             })
             .collect::<Vec<_>>();
 
-        Ok(MultiWsStreamBuilder::new(all_streams))
+        Ok(MultiWsStreamBuilder::new(all_streams).with_environment(environment))
     }
 
     fn make_from_normalized_map(map: Vec<NormalizedWsChannels>, exch_currency_proxy: Option<CexExchange>) -> eyre::Result<Self>
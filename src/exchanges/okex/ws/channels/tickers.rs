@@ -1,5 +1,3 @@
-use std::f64::EPSILON;
-
 use chrono::DateTime;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DefaultOnError, DisplayFromStr};
@@ -7,7 +5,7 @@ use tracing::warn;
 
 use crate::{
     exchanges::{normalized::types::NormalizedQuote, okex::pairs::OkexTradingPair},
-    normalized::types::TimeOrUpdateId,
+    normalized::types::{Number, TimeOrUpdateId},
     CexExchange
 };
 
@@ -21,50 +19,50 @@ pub struct OkexTicker {
     pub pair:              OkexTradingPair,
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "last")]
-    pub last_price:        f64,
+    pub last_price:        Number,
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "lastSz")]
-    pub last_size:         f64,
-    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub last_size:         Number,
+    #[serde_as(as = "DefaultOnError<Option<DisplayFromStr>>")]
     #[serde(rename = "askPx")]
-    pub ask_price:         Option<f64>,
-    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub ask_price:         Option<Number>,
+    #[serde_as(as = "DefaultOnError<Option<DisplayFromStr>>")]
     #[serde(rename = "askSz")]
-    pub ask_amt:           Option<f64>,
-    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub ask_amt:           Option<Number>,
+    #[serde_as(as = "DefaultOnError<Option<DisplayFromStr>>")]
     #[serde(rename = "bidPx")]
-    pub bid_price:         Option<f64>,
-    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub bid_price:         Option<Number>,
+    #[serde_as(as = "DefaultOnError<Option<DisplayFromStr>>")]
     #[serde(rename = "bidSz")]
-    pub bid_amt:           Option<f64>,
+    pub bid_amt:           Option<Number>,
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "open24h")]
-    pub open_price_24hr:   f64,
+    pub open_price_24hr:   Number,
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "high24h")]
-    pub high_price_24h:    f64,
+    pub high_price_24h:    Number,
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "low24h")]
-    pub low_price_24h:     f64,
+    pub low_price_24h:     Number,
     /// 24h trading volume, with a unit of currency.
     /// If it is a derivatives contract, the value is the number of base
     /// currency. If it is SPOT/MARGIN, the value is the quantity in quote
     /// currency.
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "volCcy24h")]
-    pub vol_currency_24hr: f64,
+    pub vol_currency_24hr: Number,
     /// 24h trading volume, with a unit of contract.
     /// If it is a derivatives contract, the value is the number of contracts.
     /// If it is SPOT/MARGIN, the value is the quantity in base currency.
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "vol24h")]
-    pub vol_contract_24hr: f64,
+    pub vol_contract_24hr: Number,
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "sodUtc0")]
-    pub open_price_utc0:   f64,
+    pub open_price_utc0:   Number,
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "sodUtc8")]
-    pub open_price_utc8:   f64,
+    pub open_price_utc8:   Number,
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "ts")]
     pub timestamp:         u64
@@ -80,7 +78,7 @@ impl OkexTicker {
                 ask_price,
                 bid_amount,
                 bid_price,
-                orderbook_ids_time: TimeOrUpdateId::new().with_time(DateTime::from_timestamp_millis(self.timestamp as i64).unwrap())
+                orderbook_ids_time: TimeOrUpdateId::new().with_time(DateTime::from_timestamp_millis(self.timestamp as i64).unwrap_or_default())
             })
         } else {
             None
@@ -92,11 +90,11 @@ impl PartialEq<NormalizedQuote> for OkexTicker {
     fn eq(&self, other: &NormalizedQuote) -> bool {
         let equals = other.exchange == CexExchange::Okex
             && other.pair == self.pair.normalize()
-            && (other.bid_amount - self.bid_amt.unwrap_or_default()).abs() < EPSILON
-            && (other.bid_price - self.bid_price.unwrap_or_default()).abs() < EPSILON
-            && (other.ask_amount - self.ask_amt.unwrap_or_default()).abs() < EPSILON
-            && (other.ask_price - self.ask_price.unwrap_or_default()).abs() < EPSILON
-            && other.orderbook_ids_time == TimeOrUpdateId::new().with_time(DateTime::from_timestamp_millis(self.timestamp as i64).unwrap());
+            && other.bid_amount == self.bid_amt.unwrap_or_default()
+            && other.bid_price == self.bid_price.unwrap_or_default()
+            && other.ask_amount == self.ask_amt.unwrap_or_default()
+            && other.ask_price == self.ask_price.unwrap_or_default()
+            && other.orderbook_ids_time == TimeOrUpdateId::new().with_time(DateTime::from_timestamp_millis(self.timestamp as i64).unwrap_or_default());
 
         if !equals {
             warn!(target: "cex-exchanges::okex", "okex ticker: {:?}", self);
@@ -4,7 +4,7 @@ use serde_with::{serde_as, DisplayFromStr};
 use tracing::warn;
 
 use crate::{
-    exchanges::{normalized::types::NormalizedTrade, okex::pairs::OkexTradingPair},
+    exchanges::{normalized::types::{NormalizedTrade, Number, TradeSide}, okex::pairs::OkexTradingPair},
     CexExchange
 };
 
@@ -15,10 +15,10 @@ pub struct OkexTrade {
     pub pair:       OkexTradingPair,
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "px")]
-    pub price:      f64,
+    pub price:      Number,
     #[serde_as(as = "DisplayFromStr")]
     #[serde(rename = "sz")]
-    pub quantity:   f64,
+    pub quantity:   Number,
     #[serde(rename = "tradeId")]
     pub trade_id:   String,
     pub side:       String,
@@ -32,8 +32,9 @@ impl OkexTrade {
         NormalizedTrade {
             exchange: CexExchange::Okex,
             pair:     self.pair.normalize(),
-            time:     DateTime::from_timestamp_millis(self.trade_time as i64).unwrap(),
-            side:     self.side.to_lowercase(),
+            time:     DateTime::from_timestamp_millis(self.trade_time as i64).unwrap_or_default(),
+            side:     TradeSide::parse(&self.side),
+            raw_side: self.side,
             price:    self.price,
             amount:   self.quantity,
             trade_id: Some(self.trade_id.to_string())
@@ -45,11 +46,12 @@ impl PartialEq<NormalizedTrade> for OkexTrade {
     fn eq(&self, other: &NormalizedTrade) -> bool {
         let equals = other.exchange == CexExchange::Okex
             && other.pair == self.pair.normalize()
-            && other.time == DateTime::from_timestamp_millis(self.trade_time as i64).unwrap()
-            && other.side == self.side.to_lowercase()
+            && other.time == DateTime::from_timestamp_millis(self.trade_time as i64).unwrap_or_default()
+            && other.side == TradeSide::parse(&self.side)
+            && other.raw_side == self.side
             && other.price == self.price
             && other.amount == self.quantity
-            && other.trade_id.as_ref().unwrap() == &self.trade_id.to_string();
+            && other.trade_id == Some(self.trade_id.to_string());
 
         if !equals {
             warn!(target: "cex-exchanges::okex", "okex trade: {:?}", self);
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -26,10 +27,18 @@ impl OkexWsMessage {
                 .ok_or(eyre::ErrReport::msg("Could not convert 'channel' field in Okex ws message to &str".to_string()))?;
             if channel == "trades-all" {
                 let data: Vec<OkexTrade> = serde_json::from_value(data.clone())?;
-                Ok(Self::TradesAll(data.first().unwrap().clone()))
+                let first = data
+                    .into_iter()
+                    .next()
+                    .ok_or(eyre::ErrReport::msg(format!("Empty 'data' field in Okex '{channel}' ws message")))?;
+                Ok(Self::TradesAll(first))
             } else if channel == "tickers" {
                 let data: Vec<OkexTicker> = serde_json::from_value(data.clone())?;
-                Ok(Self::Tickers(data.first().unwrap().clone()))
+                let first = data
+                    .into_iter()
+                    .next()
+                    .ok_or(eyre::ErrReport::msg(format!("Empty 'data' field in Okex '{channel}' ws message")))?;
+                Ok(Self::Tickers(first))
             } else {
                 Err(eyre::ErrReport::msg(format!("Channel type '{channel}' cannot be deserialized")))
             }
@@ -89,6 +98,18 @@ impl OkexWsMessage {
             }
         }
     }
+
+    /// the exchange's timestamp of the message w/o normalizing it, see
+    /// [NormalizedWsDataTypes::exchange_time]
+    pub fn exchange_time(&self) -> Option<DateTime<Utc>> {
+        match self {
+            OkexWsMessage::TradesAll(v) => Some(DateTime::from_timestamp_millis(v.trade_time as i64).unwrap_or_default()),
+            // tickers w/o a bid & ask aren't normalized
+            OkexWsMessage::Tickers(v) => (v.ask_amt.is_some() && v.ask_price.is_some() && v.bid_amt.is_some() && v.bid_price.is_some())
+                .then(|| DateTime::from_timestamp_millis(v.timestamp as i64).unwrap_or_default()),
+            OkexWsMessage::Subscribe(_) | OkexWsMessage::Error { .. } => None
+        }
+    }
 }
 
 impl PartialEq<NormalizedWsDataTypes> for OkexWsMessage {
@@ -24,3 +24,135 @@ where
         self.iter().any(|v| v.matches(val))
     }
 }
+
+/// retains values that match BOTH filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct And<A, B>(pub A, pub B);
+
+impl<T, A, B> ExchangeFilter<T> for And<A, B>
+where
+    A: ExchangeFilter<T>,
+    B: ExchangeFilter<T>
+{
+    fn matches(&self, val: &T) -> bool {
+        self.0.matches(val) && self.1.matches(val)
+    }
+}
+
+/// retains values that match EITHER filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Or<A, B>(pub A, pub B);
+
+impl<T, A, B> ExchangeFilter<T> for Or<A, B>
+where
+    A: ExchangeFilter<T>,
+    B: ExchangeFilter<T>
+{
+    fn matches(&self, val: &T) -> bool {
+        self.0.matches(val) || self.1.matches(val)
+    }
+}
+
+/// retains values that DON'T match the filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Not<A>(pub A);
+
+impl<T, A> ExchangeFilter<T> for Not<A>
+where
+    A: ExchangeFilter<T>
+{
+    fn matches(&self, val: &T) -> bool {
+        !self.0.matches(val)
+    }
+}
+
+/// retains values the closure returns true for, ex:
+/// `FilterFn(|instrument: &NormalizedInstrument| instrument.active)`
+#[derive(Debug, Clone, Copy)]
+pub struct FilterFn<F>(pub F);
+
+impl<T, F> ExchangeFilter<T> for FilterFn<F>
+where
+    F: Fn(&T) -> bool
+{
+    fn matches(&self, val: &T) -> bool {
+        (self.0)(val)
+    }
+}
+
+/// chains filters into [And], [Or] & [Not], ex:
+/// `InstrumentFilter::Active.and(InstrumentFilter::quote_only("USDT".to_string()).not())`
+pub trait FilterExt: Sized {
+    fn and<B>(self, other: B) -> And<Self, B> {
+        And(self, other)
+    }
+
+    fn or<B>(self, other: B) -> Or<Self, B> {
+        Or(self, other)
+    }
+
+    fn not(self) -> Not<Self> {
+        Not(self)
+    }
+}
+
+impl FilterExt for EmptyFilter {}
+
+impl<D> FilterExt for Vec<D> {}
+
+impl<A, B> FilterExt for And<A, B> {}
+
+impl<A, B> FilterExt for Or<A, B> {}
+
+impl<A> FilterExt for Not<A> {}
+
+impl<F> FilterExt for FilterFn<F> {}
+
+#[cfg(test)]
+#[cfg(feature = "us")]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::{
+        normalized::types::{InstrumentFilter, NormalizedInstrument, NormalizedTradingPair, NormalizedTradingType},
+        CexExchange
+    };
+
+    fn instrument(base: &str, quote: &str, trading_type: NormalizedTradingType, futures_expiry: Option<NaiveDate>) -> NormalizedInstrument {
+        NormalizedInstrument {
+            exchange: CexExchange::Okex,
+            trading_pair: NormalizedTradingPair::new_base_quote(CexExchange::Okex, base, quote, Some('-'), None),
+            trading_type,
+            base_asset_symbol: base.to_string(),
+            quote_asset_symbol: quote.to_string(),
+            active: true,
+            futures_expiry
+        }
+    }
+
+    #[test]
+    fn test_filter_combinators() {
+        let expiry = NaiveDate::from_ymd_opt(2024, 3, 29);
+        let mut instruments = vec![
+            instrument("BTC", "USDT", NormalizedTradingType::Spot, None),
+            instrument("ETH", "USDC", NormalizedTradingType::Spot, None),
+            instrument("ETH", "BTC", NormalizedTradingType::Spot, None),
+            instrument("BTC", "USD", NormalizedTradingType::Futures, expiry),
+            instrument("BTC", "USD", NormalizedTradingType::Futures, NaiveDate::from_ymd_opt(2024, 6, 28)),
+        ];
+
+        let spot_stables = InstrumentFilter::trading_type(NormalizedTradingType::Spot)
+            .and(InstrumentFilter::quote_in(vec!["USDT".to_string(), "USDC".to_string()]));
+        assert_eq!(instruments.iter().filter(|i| spot_stables.matches(*i)).count(), 2);
+
+        let march_futures = InstrumentFilter::futures_expiry(None, expiry);
+        let not_eth = InstrumentFilter::base_only("ETH".to_string()).not();
+        let filter = march_futures.or(not_eth.and(FilterFn(|i: &NormalizedInstrument| i.quote_asset_symbol.starts_with("USDT"))));
+        filter.filter_matches(&mut instruments);
+
+        assert_eq!(instruments.len(), 2);
+        assert_eq!(instruments[0].quote_asset_symbol, "USDT");
+        assert_eq!(instruments[1].futures_expiry, expiry);
+    }
+}
@@ -1,7 +1,7 @@
 use std::future::Future;
 
 use super::SpecificWsChannel;
-use crate::{clients::ws::MultiWsStreamBuilder, normalized::ws::NormalizedWsChannels, CexExchange, Exchange};
+use crate::{clients::ws::MultiWsStreamBuilder, normalized::ws::NormalizedWsChannels, CexExchange, Exchange, ExchangeEnvironment};
 
 pub trait SpecificWsBuilder {
     /// maximum number of connections per IP
@@ -44,6 +44,17 @@ pub trait SpecificWsBuilder {
         channels: &'a [<Self::WsChannel as SpecificWsChannel>::ChannelKind],
         streams_per_connection: Option<usize>,
         exch_currency_proxy: Option<CexExchange>,
+    ) -> impl Future<Output = eyre::Result<MultiWsStreamBuilder<Self::CexExchange>>> + 'a {
+        Self::build_from_all_instruments_with_environment(channels, streams_per_connection, exch_currency_proxy, ExchangeEnvironment::Production)
+    }
+
+    /// [Self::build_from_all_instruments], getting the instruments from &
+    /// connecting the streams to `environment`
+    fn build_from_all_instruments_with_environment<'a>(
+        channels: &'a [<Self::WsChannel as SpecificWsChannel>::ChannelKind],
+        streams_per_connection: Option<usize>,
+        exch_currency_proxy: Option<CexExchange>,
+        environment: ExchangeEnvironment,
     ) -> impl Future<Output = eyre::Result<MultiWsStreamBuilder<Self::CexExchange>>> + 'a;
 
     /// makes the builder from the normalized builder's map
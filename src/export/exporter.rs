@@ -0,0 +1,361 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf}
+};
+
+use chrono::Utc;
+use futures::{Stream, StreamExt};
+use tracing::{debug, error};
+
+#[cfg(feature = "parquet")]
+use super::Parquet;
+use super::{Csv, ExportError, ExportFormat, ExportPartition, ExportRow, L2LevelRow, QuoteRow, TradeRow};
+use crate::normalized::{
+    types::{NormalizedL2, NormalizedQuote, NormalizedTrade},
+    ws::NormalizedWsDataTypes
+};
+
+pub type CsvExporter = NormalizedExporter<Csv>;
+
+#[cfg(feature = "parquet")]
+pub type ParquetExporter = NormalizedExporter<Parquet>;
+
+/// writes normalized trades, quotes & l2 levels to partitioned files under
+/// `root`, see [ExportPartition]
+///
+/// rows are buffered per partition and written every `batch_size` rows, the
+/// remaining rows are written on [NormalizedExporter::close] (or drop). once
+/// an exchange's pair rolls over to a new date, the files of its older dates
+/// are closed & late rows for them are written to a new file
+pub struct NormalizedExporter<F: ExportFormat> {
+    root:       PathBuf,
+    batch_size: usize,
+    /// file name of this exporter's part in every partition
+    file_name:  String,
+    trades:     HashMap<ExportPartition, PartitionBuffer<TradeRow, F>>,
+    quotes:     HashMap<ExportPartition, PartitionBuffer<QuoteRow, F>>,
+    l2:         HashMap<ExportPartition, PartitionBuffer<L2LevelRow, F>>
+}
+
+struct PartitionBuffer<R: ExportRow, F: ExportFormat> {
+    rows:   Vec<R>,
+    writer: Option<F::Writer<R>>
+}
+
+impl<F: ExportFormat> NormalizedExporter<F> {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root:       root.into(),
+            batch_size: 10_000,
+            file_name:  format!("part-{}.{}", Utc::now().format("%Y%m%dT%H%M%S%.6fZ"), F::EXTENSION),
+            trades:     HashMap::new(),
+            quotes:     HashMap::new(),
+            l2:         HashMap::new()
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// buffers the trades, quotes & l2 updates, all other messages are ignored
+    pub fn write(&mut self, data: NormalizedWsDataTypes) -> Result<(), ExportError> {
+        match data {
+            NormalizedWsDataTypes::Trade(trade) => self.write_trade(trade),
+            NormalizedWsDataTypes::Trades(trades) => trades.into_iter().try_for_each(|t| self.write_trade(t)),
+            NormalizedWsDataTypes::Quote(quote) => self.write_quote(quote),
+            NormalizedWsDataTypes::Quotes(quotes) => quotes.into_iter().try_for_each(|q| self.write_quote(q)),
+            NormalizedWsDataTypes::L2(l2) => self.write_l2(l2),
+            _ => Ok(())
+        }
+    }
+
+    pub fn write_trade(&mut self, trade: NormalizedTrade) -> Result<(), ExportError> {
+        Self::buffer_row(&mut self.trades, &self.root, &self.file_name, self.batch_size, TradeRow::from(trade))
+    }
+
+    pub fn write_quote(&mut self, quote: NormalizedQuote) -> Result<(), ExportError> {
+        Self::buffer_row(&mut self.quotes, &self.root, &self.file_name, self.batch_size, QuoteRow::from(quote))
+    }
+
+    pub fn write_l2(&mut self, l2: NormalizedL2) -> Result<(), ExportError> {
+        L2LevelRow::from_l2(l2)
+            .into_iter()
+            .try_for_each(|row| Self::buffer_row(&mut self.l2, &self.root, &self.file_name, self.batch_size, row))
+    }
+
+    /// writes every row in the stream, closing the files once the stream ends
+    pub async fn write_stream<S>(mut self, mut stream: S) -> Result<(), ExportError>
+    where
+        S: Stream<Item = NormalizedWsDataTypes> + Unpin
+    {
+        while let Some(data) = stream.next().await {
+            self.write(data)?;
+        }
+
+        self.close()
+    }
+
+    /// writes all buffered rows
+    pub fn flush(&mut self) -> Result<(), ExportError> {
+        Self::flush_partitions(&mut self.trades, &self.root, &self.file_name)?;
+        Self::flush_partitions(&mut self.quotes, &self.root, &self.file_name)?;
+        Self::flush_partitions(&mut self.l2, &self.root, &self.file_name)
+    }
+
+    /// writes all buffered rows and closes the files
+    pub fn close(mut self) -> Result<(), ExportError> {
+        self.close_all()
+    }
+
+    fn close_all(&mut self) -> Result<(), ExportError> {
+        self.flush()?;
+
+        self.trades
+            .drain()
+            .filter_map(|(_, buf)| buf.writer)
+            .try_for_each(F::close)?;
+        self.quotes
+            .drain()
+            .filter_map(|(_, buf)| buf.writer)
+            .try_for_each(F::close)?;
+        self.l2
+            .drain()
+            .filter_map(|(_, buf)| buf.writer)
+            .try_for_each(F::close)
+    }
+
+    fn buffer_row<R: ExportRow>(
+        partitions: &mut HashMap<ExportPartition, PartitionBuffer<R, F>>,
+        root: &Path,
+        file_name: &str,
+        batch_size: usize,
+        row: R
+    ) -> Result<(), ExportError> {
+        let partition = row.partition();
+        if !partitions.contains_key(&partition) {
+            Self::close_older_partitions(partitions, &partition, root, file_name)?;
+        }
+
+        let buf = partitions
+            .entry(partition.clone())
+            .or_insert_with(|| PartitionBuffer { rows: Vec::new(), writer: None });
+
+        buf.rows.push(row);
+        if buf.rows.len() >= batch_size {
+            Self::flush_partition(&partition, buf, root, file_name)?;
+        }
+
+        Ok(())
+    }
+
+    /// closes the partitions of the exchange's pair w/ a date before the
+    /// partition's
+    fn close_older_partitions<R: ExportRow>(
+        partitions: &mut HashMap<ExportPartition, PartitionBuffer<R, F>>,
+        partition: &ExportPartition,
+        root: &Path,
+        file_name: &str
+    ) -> Result<(), ExportError> {
+        let older = partitions
+            .keys()
+            .filter(|p| p.exchange == partition.exchange && p.pair == partition.pair && p.date < partition.date)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        older.into_iter().try_for_each(|older| {
+            let mut buf = partitions.remove(&older).unwrap();
+            Self::flush_partition(&older, &mut buf, root, file_name)?;
+            debug!(target: "cex-exchanges::export", "closing {} of {}", R::KIND, older.dir(root, R::KIND).display());
+
+            buf.writer.map_or(Ok(()), F::close)
+        })
+    }
+
+    fn flush_partitions<R: ExportRow>(
+        partitions: &mut HashMap<ExportPartition, PartitionBuffer<R, F>>,
+        root: &Path,
+        file_name: &str
+    ) -> Result<(), ExportError> {
+        partitions
+            .iter_mut()
+            .try_for_each(|(partition, buf)| Self::flush_partition(partition, buf, root, file_name))
+    }
+
+    fn flush_partition<R: ExportRow>(
+        partition: &ExportPartition,
+        buf: &mut PartitionBuffer<R, F>,
+        root: &Path,
+        file_name: &str
+    ) -> Result<(), ExportError> {
+        if buf.rows.is_empty() {
+            return Ok(())
+        }
+
+        let writer = match buf.writer.as_mut() {
+            Some(writer) => writer,
+            None => {
+                let dir = partition.dir(root, R::KIND);
+                fs::create_dir_all(&dir)?;
+                debug!(target: "cex-exchanges::export", "writing {} to {}", R::KIND, dir.display());
+
+                buf.writer.insert(F::create::<R>(&Self::unused_path(&dir, file_name))?)
+            }
+        };
+
+        F::write(writer, &buf.rows)?;
+        buf.rows.clear();
+
+        Ok(())
+    }
+
+    /// `dir/file_name`, or `dir/<file stem>-<n>.<ext>` if the file was already
+    /// written & closed by this exporter
+    fn unused_path(dir: &Path, file_name: &str) -> PathBuf {
+        let stem = file_name.trim_end_matches(&format!(".{}", F::EXTENSION));
+
+        std::iter::once(dir.join(file_name))
+            .chain((1..).map(|n| dir.join(format!("{stem}-{n}.{}", F::EXTENSION))))
+            .find(|path| !path.exists())
+            .unwrap()
+    }
+}
+
+impl<F: ExportFormat> Drop for NormalizedExporter<F> {
+    fn drop(&mut self) {
+        if let Err(e) = self.close_all() {
+            error!(target: "cex-exchanges::export", "error closing the exporter: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "non-us")]
+mod tests {
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::{
+        normalized::types::{num, BidAsk, NormalizedTradingPair, TimeOrUpdateId, TradeSide},
+        CexExchange
+    };
+
+    fn test_data() -> Vec<NormalizedWsDataTypes> {
+        let pair = NormalizedTradingPair::new_base_quote(CexExchange::Binance, "BTC", "USDT", Some('-'), None);
+        let time = DateTime::from_timestamp_millis(1700000000000).unwrap();
+
+        vec![
+            NormalizedWsDataTypes::Trades(
+                (0..3)
+                    .map(|i| NormalizedTrade {
+                        exchange: CexExchange::Binance,
+                        pair:     pair.clone(),
+                        time,
+                        side:     TradeSide::Buy,
+                        raw_side: "false".to_string(),
+                        price:    num(100.0 + i as f64),
+                        amount:   num(1.0),
+                        trade_id: Some(i.to_string())
+                    })
+                    .collect()
+            ),
+            NormalizedWsDataTypes::L2(NormalizedL2 {
+                exchange:           CexExchange::Binance,
+                pair:               pair.clone(),
+                bids:               vec![BidAsk::new(num(99.0), num(1.0)), BidAsk::new(num(98.0), num(2.0))],
+                asks:               vec![BidAsk::new(num(101.0), num(3.0))],
+                orderbook_ids_time: TimeOrUpdateId::new().with_time(time).with_last_update_id(5)
+            }),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_csv_export() {
+        let dir = std::env::temp_dir().join(format!("cex-exchanges-csv-export-{}", std::process::id()));
+
+        CsvExporter::new(&dir)
+            .with_batch_size(2)
+            .write_stream(futures::stream::iter(test_data()))
+            .await
+            .unwrap();
+
+        let read_rows = |kind: &str| {
+            let partition = dir.join(kind).join("exchange=binance/date=2023-11-14/pair=BTC-USDT");
+            let file = fs::read_dir(partition).unwrap().next().unwrap().unwrap().path();
+            csv::Reader::from_path(file)
+                .unwrap()
+                .records()
+                .map(|r| r.unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let trades = read_rows("trades");
+        assert_eq!(trades.len(), 3);
+        assert_eq!(trades[2][4].parse::<f64>().unwrap(), 102.0);
+
+        let l2 = read_rows("l2");
+        assert_eq!(l2.len(), 3);
+        assert_eq!((&l2[2][5], &l2[2][6]), ("ask", "0"));
+        assert_eq!(l2[2][7].parse::<f64>().unwrap(), 101.0);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_close_older_partitions() {
+        let dir = std::env::temp_dir().join(format!("cex-exchanges-rollover-export-{}", std::process::id()));
+        let pair = NormalizedTradingPair::new_base_quote(CexExchange::Binance, "BTC", "USDT", Some('-'), None);
+        let trade = |millis: i64| NormalizedTrade {
+            exchange: CexExchange::Binance,
+            pair:     pair.clone(),
+            time:     DateTime::from_timestamp_millis(millis).unwrap(),
+            side:     TradeSide::Buy,
+            raw_side: "false".to_string(),
+            price:    num(100.0),
+            amount:   num(1.0),
+            trade_id: None
+        };
+
+        let mut exporter = CsvExporter::new(&dir);
+        exporter.write_trade(trade(1700000000000)).unwrap();
+        exporter.write_trade(trade(1700000000000 + 86_400_000)).unwrap();
+        assert_eq!(exporter.trades.len(), 1);
+
+        // a late row of the closed date doesn't overwrite its file
+        exporter.write_trade(trade(1700000000000)).unwrap();
+        assert_eq!(exporter.trades.len(), 2);
+        exporter.close().unwrap();
+
+        let files = fs::read_dir(dir.join("trades/exchange=binance/date=2023-11-14/pair=BTC-USDT"))
+            .unwrap()
+            .map(|file| csv::Reader::from_path(file.unwrap().path()).unwrap().records().count())
+            .collect::<Vec<_>>();
+        assert_eq!(files, vec![1, 1]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "parquet")]
+    #[tokio::test]
+    async fn test_parquet_export() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let dir = std::env::temp_dir().join(format!("cex-exchanges-parquet-export-{}", std::process::id()));
+
+        let mut exporter = ParquetExporter::new(&dir);
+        test_data()
+            .into_iter()
+            .try_for_each(|data| exporter.write(data))
+            .unwrap();
+        drop(exporter);
+
+        let partition = dir.join("trades/exchange=binance/date=2023-11-14/pair=BTC-USDT");
+        let file = fs::read_dir(partition).unwrap().next().unwrap().unwrap().path();
+        let reader = SerializedFileReader::new(fs::File::open(file).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 3);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,80 @@
+use std::{fs::File, path::Path};
+
+#[cfg(feature = "parquet")]
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, ZstdLevel},
+    file::properties::WriterProperties
+};
+
+use super::{ExportError, ExportRow};
+
+/// file format of a [NormalizedExporter](super::NormalizedExporter)
+pub trait ExportFormat {
+    const EXTENSION: &'static str;
+
+    type Writer<R: ExportRow>: Send;
+
+    fn create<R: ExportRow>(path: &Path) -> Result<Self::Writer<R>, ExportError>;
+
+    fn write<R: ExportRow>(writer: &mut Self::Writer<R>, rows: &[R]) -> Result<(), ExportError>;
+
+    fn close<R: ExportRow>(writer: Self::Writer<R>) -> Result<(), ExportError>;
+}
+
+/// csv with a header row, one file per partition
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Csv;
+
+impl ExportFormat for Csv {
+    type Writer<R: ExportRow> = csv::Writer<File>;
+
+    const EXTENSION: &'static str = "csv";
+
+    fn create<R: ExportRow>(path: &Path) -> Result<Self::Writer<R>, ExportError> {
+        Ok(csv::Writer::from_path(path)?)
+    }
+
+    fn write<R: ExportRow>(writer: &mut Self::Writer<R>, rows: &[R]) -> Result<(), ExportError> {
+        rows.iter().try_for_each(|row| writer.serialize(row))?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn close<R: ExportRow>(mut writer: Self::Writer<R>) -> Result<(), ExportError> {
+        Ok(writer.flush()?)
+    }
+}
+
+/// zstd compressed parquet, each flush is written as a row group
+#[cfg(feature = "parquet")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Parquet;
+
+#[cfg(feature = "parquet")]
+impl ExportFormat for Parquet {
+    type Writer<R: ExportRow> = ArrowWriter<File>;
+
+    const EXTENSION: &'static str = "parquet";
+
+    fn create<R: ExportRow>(path: &Path) -> Result<Self::Writer<R>, ExportError> {
+        let props = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+
+        Ok(ArrowWriter::try_new(File::create(path)?, R::schema(), Some(props))?)
+    }
+
+    fn write<R: ExportRow>(writer: &mut Self::Writer<R>, rows: &[R]) -> Result<(), ExportError> {
+        writer.write(&R::record_batch(rows)?)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn close<R: ExportRow>(writer: Self::Writer<R>) -> Result<(), ExportError> {
+        writer.close()?;
+        Ok(())
+    }
+}
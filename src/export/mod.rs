@@ -0,0 +1,11 @@
+mod errors;
+pub use errors::*;
+
+mod rows;
+pub use rows::*;
+
+mod formats;
+pub use formats::*;
+
+mod exporter;
+pub use exporter::*;
@@ -0,0 +1,372 @@
+#[cfg(feature = "parquet")]
+use std::sync::Arc;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "parquet")]
+use arrow_array::RecordBatch;
+#[cfg(feature = "parquet")]
+use arrow_schema::{ArrowError, SchemaRef};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::normalized::types::{NormalizedL2, NormalizedQuote, NormalizedTrade, Number};
+
+/// a flattened normalized type, written as a single row
+pub trait ExportRow: Serialize + Send + Sized + 'static {
+    /// top level directory of the rows (ex: `trades`)
+    const KIND: &'static str;
+
+    fn partition(&self) -> ExportPartition;
+
+    #[cfg(feature = "parquet")]
+    fn schema() -> SchemaRef;
+
+    #[cfg(feature = "parquet")]
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch, ArrowError>;
+}
+
+/// rows are partitioned by `<kind>/exchange=<exchange>/date=<yyyy-mm-dd>/pair=<pair>`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ExportPartition {
+    pub exchange: String,
+    pub date:     NaiveDate,
+    pub pair:     String
+}
+
+impl ExportPartition {
+    fn new(exchange: &str, time: Option<DateTime<Utc>>, pair: &str) -> Self {
+        let pair = pair
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+
+        Self { exchange: exchange.to_string(), date: time.unwrap_or_else(Utc::now).date_naive(), pair }
+    }
+
+    pub fn dir(&self, root: &Path, kind: &str) -> PathBuf {
+        root.join(kind)
+            .join(format!("exchange={}", self.exchange))
+            .join(format!("date={}", self.date.format("%Y-%m-%d")))
+            .join(format!("pair={}", self.pair))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+pub struct TradeRow {
+    pub exchange: String,
+    pub pair:     String,
+    pub time:     DateTime<Utc>,
+    pub side:     String,
+    pub price:    Number,
+    pub amount:   Number,
+    pub trade_id: Option<String>
+}
+
+impl From<NormalizedTrade> for TradeRow {
+    fn from(value: NormalizedTrade) -> Self {
+        Self {
+            exchange: value.exchange.to_string(),
+            pair:     value.pair.make_pair(),
+            time:     value.time,
+            side:     value.side.to_string(),
+            price:    value.price,
+            amount:   value.amount,
+            trade_id: value.trade_id
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+pub struct QuoteRow {
+    pub exchange:        String,
+    pub pair:            String,
+    pub time:            Option<DateTime<Utc>>,
+    pub first_update_id: Option<u64>,
+    pub last_update_id:  Option<u64>,
+    pub bid_price:       Number,
+    pub bid_amount:      Number,
+    pub ask_price:       Number,
+    pub ask_amount:      Number
+}
+
+impl From<NormalizedQuote> for QuoteRow {
+    fn from(value: NormalizedQuote) -> Self {
+        Self {
+            exchange:        value.exchange.to_string(),
+            pair:            value.pair.make_pair(),
+            time:            value.orderbook_ids_time.time,
+            first_update_id: value.orderbook_ids_time.first_update_id,
+            last_update_id:  value.orderbook_ids_time.last_update_id,
+            bid_price:       value.bid_price,
+            bid_amount:      value.bid_amount,
+            ask_price:       value.ask_price,
+            ask_amount:      value.ask_amount
+        }
+    }
+}
+
+/// a single price level of a [NormalizedL2]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+pub struct L2LevelRow {
+    pub exchange:        String,
+    pub pair:            String,
+    pub time:            Option<DateTime<Utc>>,
+    pub first_update_id: Option<u64>,
+    pub last_update_id:  Option<u64>,
+    /// 'bid' or 'ask'
+    pub side:            String,
+    /// index of the level in the update
+    pub level:           u32,
+    pub price:           Number,
+    pub amount:          Number
+}
+
+impl L2LevelRow {
+    /// flattens the bids & asks of the update into one row per level
+    pub fn from_l2(value: NormalizedL2) -> Vec<Self> {
+        let exchange = value.exchange.to_string();
+        let pair = value.pair.make_pair();
+        let ids_time = value.orderbook_ids_time;
+
+        let bids = value.bids.into_iter().map(|l| ("bid", l));
+        let asks = value.asks.into_iter().map(|l| ("ask", l));
+
+        bids.enumerate()
+            .chain(asks.enumerate())
+            .map(|(level, (side, bid_ask))| Self {
+                exchange:        exchange.clone(),
+                pair:            pair.clone(),
+                time:            ids_time.time,
+                first_update_id: ids_time.first_update_id,
+                last_update_id:  ids_time.last_update_id,
+                side:            side.to_string(),
+                level:           level as u32,
+                price:           bid_ask.price,
+                amount:          bid_ask.amount
+            })
+            .collect()
+    }
+}
+
+impl ExportRow for TradeRow {
+    const KIND: &'static str = "trades";
+
+    fn partition(&self) -> ExportPartition {
+        ExportPartition::new(&self.exchange, Some(self.time), &self.pair)
+    }
+
+    #[cfg(feature = "parquet")]
+    fn schema() -> SchemaRef {
+        arrow::schema(vec![
+            arrow::utf8("exchange", false),
+            arrow::utf8("pair", false),
+            arrow::timestamp("time", false),
+            arrow::utf8("side", false),
+            arrow::number("price"),
+            arrow::number("amount"),
+            arrow::utf8("trade_id", true),
+        ])
+    }
+
+    #[cfg(feature = "parquet")]
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch, ArrowError> {
+        use arrow_array::{StringArray, TimestampMicrosecondArray};
+
+        RecordBatch::try_new(
+            Self::schema(),
+            vec![
+                Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.exchange))),
+                Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.pair))),
+                Arc::new(TimestampMicrosecondArray::from_iter_values(rows.iter().map(|r| r.time.timestamp_micros())).with_timezone("UTC")),
+                Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.side))),
+                arrow::numbers(rows.iter().map(|r| r.price))?,
+                arrow::numbers(rows.iter().map(|r| r.amount))?,
+                Arc::new(rows.iter().map(|r| r.trade_id.as_deref()).collect::<StringArray>()),
+            ]
+        )
+    }
+}
+
+impl ExportRow for QuoteRow {
+    const KIND: &'static str = "quotes";
+
+    fn partition(&self) -> ExportPartition {
+        ExportPartition::new(&self.exchange, self.time, &self.pair)
+    }
+
+    #[cfg(feature = "parquet")]
+    fn schema() -> SchemaRef {
+        arrow::schema(vec![
+            arrow::utf8("exchange", false),
+            arrow::utf8("pair", false),
+            arrow::timestamp("time", true),
+            arrow::uint64("first_update_id"),
+            arrow::uint64("last_update_id"),
+            arrow::number("bid_price"),
+            arrow::number("bid_amount"),
+            arrow::number("ask_price"),
+            arrow::number("ask_amount"),
+        ])
+    }
+
+    #[cfg(feature = "parquet")]
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch, ArrowError> {
+        use arrow_array::{StringArray, TimestampMicrosecondArray, UInt64Array};
+
+        RecordBatch::try_new(
+            Self::schema(),
+            vec![
+                Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.exchange))),
+                Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.pair))),
+                Arc::new(
+                    rows.iter()
+                        .map(|r| r.time.map(|t| t.timestamp_micros()))
+                        .collect::<TimestampMicrosecondArray>()
+                        .with_timezone("UTC")
+                ),
+                Arc::new(rows.iter().map(|r| r.first_update_id).collect::<UInt64Array>()),
+                Arc::new(rows.iter().map(|r| r.last_update_id).collect::<UInt64Array>()),
+                arrow::numbers(rows.iter().map(|r| r.bid_price))?,
+                arrow::numbers(rows.iter().map(|r| r.bid_amount))?,
+                arrow::numbers(rows.iter().map(|r| r.ask_price))?,
+                arrow::numbers(rows.iter().map(|r| r.ask_amount))?,
+            ]
+        )
+    }
+}
+
+impl ExportRow for L2LevelRow {
+    const KIND: &'static str = "l2";
+
+    fn partition(&self) -> ExportPartition {
+        ExportPartition::new(&self.exchange, self.time, &self.pair)
+    }
+
+    #[cfg(feature = "parquet")]
+    fn schema() -> SchemaRef {
+        arrow::schema(vec![
+            arrow::utf8("exchange", false),
+            arrow::utf8("pair", false),
+            arrow::timestamp("time", true),
+            arrow::uint64("first_update_id"),
+            arrow::uint64("last_update_id"),
+            arrow::utf8("side", false),
+            arrow_schema::Field::new("level", arrow_schema::DataType::UInt32, false),
+            arrow::number("price"),
+            arrow::number("amount"),
+        ])
+    }
+
+    #[cfg(feature = "parquet")]
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch, ArrowError> {
+        use arrow_array::{StringArray, TimestampMicrosecondArray, UInt32Array, UInt64Array};
+
+        RecordBatch::try_new(
+            Self::schema(),
+            vec![
+                Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.exchange))),
+                Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.pair))),
+                Arc::new(
+                    rows.iter()
+                        .map(|r| r.time.map(|t| t.timestamp_micros()))
+                        .collect::<TimestampMicrosecondArray>()
+                        .with_timezone("UTC")
+                ),
+                Arc::new(rows.iter().map(|r| r.first_update_id).collect::<UInt64Array>()),
+                Arc::new(rows.iter().map(|r| r.last_update_id).collect::<UInt64Array>()),
+                Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.side))),
+                Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.level))),
+                arrow::numbers(rows.iter().map(|r| r.price))?,
+                arrow::numbers(rows.iter().map(|r| r.amount))?,
+            ]
+        )
+    }
+}
+
+#[cfg(feature = "parquet")]
+mod arrow {
+    use std::sync::Arc;
+
+    use arrow_array::ArrayRef;
+    use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
+
+    use crate::normalized::types::Number;
+
+    /// decimal columns keep 18 fractional digits of every [Number], enough for
+    /// the exchanges' smallest ticks w/ 20 integer digits left
+    #[cfg(feature = "decimal")]
+    const DECIMAL_PRECISION: u8 = 38;
+    #[cfg(feature = "decimal")]
+    const DECIMAL_SCALE: i8 = 18;
+
+    pub(super) fn schema(fields: Vec<Field>) -> SchemaRef {
+        Arc::new(Schema::new(fields))
+    }
+
+    pub(super) fn utf8(name: &str, nullable: bool) -> Field {
+        Field::new(name, DataType::Utf8, nullable)
+    }
+
+    pub(super) fn timestamp(name: &str, nullable: bool) -> Field {
+        Field::new(name, DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())), nullable)
+    }
+
+    pub(super) fn uint64(name: &str) -> Field {
+        Field::new(name, DataType::UInt64, true)
+    }
+
+    /// a [Number] column, `Decimal128` w/ the `decimal` feature & `Float64`
+    /// otherwise
+    pub(super) fn number(name: &str) -> Field {
+        #[cfg(feature = "decimal")]
+        let data_type = DataType::Decimal128(DECIMAL_PRECISION, DECIMAL_SCALE);
+        #[cfg(not(feature = "decimal"))]
+        let data_type = DataType::Float64;
+
+        Field::new(name, data_type, false)
+    }
+
+    #[cfg(not(feature = "decimal"))]
+    pub(super) fn numbers(values: impl Iterator<Item = Number>) -> Result<ArrayRef, ArrowError> {
+        Ok(Arc::new(arrow_array::Float64Array::from_iter_values(values)))
+    }
+
+    #[cfg(feature = "decimal")]
+    pub(super) fn numbers(values: impl Iterator<Item = Number>) -> Result<ArrayRef, ArrowError> {
+        let values = values.map(decimal128).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Arc::new(arrow_array::Decimal128Array::from_iter_values(values).with_precision_and_scale(DECIMAL_PRECISION, DECIMAL_SCALE)?))
+    }
+
+    /// the decimal's value in units of 10^-[DECIMAL_SCALE], rounding smaller
+    /// digits
+    #[cfg(feature = "decimal")]
+    fn decimal128(value: Number) -> Result<i128, ArrowError> {
+        let scale = DECIMAL_SCALE as u32;
+        let value = if value.scale() > scale { value.round_dp(scale) } else { value };
+
+        value
+            .mantissa()
+            .checked_mul(10i128.pow(scale - value.scale()))
+            .filter(|units| units.unsigned_abs() < 10u128.pow(u32::from(DECIMAL_PRECISION)))
+            .ok_or_else(|| ArrowError::InvalidArgumentError(format!("{value} doesn't fit in a Decimal128({DECIMAL_PRECISION}, {DECIMAL_SCALE})")))
+    }
+
+    #[cfg(test)]
+    #[cfg(feature = "decimal")]
+    mod tests {
+        use std::str::FromStr;
+
+        use super::*;
+
+        #[test]
+        fn test_decimal128() {
+            let units = |value: &str| decimal128(Number::from_str(value).unwrap()).unwrap();
+
+            assert_eq!(units("37000.1"), 37_000_100_000_000_000_000_000);
+            assert_eq!(units("-0.00000001"), -10_000_000_000);
+            assert_eq!(units("0.0000000000000000015"), 2);
+            assert!(decimal128(Number::MAX).is_err());
+        }
+    }
+}
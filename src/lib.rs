@@ -1,5 +1,7 @@
 mod exchanges;
 pub use exchanges::*;
 
+pub mod analytics;
 pub mod clients;
+pub mod export;
 pub mod tracing;
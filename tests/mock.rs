@@ -0,0 +1,537 @@
+use std::{fmt::Debug, time::Duration};
+
+use cex_exchanges::{
+    clients::{
+        mock::{MockExchangeServer, MockFixtures},
+        rest_api::{ExchangeApi, RestApiError},
+        ws::{WsErrorKind, WsStream, WsStreamConfig}
+    },
+    normalized::{
+        registry::{AssetAliases, InstrumentEvent, InstrumentPoller, InstrumentRegistry},
+        types::{CanonicalPair, InstrumentFilter, NormalizedTrade, NumberExt, RawTradingPair},
+        ws::{
+            ChannelFeedConfig, ExchangeFeedConfig, NormalizedExchangeBuilder, NormalizedWsChannelKinds, NormalizedWsDataTypes, WsFeedConfig,
+            WsFeedConfigError
+        }
+    },
+    traits::{EmptyFilter, SpecificWsBuilder, SpecificWsChannel},
+    Exchange, ExchangeEnvironment
+};
+use futures::{Stream, StreamExt};
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/mock");
+
+async fn mock_server() -> MockExchangeServer {
+    MockExchangeServer::start(MockFixtures::from_dir(FIXTURES_DIR).unwrap())
+        .await
+        .unwrap()
+}
+
+fn trading_pairs() -> Vec<RawTradingPair> {
+    vec![RawTradingPair::new_raw("BTC_USDT", '_'), RawTradingPair::new_raw("ETH_USDT", '_')]
+}
+
+/// reads trades until `count` are recieved, checking every message in between
+async fn next_trades<S>(stream: &mut S, count: usize) -> Vec<NormalizedTrade>
+where
+    S: Stream<Item = cex_exchanges::normalized::ws::CombinedWsMessage> + Unpin
+{
+    let mut trades = Vec::new();
+    while trades.len() < count {
+        let msg = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("timed out waiting for a mocked message")
+            .expect("stream ended");
+
+        assert!(msg.is_ok(), "{:?}", msg);
+        let normalized = msg.clone().normalize();
+        assert_eq!(msg, normalized);
+        assert_eq!(msg.exchange_time(), normalized.exchange_time());
+
+        match normalized {
+            NormalizedWsDataTypes::Trade(trade) => trades.push(trade),
+            NormalizedWsDataTypes::Trades(t) => trades.extend(t),
+            _ => ()
+        }
+    }
+
+    trades
+}
+
+async fn mock_stream_util<E: Exchange + Debug>(server: &MockExchangeServer, exchange: E) -> Vec<NormalizedTrade> {
+    let mut stream = WsStream::new(exchange.with_environment(server.environment()), WsStreamConfig::default());
+    stream.connect().await.unwrap();
+
+    let trades = next_trades(&mut stream, 3).await;
+    assert_eq!(server.subscriptions(E::EXCHANGE).len(), 1);
+
+    // the server pings on connect & expects a pong
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while server.pongs_received() == 0 {
+            let _ = tokio::time::timeout(Duration::from_millis(10), stream.next()).await;
+        }
+    })
+    .await
+    .expect("no pong sent to the mock server");
+
+    trades
+}
+
+fn assert_fixture_trades(trades: &[NormalizedTrade]) {
+    assert_eq!(trades.len(), 3);
+    let price_amount = |trade: &NormalizedTrade| (trade.price.to_f64(), trade.amount.to_f64());
+    assert_eq!(price_amount(&trades[0]), (37000.1, 0.5));
+    assert_eq!(price_amount(&trades[1]), (37000.2, 0.1));
+    assert_eq!(price_amount(&trades[2]), (2000.5, 1.25));
+}
+
+#[cfg(feature = "us")]
+mod coinbase_tests {
+    use cex_exchanges::{
+        coinbase::{
+            ws::{channels::CoinbaseWsChannel, CoinbaseWsBuilder},
+            Coinbase
+        },
+        CexExchange
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_matches() {
+        let server = mock_server().await;
+        let builder = CoinbaseWsBuilder::default().add_channel(CoinbaseWsChannel::new_trade(trading_pairs()).unwrap());
+
+        let trades = mock_stream_util(&server, builder.build_single()).await;
+        assert_fixture_trades(&trades);
+        assert!(trades.iter().all(|t| t.exchange == CexExchange::Coinbase));
+    }
+
+    #[tokio::test]
+    async fn test_mock_rest_api() {
+        let server = mock_server().await;
+        let exchange_api = ExchangeApi::new().with_environment(server.environment());
+
+        let currencies = exchange_api.all_currencies::<Coinbase>().await.unwrap();
+        assert_eq!(currencies.clone().take_coinbase_currencies().unwrap().len(), 4);
+        assert_eq!(currencies.clone(), currencies.normalize());
+
+        let instruments = exchange_api.all_instruments::<Coinbase>().await.unwrap();
+        let normalized = instruments.clone().normalize();
+        assert_eq!(normalized.clone().take_instruments::<EmptyFilter>(None).unwrap().len(), 3);
+        assert_eq!(instruments, normalized);
+    }
+}
+
+#[cfg(feature = "us")]
+mod okex_tests {
+    use cex_exchanges::{
+        okex::{
+            ws::{channels::OkexWsChannel, OkexWsBuilder},
+            Okex
+        },
+        CexExchange
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_trades() {
+        let server = mock_server().await;
+        let builder = OkexWsBuilder::new(None).add_channel(OkexWsChannel::new_trade(trading_pairs()).unwrap());
+
+        let trades = mock_stream_util(&server, builder.build_single()).await;
+        assert_fixture_trades(&trades);
+        assert!(trades.iter().all(|t| t.exchange == CexExchange::Okex));
+    }
+
+    #[tokio::test]
+    async fn test_mock_non_retryable_error() {
+        let login_failed = r#"{"event":"error","msg":"Login failed.","code":"60009","connId":"mock"}"#;
+        let fixtures = MockFixtures::from_dir(FIXTURES_DIR)
+            .unwrap()
+            .with_ws_frames(CexExchange::Okex, vec![login_failed.to_string()]);
+        let server = MockExchangeServer::start(fixtures).await.unwrap();
+
+        let builder = OkexWsBuilder::new(None).add_channel(OkexWsChannel::new_trade(trading_pairs()).unwrap());
+        let mut stream = WsStream::new(builder.build_single().with_environment(server.environment()), WsStreamConfig::default());
+        stream.connect().await.unwrap();
+
+        let msg = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let msg = stream.next().await.expect("stream ended");
+                if msg.error_kind().is_some() {
+                    break msg
+                }
+            }
+        })
+        .await
+        .expect("timed out waiting for the error");
+        assert_eq!(msg.error_kind(), Some(WsErrorKind::Auth));
+
+        // the stream ends after the close handshake instead of reconnecting
+        assert!(stream.next().await.is_none());
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while server.closes_received() == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("no close frame sent to the mock server");
+        assert_eq!(server.ws_connections(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mock_rest_api() {
+        let server = mock_server().await;
+        let exchange_api = ExchangeApi::new().with_environment(server.environment());
+
+        let instruments = exchange_api.all_instruments::<Okex>().await.unwrap();
+        let normalized = instruments.clone().normalize();
+        assert_eq!(normalized.clone().take_instruments::<EmptyFilter>(None).unwrap().len(), 3);
+        assert_eq!(instruments, normalized);
+
+        // the currencies come from the proxy exchange, which is also mocked
+        let currencies = exchange_api.all_currencies::<Okex>().await.unwrap();
+        let normalized = currencies.clone().normalize();
+        assert!(!normalized.clone().take_currencies::<EmptyFilter>(None).unwrap().is_empty());
+        assert_eq!(currencies, normalized);
+    }
+}
+
+#[cfg(feature = "non-us")]
+mod binance_tests {
+    use cex_exchanges::{
+        binance::{
+            ws::{channels::BinanceWsChannel, BinanceWsBuilder},
+            Binance
+        },
+        traits::FilterExt,
+        CexExchange
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_trade() {
+        let server = mock_server().await;
+        let builder = BinanceWsBuilder::default().add_channel(BinanceWsChannel::new_trade(trading_pairs()).unwrap());
+
+        let trades = mock_stream_util(&server, builder.build_single()).await;
+        assert_fixture_trades(&trades);
+        assert!(trades.iter().all(|t| t.exchange == CexExchange::Binance));
+    }
+
+    #[tokio::test]
+    async fn test_mock_single_stream() {
+        let server = mock_server().await;
+        let builder =
+            BinanceWsBuilder::default().add_channel(BinanceWsChannel::new_trade(vec![RawTradingPair::new_raw("BTC_USDT", '_')]).unwrap());
+
+        assert_fixture_trades(&mock_stream_util(&server, builder.build_single()).await);
+        assert_eq!(server.subscriptions(CexExchange::Binance), vec![serde_json::json!("btcusdt@trade")]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_multistream() {
+        let server = mock_server().await;
+        let mut stream = BinanceWsBuilder::default()
+            .add_channel(BinanceWsChannel::new_trade(trading_pairs()).unwrap())
+            .add_channel(BinanceWsChannel::new_quote(trading_pairs()).unwrap())
+            .build_many_distributed()
+            .unwrap()
+            .with_environment(server.environment())
+            .build_multistream_unconnected(WsStreamConfig::default());
+
+        assert_eq!(next_trades(&mut stream, 6).await.len(), 6);
+        assert_eq!(server.ws_connections(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mock_normalized_builder() {
+        let server = mock_server().await;
+        let mut builder = NormalizedExchangeBuilder::new().with_environment(CexExchange::Binance, server.environment());
+        builder.add_pairs_single_channel(CexExchange::Binance, NormalizedWsChannelKinds::Trades, &trading_pairs());
+
+        let mut stream = builder
+            .build_all_multistream(WsStreamConfig::default(), None)
+            .unwrap()
+            .unwrap();
+
+        assert_fixture_trades(&next_trades(&mut stream, 3).await);
+        assert_eq!(server.ws_connections(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mock_instrument_registry() {
+        let server = mock_server().await;
+        let exchange_api = ExchangeApi::new().with_environment(server.environment());
+        let registry = InstrumentRegistry::from_exchanges_with_api(&exchange_api, &[CexExchange::Binance], AssetAliases::default())
+            .await
+            .unwrap();
+        assert_eq!(registry.len(), 3);
+
+        let mut builder = NormalizedExchangeBuilder::new().with_environment(CexExchange::Binance, server.environment());
+        builder.add_pairs_single_channel(CexExchange::Binance, NormalizedWsChannelKinds::Trades, &trading_pairs());
+        let stream = builder
+            .build_all_multistream(WsStreamConfig::default(), None)
+            .unwrap()
+            .unwrap();
+
+        let pairs = registry
+            .into_stream(stream)
+            .filter_map(|msg| async move { matches!(msg.data, NormalizedWsDataTypes::Trade(_)).then_some(msg.instrument) })
+            .take(3)
+            .collect::<Vec<_>>();
+        let pairs = tokio::time::timeout(Duration::from_secs(5), pairs)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|instrument| instrument.unwrap().pair)
+            .collect::<Vec<_>>();
+        assert_eq!(pairs, vec![CanonicalPair::new("BTC", "USDT"), CanonicalPair::new("BTC", "USDT"), CanonicalPair::new("ETH", "USDT")]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_from_config() {
+        let server = mock_server().await;
+        let config = |pairs: &[&str], instrument_filters| WsFeedConfig {
+            exchanges: vec![ExchangeFeedConfig {
+                exchange: CexExchange::Binance,
+                environment: server.environment(),
+                channels: vec![ChannelFeedConfig::new(NormalizedWsChannelKinds::Trades)],
+                pairs: pairs.iter().map(ToString::to_string).collect(),
+                instrument_filters
+            }],
+            ..Default::default()
+        };
+
+        let err = NormalizedExchangeBuilder::from_config(&config(&["BTC-USDT", "DOGE-USDT"], Vec::new()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WsFeedConfigError::UnknownPairs { pairs, .. } if pairs == vec!["DOGE-USDT".to_string()]));
+
+        let err = NormalizedExchangeBuilder::from_config(&config(&[], vec![InstrumentFilter::QuoteOnly("DOGE".to_string())]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WsFeedConfigError::NoPairs(CexExchange::Binance)));
+
+        // the explicit pair is also matched by the filter
+        let builder = NormalizedExchangeBuilder::from_config(&config(&["btc_usdt"], vec![InstrumentFilter::QuoteOnly("USDT".to_string())]))
+            .await
+            .unwrap();
+        assert_eq!(builder.take_all_single_channels(CexExchange::Binance).unwrap().len(), 2);
+
+        let mut stream = builder
+            .build_all_multistream(WsStreamConfig::default(), None)
+            .unwrap()
+            .unwrap();
+        assert_fixture_trades(&next_trades(&mut stream, 3).await);
+    }
+
+    #[tokio::test]
+    async fn test_mock_from_instruments() {
+        let server = mock_server().await;
+        let new_builder = || NormalizedExchangeBuilder::new().with_environment(CexExchange::Binance, server.environment());
+        let channels = [NormalizedWsChannelKinds::Trades, NormalizedWsChannelKinds::Quotes];
+
+        let stable_quotes = InstrumentFilter::Active.and(InstrumentFilter::quote_in(vec!["USDT".to_string(), "USDC".to_string()]));
+        let builder = new_builder()
+            .add_channels_from_instruments(&[CexExchange::Binance], &channels, stable_quotes)
+            .await
+            .unwrap();
+        assert_eq!(builder.take_all_single_channels(CexExchange::Binance).unwrap().len(), 4);
+
+        let mut stream = builder
+            .build_all_multistream(WsStreamConfig::default(), None)
+            .unwrap()
+            .unwrap();
+        assert_fixture_trades(&next_trades(&mut stream, 3).await);
+
+        let none = new_builder()
+            .add_channels_from_instruments(&[CexExchange::Binance], &channels, InstrumentFilter::quote_in(vec!["DOGE".to_string()]))
+            .await
+            .unwrap();
+        assert!(none.take_all_single_channels(CexExchange::Binance).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mock_listings() {
+        let server = mock_server().await;
+        let mut poller = InstrumentPoller::new(&[CexExchange::Binance], Duration::from_secs(60)).with_base_url(&server.base_url());
+        assert!(poller.poll().await.is_empty());
+        assert!(poller.poll().await.is_empty());
+
+        // every instrument is relisted after an empty poll
+        assert_eq!(poller.update(CexExchange::Binance, Vec::new()).len(), 3);
+        let events = poller.poll_exchange(CexExchange::Binance).await.unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|event| matches!(event, InstrumentEvent::Listed(_))));
+
+        let filter = InstrumentFilter::Active.and(InstrumentFilter::quote_only("USDT".to_string()));
+        let mut stream = NormalizedExchangeBuilder::new()
+            .with_environment(CexExchange::Binance, server.environment())
+            .add_channels_from_instruments(&[CexExchange::Binance], &[NormalizedWsChannelKinds::Trades], filter.clone())
+            .await
+            .unwrap()
+            .build_refreshing_multistream(filter, Duration::from_millis(100), WsStreamConfig::default(), Some(1))
+            .unwrap();
+        assert_eq!(stream.subscribed_pairs(CexExchange::Binance).len(), 2);
+        assert_eq!(stream.stream_count(), 2);
+
+        // every connection is sent the fixture trades
+        assert_eq!(next_trades(&mut stream, 6).await.len(), 6);
+        // drives the stream until the first polls of the instruments are done
+        while tokio::time::timeout(Duration::from_millis(300), stream.next())
+            .await
+            .is_ok()
+        {}
+        assert_eq!(server.ws_connections(), 2);
+
+        // lists SOLUSDT & delists ETHUSDT
+        let exchange_info = std::fs::read_to_string(format!("{FIXTURES_DIR}/binance/rest/api/v3/exchangeInfo.json")).unwrap();
+        let mut exchange_info: serde_json::Value = serde_json::from_str(&exchange_info).unwrap();
+        let symbols = exchange_info["symbols"].as_array_mut().unwrap();
+        let mut listed = symbols[0].clone();
+        listed["symbol"] = serde_json::json!("SOLUSDT");
+        listed["baseAsset"] = serde_json::json!("SOL");
+        symbols.retain(|symbol| symbol["symbol"] != "ETHUSDT");
+        symbols.push(listed);
+        server.set_rest("/binance/api/v3/exchangeInfo", exchange_info.to_string());
+
+        // the trades of the new connection
+        assert_fixture_trades(&next_trades(&mut stream, 3).await);
+
+        // only the delisted pair's connection is replaced
+        assert_eq!(server.ws_connections(), 3);
+        let mut subscriptions = server.subscriptions(CexExchange::Binance);
+        subscriptions.sort_by_key(ToString::to_string);
+        assert_eq!(
+            subscriptions,
+            vec![serde_json::json!("btcusdt@trade"), serde_json::json!("ethusdt@trade"), serde_json::json!("solusdt@trade")]
+        );
+        assert_eq!(stream.stream_count(), 2);
+        assert_eq!(stream.subscribed_pairs(CexExchange::Binance).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mock_rest_api() {
+        let server = mock_server().await;
+        let exchange_api = ExchangeApi::new().with_environment(server.environment());
+
+        let currencies = exchange_api.all_currencies::<Binance>().await.unwrap();
+        let normalized = currencies.clone().normalize();
+        assert_eq!(normalized.clone().take_currencies::<EmptyFilter>(None).unwrap().len(), 3);
+        assert_eq!(currencies, normalized);
+
+        let instruments = exchange_api.all_instruments::<Binance>().await.unwrap();
+        let normalized = instruments.clone().normalize();
+        assert_eq!(normalized.clone().take_instruments::<EmptyFilter>(None).unwrap().len(), 3);
+        assert_eq!(instruments, normalized);
+    }
+}
+
+#[cfg(feature = "non-us")]
+mod kucoin_tests {
+    use cex_exchanges::{
+        kucoin::{
+            ws::{channels::KucoinWsChannel, KucoinWsBuilder},
+            Kucoin
+        },
+        CexExchange
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_match() {
+        let server = mock_server().await;
+        let builder = KucoinWsBuilder::default().add_channel(KucoinWsChannel::new_trade(trading_pairs()).unwrap());
+
+        let trades = mock_stream_util(&server, builder.build_single()).await;
+        assert_fixture_trades(&trades);
+        assert!(trades.iter().all(|t| t.exchange == CexExchange::Kucoin));
+    }
+
+    #[tokio::test]
+    async fn test_no_testnet() {
+        let exchange_api = ExchangeApi::new().with_environment(ExchangeEnvironment::Testnet);
+
+        let err = exchange_api.all_instruments::<Kucoin>().await.unwrap_err();
+        assert!(matches!(err, RestApiError::UnsupportedEnvironment(_)), "{err:?}");
+
+        let builder = KucoinWsBuilder::default().add_channel(KucoinWsChannel::new_trade(trading_pairs()).unwrap());
+        let err = builder
+            .build_single()
+            .with_environment(ExchangeEnvironment::Testnet)
+            .make_ws_connection()
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), WsErrorKind::Config);
+        assert!(!err.kind().is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_mock_rejects_invalid_token() {
+        let server = mock_server().await;
+        let url = format!("ws://{}/kucoin/ws?token=invalid", server.addr());
+
+        assert!(tokio_tungstenite::connect_async(url).await.is_err());
+        assert_eq!(server.ws_connections(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_mock_rest_api() {
+        let server = mock_server().await;
+        let exchange_api = ExchangeApi::new().with_environment(server.environment());
+
+        let currencies = exchange_api.all_currencies::<Kucoin>().await.unwrap();
+        let normalized = currencies.clone().normalize();
+        assert_eq!(normalized.clone().take_currencies::<EmptyFilter>(None).unwrap().len(), 3);
+        assert_eq!(currencies, normalized);
+
+        let instruments = exchange_api.all_instruments::<Kucoin>().await.unwrap();
+        let normalized = instruments.clone().normalize();
+        assert_eq!(normalized.clone().take_instruments::<EmptyFilter>(None).unwrap().len(), 3);
+        assert_eq!(instruments, normalized);
+    }
+}
+
+#[cfg(feature = "non-us")]
+mod bybit_tests {
+    use cex_exchanges::{
+        bybit::{
+            ws::{channels::BybitWsChannel, BybitWsBuilder},
+            Bybit
+        },
+        CexExchange
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_trade() {
+        let server = mock_server().await;
+        let builder = BybitWsBuilder::default().add_channel(BybitWsChannel::new_trade(trading_pairs()).unwrap());
+
+        let trades = mock_stream_util(&server, builder.build_single()).await;
+        assert_fixture_trades(&trades);
+        assert!(trades.iter().all(|t| t.exchange == CexExchange::Bybit));
+    }
+
+    #[tokio::test]
+    async fn test_mock_rest_api() {
+        let server = mock_server().await;
+        let exchange_api = ExchangeApi::new().with_environment(server.environment());
+
+        let instruments = exchange_api.all_instruments::<Bybit>().await.unwrap();
+        let normalized = instruments.normalize();
+        assert_eq!(normalized.take_instruments::<EmptyFilter>(None).unwrap().len(), 3);
+
+        // the coins are binance's currencies listed on bybit
+        let currencies = exchange_api.all_currencies::<Bybit>().await.unwrap();
+        let normalized = currencies.clone().normalize();
+        assert_eq!(normalized.clone().take_currencies::<EmptyFilter>(None).unwrap().len(), 3);
+        assert_eq!(currencies, normalized);
+    }
+}
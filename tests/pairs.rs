@@ -0,0 +1,204 @@
+//! property tests for the trading pair normalize/denormalize round-trips
+//!
+//! the pair parsers run on every ws frame, so beyond the round-trips every
+//! parser is also checked to never panic on arbitrary input
+
+use cex_exchanges::{
+    normalized::types::{NormalizedTradingPair, RawTradingPair},
+    CexExchange
+};
+use proptest::prelude::*;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+#[path = "../fuzz/src/pairs.rs"]
+mod fuzz_pairs;
+use fuzz_pairs::try_into_every_exchange;
+
+/// an uppercase symbol like 'BTC' or '1INCH'
+fn symbol() -> impl Strategy<Value = String> {
+    "[A-Z0-9]{2,8}"
+}
+
+/// extra data like '240201' in 'BTC-USD-240201'
+fn extra_data() -> impl Strategy<Value = Option<String>> {
+    proptest::option::of("[A-Z0-9]{1,8}")
+}
+
+fn delimiter() -> impl Strategy<Value = char> {
+    prop_oneof![Just('-'), Just('_'), Just('/')]
+}
+
+fn exchange() -> impl Strategy<Value = CexExchange> {
+    proptest::sample::select(vec![
+        #[cfg(feature = "us")]
+        CexExchange::Coinbase,
+        #[cfg(feature = "us")]
+        CexExchange::Okex,
+        #[cfg(feature = "non-us")]
+        CexExchange::Binance,
+        #[cfg(feature = "non-us")]
+        CexExchange::Kucoin,
+        #[cfg(feature = "non-us")]
+        CexExchange::Bybit,
+    ])
+}
+
+/// exchange pairs are deserialized from the ws messages without any checks
+fn from_json<T: DeserializeOwned>(value: Value) -> T {
+    serde_json::from_value(value).unwrap()
+}
+
+proptest! {
+    #[test]
+    fn test_split_round_trip(exchange in exchange(), base in symbol(), quote in symbol(), ed in extra_data()) {
+        let normalized = RawTradingPair::new_base_quote(&base, &quote, ed.clone()).get_normalized_pair(exchange);
+        prop_assert_eq!(normalized.base_quote(), &Some((base, quote)));
+        prop_assert_eq!(normalized.extra_data(), ed);
+
+        let round_trip = RawTradingPair::from(normalized.clone()).get_normalized_pair(exchange);
+        prop_assert_eq!(round_trip, normalized);
+    }
+
+    #[test]
+    fn test_raw_delim_round_trip(
+        exchange in exchange(),
+        base in symbol(),
+        quote in symbol(),
+        ed in extra_data(),
+        delimiter in delimiter()
+    ) {
+        let pair = match &ed {
+            Some(ed) => format!("{base}{delimiter}{quote}{delimiter}{ed}"),
+            None => format!("{base}{delimiter}{quote}")
+        };
+
+        let normalized = RawTradingPair::new_raw(&pair, delimiter).get_normalized_pair(exchange);
+        prop_assert_eq!(normalized.base_quote(), &Some((base, quote)));
+        prop_assert_eq!(normalized.delimiter(), Some(delimiter));
+        prop_assert_eq!(normalized.extra_data(), ed);
+        prop_assert_eq!(normalized.make_pair(), pair);
+    }
+
+    #[test]
+    fn test_parse(exchange in exchange(), base in symbol(), quote in symbol(), delimiter in delimiter()) {
+        let normalized = RawTradingPair::parse(&format!("{base}{delimiter}{quote}").to_lowercase()).get_normalized_pair(exchange);
+        prop_assert_eq!(normalized.base_quote(), &Some((base.clone(), quote.clone())));
+
+        let normalized = RawTradingPair::parse(&format!("{base}{quote}")).get_normalized_pair(exchange);
+        prop_assert_eq!(normalized.base_quote(), &None);
+        prop_assert_eq!(normalized.pair(), &Some(format!("{base}{quote}")));
+    }
+
+    #[test]
+    fn test_no_panic_raw(pair in ".*", delimiter in any::<char>(), exchange in exchange()) {
+        try_into_every_exchange(&RawTradingPair::new_no_delim(&pair).get_normalized_pair(exchange));
+        if delimiter != '\0' {
+            try_into_every_exchange(&RawTradingPair::new_raw(&pair, delimiter).get_normalized_pair(exchange));
+        }
+    }
+
+    #[test]
+    fn test_no_panic_normalized(
+        exchange in exchange(),
+        pair in proptest::option::of(".*"),
+        base_quote in proptest::option::of((".*", ".*")),
+        delimiter in proptest::option::of(any::<char>())
+    ) {
+        let value = json!({ "exchange": exchange, "pair": pair, "base_quote": base_quote, "delimiter": delimiter });
+        match serde_json::from_value::<NormalizedTradingPair>(value) {
+            Ok(normalized) => try_into_every_exchange(&normalized),
+            Err(_) => prop_assert!(pair.is_none() && base_quote.is_none())
+        }
+    }
+}
+
+#[cfg(feature = "us")]
+mod us {
+    use cex_exchanges::{coinbase::CoinbaseTradingPair, okex::OkexTradingPair};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_okex_round_trip(base in symbol(), quote in symbol(), ed in extra_data()) {
+            let raw = match &ed {
+                Some(ed) => format!("{base}-{quote}-{ed}"),
+                None => format!("{base}-{quote}")
+            };
+            let pair = OkexTradingPair::new_checked(&raw).unwrap();
+
+            let normalized = pair.normalize();
+            prop_assert_eq!(normalized.base_quote(), &Some((base.clone(), quote.clone())));
+            prop_assert_eq!(normalized.extra_data(), ed.clone());
+            prop_assert_eq!(OkexTradingPair::try_from(normalized).unwrap(), pair.clone());
+
+            let normalized = RawTradingPair::new_base_quote(&base, &quote, ed).get_normalized_pair(CexExchange::Okex);
+            prop_assert_eq!(OkexTradingPair::try_from(normalized).unwrap(), pair);
+        }
+
+        #[test]
+        fn test_coinbase_round_trip(base in symbol(), quote in symbol(), delimiter in delimiter()) {
+            let pair = CoinbaseTradingPair::new_checked(&format!("{base}-{quote}")).unwrap();
+
+            let normalized = pair.normalize();
+            prop_assert_eq!(normalized.base_quote(), &Some((base.clone(), quote.clone())));
+            prop_assert_eq!(CoinbaseTradingPair::try_from(normalized).unwrap(), pair.clone());
+
+            let normalized = RawTradingPair::new_raw(&format!("{base}{delimiter}{quote}"), delimiter).get_normalized_pair(CexExchange::Coinbase);
+            prop_assert_eq!(CoinbaseTradingPair::try_from(normalized).unwrap(), pair);
+        }
+
+        #[test]
+        fn test_no_panic_us(pair in ".*") {
+            try_into_every_exchange(&from_json::<CoinbaseTradingPair>(json!(pair)).normalize());
+            try_into_every_exchange(&from_json::<OkexTradingPair>(json!(pair)).normalize());
+            let _ = CoinbaseTradingPair::parse_for_bad_pair(&pair);
+            let _ = OkexTradingPair::parse_for_bad_pair(&pair);
+        }
+    }
+}
+
+#[cfg(feature = "non-us")]
+mod non_us {
+    use cex_exchanges::{binance::BinanceTradingPair, bybit::BybitTradingPair, kucoin::KucoinTradingPair};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_binance_round_trip(base in symbol(), quote in symbol()) {
+            let pair = BinanceTradingPair::new_checked(&format!("{base}{quote}")).unwrap();
+
+            prop_assert_eq!(BinanceTradingPair::try_from(pair.normalize()).unwrap(), pair.clone());
+            prop_assert_eq!(BinanceTradingPair::try_from(pair.normalize_with(&base, &quote)).unwrap(), pair);
+        }
+
+        #[test]
+        fn test_bybit_round_trip(base in symbol(), quote in symbol()) {
+            let pair = BybitTradingPair::new_checked(&format!("{base}{quote}")).unwrap();
+
+            prop_assert_eq!(BybitTradingPair::try_from(pair.normalize()).unwrap(), pair.clone());
+            prop_assert_eq!(BybitTradingPair::try_from(pair.normalize_with(&base, &quote)).unwrap(), pair);
+        }
+
+        #[test]
+        fn test_kucoin_round_trip(base in symbol(), quote in symbol(), delimiter in delimiter()) {
+            let pair = KucoinTradingPair::new_checked(&format!("{base}-{quote}")).unwrap();
+
+            let normalized = pair.normalize();
+            prop_assert_eq!(normalized.base_quote(), &Some((base.clone(), quote.clone())));
+            prop_assert_eq!(KucoinTradingPair::try_from(normalized).unwrap(), pair.clone());
+
+            let normalized = RawTradingPair::new_raw(&format!("{base}{delimiter}{quote}"), delimiter).get_normalized_pair(CexExchange::Kucoin);
+            prop_assert_eq!(KucoinTradingPair::try_from(normalized).unwrap(), pair);
+        }
+
+        #[test]
+        fn test_no_panic_non_us(pair in ".*") {
+            try_into_every_exchange(&from_json::<BinanceTradingPair>(json!(pair)).normalize());
+            try_into_every_exchange(&from_json::<BybitTradingPair>(json!(pair)).normalize());
+            try_into_every_exchange(&from_json::<KucoinTradingPair>(json!(pair)).normalize());
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! deserializes the captured ws frames in `tests/fixtures/messages` and
+//! compares the normalized output against the golden files next to them
+//!
+//! run with `UPDATE_GOLDEN=1` to rewrite the golden files after an intended
+//! change to the normalized output
+
+use std::{fs, path::PathBuf};
+
+use cex_exchanges::{
+    clients::ws::{CriticalWsMessage, WsError},
+    normalized::ws::{CombinedWsMessage, NormalizedWsDataTypes},
+    Exchange
+};
+use serde_json::Value;
+
+const MESSAGES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/messages");
+
+/// normalizes every frame of `<exchange>/<channel>.jsonl` the same way the
+/// live stream does
+fn normalize_frames<E: Exchange>(channel: &str) -> Vec<NormalizedWsDataTypes> {
+    let path = PathBuf::from(MESSAGES_DIR).join(format!("{}/{channel}.jsonl", E::EXCHANGE));
+    let frames = fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()));
+
+    frames
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|frame| match serde_json::from_str::<E::WsMessage>(frame) {
+            Ok(mut msg) => {
+                msg.make_critical(frame.to_string());
+                let msg: CombinedWsMessage = msg.into();
+                let normalized = msg.clone().normalize();
+                assert_eq!(msg, normalized, "{} {channel}: {frame}", E::EXCHANGE);
+                normalized
+            }
+            Err(e) => WsError::from(e)
+                .normalized_with_exchange(E::EXCHANGE, Some(frame.to_string()))
+                .normalize()
+        })
+        .collect()
+}
+
+/// binance's book ticker has no exchange timestamp, so the quote is stamped
+/// with the local time when it's deserialized
+fn mask_local_times<E: Exchange>(normalized: &mut Value) {
+    if E::EXCHANGE.to_string() != "binance" {
+        return
+    }
+
+    for msg in normalized.as_array_mut().into_iter().flatten() {
+        if let Some(time) = msg.pointer_mut("/Quote/orderbook_ids_time/time") {
+            *time = Value::String("<local time>".to_string());
+        }
+    }
+}
+
+/// the `decimal` feature serializes the prices & amounts as strings, the golden
+/// files have them as floats
+#[cfg(feature = "decimal")]
+fn decimals_as_floats(normalized: &mut Value) {
+    const NUMBER_KEYS: [&str; 6] = ["price", "amount", "bid_price", "bid_amount", "ask_price", "ask_amount"];
+
+    match normalized {
+        Value::Object(map) => map.iter_mut().for_each(|(key, value)| {
+            match value.as_str().and_then(|v| v.parse::<f64>().ok()) {
+                Some(float) if NUMBER_KEYS.contains(&key.as_str()) => *value = Value::from(float),
+                _ => decimals_as_floats(value)
+            }
+        }),
+        Value::Array(values) => values.iter_mut().for_each(decimals_as_floats),
+        _ => ()
+    }
+}
+
+fn assert_golden<E: Exchange>(channels: &[&str]) {
+    let mut failed = Vec::new();
+
+    for channel in channels {
+        let mut normalized = serde_json::to_value(normalize_frames::<E>(channel)).unwrap();
+        mask_local_times::<E>(&mut normalized);
+        #[cfg(feature = "decimal")]
+        decimals_as_floats(&mut normalized);
+        let normalized = serde_json::to_string_pretty(&normalized).unwrap() + "\n";
+
+        let golden_path = PathBuf::from(MESSAGES_DIR).join(format!("{}/{channel}.golden.json", E::EXCHANGE));
+        if std::env::var("UPDATE_GOLDEN").is_ok() {
+            fs::write(&golden_path, &normalized).unwrap();
+            continue
+        }
+
+        match fs::read_to_string(&golden_path) {
+            Ok(golden) if golden == normalized => (),
+            Ok(golden) => failed.push(format!("{channel}:\n--- golden\n{golden}\n--- normalized\n{normalized}")),
+            Err(e) => failed.push(format!("{channel}: failed to read {} ({e}), run with UPDATE_GOLDEN=1", golden_path.display()))
+        }
+    }
+
+    assert!(failed.is_empty(), "{} normalized output doesn't match the golden files:\n{}", E::EXCHANGE, failed.join("\n"));
+}
+
+#[cfg(feature = "us")]
+#[test]
+fn test_coinbase_golden() {
+    assert_golden::<cex_exchanges::coinbase::Coinbase>(&["matches", "ticker", "status", "subscriptions"]);
+}
+
+#[cfg(feature = "us")]
+#[test]
+fn test_okex_golden() {
+    assert_golden::<cex_exchanges::okex::Okex>(&["trades_all", "tickers"]);
+}
+
+#[cfg(feature = "non-us")]
+#[test]
+fn test_binance_golden() {
+    assert_golden::<cex_exchanges::binance::Binance>(&["trade", "book_ticker", "partial_book_depth", "diff_depth", "subscription"]);
+}
+
+#[cfg(feature = "non-us")]
+#[test]
+fn test_kucoin_golden() {
+    assert_golden::<cex_exchanges::kucoin::Kucoin>(&["match", "ticker"]);
+}
+
+#[cfg(feature = "non-us")]
+#[test]
+fn test_bybit_golden() {
+    assert_golden::<cex_exchanges::bybit::Bybit>(&["trade", "orderbook"]);
+}
@@ -59,7 +59,7 @@ pub async fn mutlistream_util<E: Exchange + Unpin + Debug + Send + 'static>(buil
 }
 
 pub async fn mutlithreaded_util<E: Exchange + Unpin + Debug + Send + 'static>(builder: MultiWsStreamBuilder<E>, iterations: usize) {
-    let mut rx = builder.spawn_multithreaded(8, WsStreamConfig::default());
+    let (mut rx, handle) = builder.spawn_multithreaded(8, WsStreamConfig::default());
     info!(target: "cex-exchanges::tests::ws", "connected stream");
 
     let mut i = 0;
@@ -80,10 +80,12 @@ pub async fn mutlithreaded_util<E: Exchange + Unpin + Debug + Send + 'static>(bu
         }
         i += 1;
     }
+
+    handle.shutdown_and_join().await.unwrap();
 }
 
 pub async fn normalized_mutlithreaded_util(builder: NormalizedExchangeBuilder, iterations: usize) {
-    let mut rx = builder
+    let (mut rx, handle) = builder
         .build_all_multithreaded(1, WsStreamConfig::default().with_max_retries(10), Some(25))
         .unwrap()
         .unwrap();
@@ -107,6 +109,8 @@ pub async fn normalized_mutlithreaded_util(builder: NormalizedExchangeBuilder, i
         }
         i += 1;
     }
+
+    handle.shutdown_and_join().await.unwrap();
 }
 
 pub fn write_json<D>(a: D, path: &str)